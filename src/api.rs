@@ -0,0 +1,247 @@
+// Library entry point for exporting discussions
+//
+// Wires authentication, the GraphQL client, fetching, and formatting together
+// so other Rust programs can export a discussion without shelling out to the CLI.
+
+use crate::auth::get_github_token_for_host;
+use crate::cli::OutputFormat;
+use crate::client::{
+    GitHubClient, HttpClient, HttpOptions, ReqwestClient, RetryPolicy, graphql_endpoint,
+};
+use crate::error::Result;
+use crate::fetch::{FetchOptions, fetch_discussion_with_options};
+use crate::models::Discussion;
+use crate::output::{
+    OutputOptions, format_discussion_json, format_discussion_ndjson, format_discussion_with_options,
+};
+use crate::template::render_template;
+
+/// Options for a complete export, mirroring the CLI flags
+#[derive(Debug, Clone, Default)]
+pub struct ExportOptions {
+    /// Output format (Markdown by default)
+    pub format: OutputFormat,
+    /// GitHub Enterprise Server host or GraphQL endpoint URL (github.com when `None`)
+    pub github_host: Option<String>,
+    /// Retry behavior for rate-limited requests
+    pub retry_policy: RetryPolicy,
+    /// Timeouts and proxy for the HTTP client
+    pub http: HttpOptions,
+    /// Options controlling how the discussion is fetched
+    pub fetch: FetchOptions,
+    /// Options controlling how the discussion is rendered
+    pub output: OutputOptions,
+}
+
+/// Export pipeline tying a GitHub client to fetch and format options
+///
+/// Construct with `with_token` for real requests, or `with_http_client` to
+/// supply a custom `HttpClient` (e.g. a recording or replaying client).
+pub struct Exporter {
+    client: GitHubClient,
+    options: ExportOptions,
+}
+
+impl Exporter {
+    /// Create an exporter that authenticates to GitHub with `token`
+    ///
+    /// Uses `options.http` to configure the HTTP client.
+    /// Returns Error if the HTTP client cannot be created.
+    pub fn with_token(token: String, options: ExportOptions) -> Result<Self> {
+        let http_client = ReqwestClient::with_options(token, &options.http)?;
+        Ok(Self::with_http_client(Box::new(http_client), options))
+    }
+
+    /// Create an exporter that sends requests through the given HTTP client
+    ///
+    /// The endpoint and retry policy are taken from `options`; `options.http`
+    /// is ignored since the HTTP client is already configured.
+    pub fn with_http_client(http_client: Box<dyn HttpClient>, options: ExportOptions) -> Self {
+        let client = GitHubClient::with_endpoint(
+            http_client,
+            graphql_endpoint(options.github_host.as_deref()),
+        )
+        .with_retry_policy(options.retry_policy);
+        Self { client, options }
+    }
+
+    /// Fetch a discussion and return it formatted as a String
+    pub fn export(&self, owner: &str, repo: &str, number: u64) -> Result<String> {
+        export_discussion_with_client(&self.client, owner, repo, number, &self.options)
+    }
+
+    /// The underlying GitHub client (e.g. to inspect rate limit usage)
+    pub fn client(&self) -> &GitHubClient {
+        &self.client
+    }
+}
+
+/// Fetch a discussion and return it formatted as a String
+///
+/// Resolves the token from `GH_TOKEN`, `GITHUB_TOKEN`, or `gh auth token`
+/// (for `options.github_host`), then runs the same fetch and format pipeline as the CLI.
+pub fn export_discussion(
+    owner: &str,
+    repo: &str,
+    number: u64,
+    options: &ExportOptions,
+) -> Result<String> {
+    let token = get_github_token_for_host(options.github_host.as_deref())?;
+    Exporter::with_token(token, options.clone())?.export(owner, repo, number)
+}
+
+/// Fetch a discussion with the given client and return it formatted as a String
+///
+/// `options.github_host`, `options.retry_policy`, and `options.http` are
+/// ignored; they are properties of the client, which the caller has already configured.
+pub fn export_discussion_with_client(
+    client: &GitHubClient,
+    owner: &str,
+    repo: &str,
+    number: u64,
+    options: &ExportOptions,
+) -> Result<String> {
+    let discussion = fetch_discussion_with_options(client, owner, repo, number, &options.fetch)?;
+    render_discussion(&discussion, owner, repo, options.format, &options.output)
+}
+
+/// Format a fetched discussion in the requested output format
+///
+/// `output_options` only apply to Markdown; JSON is always the full discussion,
+/// and NDJSON one line per post, comment, and reply.
+/// With `output_options.template`, Markdown is rendered through that template.
+pub fn render_discussion(
+    discussion: &Discussion,
+    owner: &str,
+    repo: &str,
+    format: OutputFormat,
+    output_options: &OutputOptions,
+) -> Result<String> {
+    match format {
+        OutputFormat::Markdown => match &output_options.template {
+            Some(template) => render_template(template, discussion, owner, repo, output_options),
+            None => Ok(format_discussion_with_options(
+                discussion,
+                owner,
+                repo,
+                output_options,
+            )),
+        },
+        OutputFormat::Json => format_discussion_json(discussion),
+        OutputFormat::Ndjson => format_discussion_ndjson(discussion),
+    }
+}
+
+// Canned GraphQL responses, shared with the integration tests
+#[cfg(test)]
+#[path = "../tests/common/mod.rs"]
+mod test_common;
+
+#[cfg(test)]
+mod tests {
+    use super::test_common as common;
+    use super::*;
+    use crate::client::MockHttpClient;
+
+    #[test]
+    fn test_exporter_with_mock_http_client() {
+        let mut mock_http = MockHttpClient::new();
+        mock_http
+            .expect_post()
+            .withf(|url, _body| url == "https://ghe.example.com/api/graphql")
+            .times(2)
+            .returning(|_url, body| Ok(common::canned_response(body).unwrap()));
+
+        let options = ExportOptions {
+            github_host: Some("ghe.example.com".to_string()),
+            ..Default::default()
+        };
+        let exporter = Exporter::with_http_client(Box::new(mock_http), options);
+        let markdown = exporter.export("owner", "repo", 7).unwrap();
+
+        assert_eq!(
+            markdown,
+            "# Canned discussion\n\n\
+             Discussion: owner/repo#7\n\
+             URL: https://github.com/owner/repo/discussions/7\n\n\
+             Created at: 2024-01-15T10:30:00Z\n\
+             Author: poster\n\n\
+             ---\n\
+             ## Original Post\n\n\
+             _author: poster (2024-01-15T10:30:00Z)_\n\n\
+             Original body\n\n\
+             ---\n\
+             ## Comments\n\n\n\
+             ### Comment 1\n\n\
+             _author: commenter (2024-01-15T11:00:00Z)_\n\n\
+             First comment\n\n\n\
+             #### Reply 1.1\n\n\
+             _author: replier (2024-01-15T12:00:00Z)_\n\n\
+             First reply\n\n\n\
+             ### Comment 2\n\n\
+             _author: \\<deleted\\> (2024-01-15T13:00:00Z)_\n\n\
+             Second comment\n\n"
+        );
+    }
+
+    #[test]
+    fn test_exporter_propagates_http_errors() {
+        let mut mock_http = MockHttpClient::new();
+        mock_http
+            .expect_post()
+            .times(1)
+            .returning(|_url, _body| Err(crate::error::Error::Authentication));
+
+        let exporter = Exporter::with_http_client(Box::new(mock_http), ExportOptions::default());
+        assert!(matches!(
+            exporter.export("owner", "repo", 42),
+            Err(crate::error::Error::Authentication)
+        ));
+    }
+
+    #[test]
+    fn test_exporter_with_token() {
+        assert!(Exporter::with_token("test_token".to_string(), ExportOptions::default()).is_ok());
+    }
+
+    fn make_discussion() -> Discussion {
+        Discussion {
+            id: "discussion_id".to_string(),
+            title: "Test Discussion".to_string(),
+            number: 123,
+            url: "https://github.com/owner/repo/discussions/123".to_string(),
+            body: "Body".to_string(),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_render_discussion_markdown() {
+        let rendered = render_discussion(
+            &make_discussion(),
+            "owner",
+            "repo",
+            OutputFormat::Markdown,
+            &OutputOptions::default(),
+        )
+        .unwrap();
+
+        assert!(rendered.starts_with("# Test Discussion\n"));
+        assert!(rendered.contains("Discussion: owner/repo#123"));
+    }
+
+    #[test]
+    fn test_render_discussion_json() {
+        let rendered = render_discussion(
+            &make_discussion(),
+            "owner",
+            "repo",
+            OutputFormat::Json,
+            &OutputOptions::default(),
+        )
+        .unwrap();
+
+        let value: serde_json::Value = serde_json::from_str(&rendered).unwrap();
+        assert_eq!(value["title"], "Test Discussion");
+    }
+}
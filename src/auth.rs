@@ -1,26 +1,78 @@
 //! GitHub CLI authentication module
 //!
 //! This module provides functionality to retrieve GitHub authentication tokens
-//! using the GitHub CLI (`gh`).
+//! from the `GH_TOKEN` / `GITHUB_TOKEN` environment variables or using the
+//! GitHub CLI (`gh`).
 
 use crate::command_runner::CommandRunner;
 use crate::error::{Error, Result};
-use std::io::ErrorKind;
+use std::io::{BufRead, ErrorKind, IsTerminal};
 
-/// Retrieves a GitHub authentication token by calling `gh auth token`.
+/// Environment variables checked for a token, in order of precedence
+const TOKEN_ENV_VARS: [&str; 2] = ["GH_TOKEN", "GITHUB_TOKEN"];
+
+/// Retrieves a GitHub authentication token.
+///
+/// Checks the `GH_TOKEN` and `GITHUB_TOKEN` environment variables first and
+/// falls back to calling `gh auth token` when neither is set.
 ///
-/// This is a convenience function that uses the standard command runner.
-/// For testing, use `get_github_token_with_runner` with a mock runner.
+/// This is a convenience function that uses the process environment and the
+/// standard command runner. For testing, use `get_github_token_with_env` with
+/// a fake environment lookup and a mock runner.
 ///
 /// # Returns
 ///
 /// Returns `Ok(String)` containing the GitHub token if successful.
 ///
-/// Returns `Err(Error::GitHubCliNotFound)` if the GitHub CLI is not installed.
+/// Returns `Err(Error::GitHubCliNotFound)` if no environment variable is set
+/// and the GitHub CLI is not installed.
 /// Returns `Err(Error::Authentication)` if the user is not authenticated or
 /// the token is empty.
 pub fn get_github_token() -> Result<String> {
-    get_github_token_with_runner(&crate::command_runner::StdCommandRunner)
+    get_github_token_for_host(None)
+}
+
+/// Retrieves a GitHub authentication token for a GitHub host.
+///
+/// Like `get_github_token`, but `gh auth token` is asked for the token of
+/// `github_host` (a `--github-host` value: a hostname or a GraphQL endpoint
+/// URL) instead of github.com.
+pub fn get_github_token_for_host(github_host: Option<&str>) -> Result<String> {
+    get_github_token_with_env(
+        &|name| std::env::var(name).ok(),
+        &crate::command_runner::StdCommandRunner,
+        github_host,
+    )
+}
+
+/// Retrieves a GitHub authentication token using a custom environment lookup
+/// and command runner.
+///
+/// Environment variables are checked in order (`GH_TOKEN`, then
+/// `GITHUB_TOKEN`). Values are trimmed, and a variable that is set but empty
+/// is treated as unset. If no variable yields a token, falls back to
+/// `get_github_token_with_runner`.
+///
+/// # Arguments
+///
+/// * `env` - Looks up an environment variable by name
+/// * `command_runner` - A `CommandRunner` implementation for executing commands
+/// * `github_host` - The host to ask `gh` for, as in `get_github_token_for_host`
+pub(crate) fn get_github_token_with_env(
+    env: &dyn Fn(&str) -> Option<String>,
+    command_runner: &dyn CommandRunner,
+    github_host: Option<&str>,
+) -> Result<String> {
+    for name in TOKEN_ENV_VARS {
+        if let Some(value) = env(name) {
+            let token = value.trim();
+            if !token.is_empty() {
+                return Ok(token.to_string());
+            }
+        }
+    }
+
+    get_github_token_with_runner(command_runner, github_host)
 }
 
 /// Retrieves a GitHub authentication token using a custom command runner.
@@ -30,6 +82,8 @@ pub fn get_github_token() -> Result<String> {
 /// # Arguments
 ///
 /// * `command_runner` - A `CommandRunner` implementation for executing commands
+/// * `github_host` - The host to ask `gh` for, passed as `--hostname` unless
+///   it is github.com
 ///
 /// # Returns
 ///
@@ -38,9 +92,18 @@ pub fn get_github_token() -> Result<String> {
 /// Returns `Err(Error::GitHubCliNotFound)` if the GitHub CLI is not installed.
 /// Returns `Err(Error::Authentication)` if the user is not authenticated or
 /// the token is empty.
-pub(crate) fn get_github_token_with_runner(command_runner: &dyn CommandRunner) -> Result<String> {
+pub(crate) fn get_github_token_with_runner(
+    command_runner: &dyn CommandRunner,
+    github_host: Option<&str>,
+) -> Result<String> {
     // Execute `gh auth token` command
-    let args = vec!["auth", "token"];
+    let mut args = vec!["auth", "token"];
+    if let Some(hostname) = github_host
+        .map(crate::client::host_name)
+        .filter(|hostname| !hostname.is_empty() && !hostname.eq_ignore_ascii_case("github.com"))
+    {
+        args.extend(["--hostname", hostname]);
+    }
     let output = command_runner.run("gh", &args).map_err(|err| {
         // Distinguish between "gh not found" vs other I/O errors
         if err.kind() == ErrorKind::NotFound {
@@ -66,6 +129,45 @@ pub(crate) fn get_github_token_with_runner(command_runner: &dyn CommandRunner) -
     Ok(token)
 }
 
+/// Reads a GitHub authentication token from standard input.
+///
+/// Used by `--token-stdin` so the token never appears on the command line or
+/// in the environment.
+///
+/// # Returns
+///
+/// Returns `Ok(String)` containing the token read from the first line of stdin.
+///
+/// Returns `Err(Error::InvalidArgs)` if stdin is a terminal (nothing piped)
+/// or if no token could be read.
+pub fn read_token_from_stdin() -> Result<String> {
+    let stdin = std::io::stdin();
+    if stdin.is_terminal() {
+        return Err(Error::InvalidArgs(
+            "--token-stdin requires the token to be piped via standard input".to_string(),
+        ));
+    }
+    read_token_from_reader(stdin.lock())
+}
+
+/// Reads a single line from the given reader and returns it as a token.
+///
+/// Surrounding whitespace (including the trailing newline) is trimmed.
+/// An empty line is rejected with `Error::InvalidArgs`.
+pub(crate) fn read_token_from_reader<R: BufRead>(mut reader: R) -> Result<String> {
+    let mut line = String::new();
+    reader.read_line(&mut line)?;
+
+    let token = line.trim();
+    if token.is_empty() {
+        return Err(Error::InvalidArgs(
+            "No token received on standard input".to_string(),
+        ));
+    }
+
+    Ok(token.to_string())
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -112,11 +214,34 @@ mod tests {
             .times(1)
             .returning(|_, _| Ok(mock_success_output("ghp_test_token_123")));
 
-        let result = get_github_token_with_runner(&mock);
+        let result = get_github_token_with_runner(&mock, None);
         let token = result.expect("Expected Ok(token), got Err");
         assert_eq!(token, "ghp_test_token_123");
     }
 
+    #[test]
+    fn test_get_github_token_for_host() {
+        let mut mock = MockCommandRunner::new();
+        mock.expect_run()
+            .withf(|program, args| {
+                program == "gh" && args == ["auth", "token", "--hostname", "ghe.example.com"]
+            })
+            .times(2)
+            .returning(|_, _| Ok(mock_success_output("ghe_token")));
+        mock.expect_run()
+            .withf(|program, args| program == "gh" && args == ["auth", "token"])
+            .times(1)
+            .returning(|_, _| Ok(mock_success_output("ghp_token")));
+
+        let token = get_github_token_with_runner(&mock, Some("ghe.example.com"));
+        assert_eq!(token.unwrap(), "ghe_token");
+        let token =
+            get_github_token_with_runner(&mock, Some("https://ghe.example.com/api/graphql"));
+        assert_eq!(token.unwrap(), "ghe_token");
+        let token = get_github_token_with_runner(&mock, Some("github.com"));
+        assert_eq!(token.unwrap(), "ghp_token");
+    }
+
     #[test]
     fn test_get_github_token_not_found() {
         let mut mock = MockCommandRunner::new();
@@ -127,7 +252,7 @@ mod tests {
             ))
         });
 
-        let result = get_github_token_with_runner(&mock);
+        let result = get_github_token_with_runner(&mock, None);
         assert!(matches!(result, Err(Error::GitHubCliNotFound)));
     }
 
@@ -138,7 +263,7 @@ mod tests {
             .times(1)
             .returning(|_, _| Ok(mock_failure_output()));
 
-        let result = get_github_token_with_runner(&mock);
+        let result = get_github_token_with_runner(&mock, None);
         assert!(matches!(result, Err(Error::Authentication)));
     }
 
@@ -149,7 +274,89 @@ mod tests {
             .times(1)
             .returning(|_, _| Ok(mock_success_output("")));
 
-        let result = get_github_token_with_runner(&mock);
+        let result = get_github_token_with_runner(&mock, None);
         assert!(matches!(result, Err(Error::Authentication)));
     }
+
+    /// Helper to build an environment lookup from a list of (name, value) pairs
+    fn fake_env(vars: &[(&str, &str)]) -> impl Fn(&str) -> Option<String> {
+        let vars: Vec<(String, String)> = vars
+            .iter()
+            .map(|(k, v)| (k.to_string(), v.to_string()))
+            .collect();
+        move |name| vars.iter().find(|(k, _)| k == name).map(|(_, v)| v.clone())
+    }
+
+    #[test]
+    fn test_get_github_token_from_gh_token_env() {
+        // No expectations: the runner must not be called
+        let mock = MockCommandRunner::new();
+        let env = fake_env(&[("GH_TOKEN", "  gh_env_token\n")]);
+
+        let result = get_github_token_with_env(&env, &mock, None);
+        assert_eq!(result.unwrap(), "gh_env_token");
+    }
+
+    #[test]
+    fn test_get_github_token_from_github_token_env() {
+        let mock = MockCommandRunner::new();
+        let env = fake_env(&[("GITHUB_TOKEN", "github_env_token")]);
+
+        let result = get_github_token_with_env(&env, &mock, None);
+        assert_eq!(result.unwrap(), "github_env_token");
+    }
+
+    #[test]
+    fn test_get_github_token_gh_token_takes_precedence() {
+        let mock = MockCommandRunner::new();
+        let env = fake_env(&[
+            ("GH_TOKEN", "gh_env_token"),
+            ("GITHUB_TOKEN", "github_env_token"),
+        ]);
+
+        let result = get_github_token_with_env(&env, &mock, None);
+        assert_eq!(result.unwrap(), "gh_env_token");
+    }
+
+    #[test]
+    fn test_get_github_token_empty_env_is_skipped() {
+        let mock = MockCommandRunner::new();
+        let env = fake_env(&[("GH_TOKEN", "   "), ("GITHUB_TOKEN", "github_env_token")]);
+
+        let result = get_github_token_with_env(&env, &mock, None);
+        assert_eq!(result.unwrap(), "github_env_token");
+    }
+
+    #[test]
+    fn test_get_github_token_falls_back_to_gh() {
+        let mut mock = MockCommandRunner::new();
+        mock.expect_run()
+            .times(1)
+            .returning(|_, _| Ok(mock_success_output("ghp_cli_token")));
+        let env = fake_env(&[("GH_TOKEN", "")]);
+
+        let result = get_github_token_with_env(&env, &mock, None);
+        assert_eq!(result.unwrap(), "ghp_cli_token");
+    }
+
+    #[test]
+    fn test_read_token_from_reader() {
+        let reader = std::io::Cursor::new("ghp_stdin_token\n");
+        let token = read_token_from_reader(reader).unwrap();
+        assert_eq!(token, "ghp_stdin_token");
+    }
+
+    #[test]
+    fn test_read_token_from_reader_reads_first_line_only() {
+        let reader = std::io::Cursor::new("  ghp_first  \nghp_second\n");
+        let token = read_token_from_reader(reader).unwrap();
+        assert_eq!(token, "ghp_first");
+    }
+
+    #[test]
+    fn test_read_token_from_reader_empty_input() {
+        let reader = std::io::Cursor::new("");
+        let result = read_token_from_reader(reader);
+        assert!(matches!(result, Err(Error::InvalidArgs(_))));
+    }
 }
@@ -1,9 +1,16 @@
 use std::borrow::Cow;
 
-use clap::Parser;
+use clap::{ArgAction, CommandFactory, Parser, ValueEnum};
+use clap_complete::Shell;
+use log::LevelFilter;
+use serde::Deserialize;
 
+use crate::api::ExportOptions;
+use crate::client::{HttpOptions, RetryPolicy, Timeouts};
 use crate::command_runner::CommandRunner;
 use crate::error::{Error, Result};
+use crate::fetch::FetchOptions;
+use crate::output::{HeadingEscape, OutputOptions};
 
 /// Custom validator to ensure discussion number is positive (>= 1)
 fn validate_positive_number(s: &str) -> std::result::Result<u64, String> {
@@ -13,14 +20,269 @@ fn validate_positive_number(s: &str) -> std::result::Result<u64, String> {
     }
 }
 
+/// Parse a discussion URL into host, owner, repository name, and number
+///
+/// Accepts `https://<host>/<owner>/<repo>/discussions/<number>`, optionally
+/// with a trailing slash, query string, or fragment (e.g. a
+/// `#discussioncomment-<id>` link). Any host is accepted; a host other than
+/// github.com is queried as GitHub Enterprise Server (see
+/// `CliArgs::api_host`).
+pub fn parse_discussion_url(url: &str) -> Result<(String, String, String, u64)> {
+    let invalid = || {
+        Error::InvalidArgs(format!(
+            "Invalid discussion URL '{}'. Expected https://github.com/OWNER/REPO/discussions/NUMBER",
+            url
+        ))
+    };
+
+    let rest = url
+        .strip_prefix("https://")
+        .or_else(|| url.strip_prefix("http://"))
+        .ok_or_else(invalid)?;
+    let rest = rest.split(['?', '#']).next().unwrap_or_default();
+    let (host, path) = rest.split_once('/').ok_or_else(invalid)?;
+    if host.is_empty() {
+        return Err(invalid());
+    }
+
+    match path
+        .strip_suffix('/')
+        .unwrap_or(path)
+        .split('/')
+        .collect::<Vec<_>>()[..]
+    {
+        [owner, repo, "discussions", number] if !owner.is_empty() && !repo.is_empty() => {
+            let number = validate_positive_number(number).map_err(|_| invalid())?;
+            Ok((
+                host.to_string(),
+                owner.to_string(),
+                repo.to_string(),
+                number,
+            ))
+        }
+        _ => Err(invalid()),
+    }
+}
+
+/// The positional discussion argument: a number or a full discussion URL
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum DiscussionArg {
+    /// Discussion number in the repository given by `--repo` or auto-detected
+    Number(u64),
+    /// Discussion URL, which also identifies the host and repository
+    Url {
+        host: String,
+        owner: String,
+        repo: String,
+        number: u64,
+    },
+}
+
+/// Custom parser for the positional argument (a number or a discussion URL)
+fn parse_discussion_arg(s: &str) -> std::result::Result<DiscussionArg, String> {
+    if s.contains("://") {
+        let (host, owner, repo, number) = parse_discussion_url(s).map_err(|e| e.to_string())?;
+        Ok(DiscussionArg::Url {
+            host,
+            owner,
+            repo,
+            number,
+        })
+    } else {
+        validate_positive_number(s).map(DiscussionArg::Number)
+    }
+}
+
+/// Custom parser for IANA timezone names such as `Asia/Tokyo`
+pub(crate) fn parse_timezone(s: &str) -> std::result::Result<chrono_tz::Tz, String> {
+    s.parse::<chrono_tz::Tz>().map_err(|_| {
+        format!(
+            "Unknown timezone '{}'. Use an IANA timezone name such as Asia/Tokyo or UTC.",
+            s
+        )
+    })
+}
+
+/// Custom validator for strftime-style date format strings
+///
+/// Rejects patterns with unknown or incomplete specifiers up front, so an
+/// invalid pattern is reported before anything is fetched or written.
+pub(crate) fn parse_date_format(s: &str) -> std::result::Result<String, String> {
+    chrono::format::StrftimeItems::new(s)
+        .parse()
+        .map(|_| s.to_string())
+        .map_err(|_| {
+            format!(
+                "Invalid date format '{}'. Use a strftime-style pattern such as %Y-%m-%d.",
+                s
+            )
+        })
+}
+
+/// Custom parser for `--heading-escape`: `never`, `all`, or `level>=N` (N in 1..=6)
+pub(crate) fn parse_heading_escape(s: &str) -> std::result::Result<HeadingEscape, String> {
+    let level = |n: &str| {
+        n.trim()
+            .parse::<usize>()
+            .ok()
+            .filter(|n| (1..=6).contains(n))
+    };
+    match s {
+        "never" => Ok(HeadingEscape::Never),
+        "all" => Ok(HeadingEscape::All),
+        _ => s
+            .strip_prefix("level>=")
+            .and_then(level)
+            .map(HeadingEscape::MinLevel)
+            .ok_or_else(|| {
+                format!(
+                    "Invalid heading escape '{}'. Use never, all, or level>=N with N from 1 to 6.",
+                    s
+                )
+            }),
+    }
+}
+
+/// Compose the output file path from an optional output directory
+///
+/// A relative `file` is placed inside `output_dir`; without a directory
+/// `file` is returned unchanged.
+fn compose_output_path(output_dir: Option<&str>, file: &str) -> String {
+    match output_dir {
+        Some(dir) => std::path::Path::new(dir)
+            .join(file)
+            .to_string_lossy()
+            .into_owned(),
+        None => file.to_string(),
+    }
+}
+
+/// Parse an RFC 3339 timestamp or a `YYYY-MM-DD` date (interpreted in UTC)
+///
+/// A date-only value maps to the start of the day, or to its last instant
+/// when `end_of_day` is set, so that date-only ranges are inclusive.
+fn parse_date_bound(
+    s: &str,
+    end_of_day: bool,
+) -> std::result::Result<chrono::DateTime<chrono::Utc>, String> {
+    if let Ok(timestamp) = chrono::DateTime::parse_from_rfc3339(s) {
+        return Ok(timestamp.with_timezone(&chrono::Utc));
+    }
+    let date = chrono::NaiveDate::parse_from_str(s, "%Y-%m-%d").map_err(|_| {
+        format!(
+            "Invalid date '{}'. Use YYYY-MM-DD or an RFC 3339 timestamp such as 2024-01-15T10:30:00Z.",
+            s
+        )
+    })?;
+    let time = if end_of_day {
+        chrono::NaiveTime::from_hms_nano_opt(23, 59, 59, 999_999_999)
+    } else {
+        chrono::NaiveTime::from_hms_opt(0, 0, 0)
+    };
+    Ok(date.and_time(time.expect("valid time of day")).and_utc())
+}
+
+/// Custom parser for `--since` (date-only values start at 00:00:00 UTC)
+fn parse_since(s: &str) -> std::result::Result<chrono::DateTime<chrono::Utc>, String> {
+    parse_date_bound(s, false)
+}
+
+/// Custom parser for `--until` (date-only values include the whole day)
+fn parse_until(s: &str) -> std::result::Result<chrono::DateTime<chrono::Utc>, String> {
+    parse_date_bound(s, true)
+}
+
+/// Map the number of `-v` flags to a log level filter
+///
+/// No flag keeps logging off; `-v` enables debug messages (one per GraphQL
+/// request) and `-vv` or more enables trace messages.
+pub fn log_level_filter(verbosity: u8) -> LevelFilter {
+    match verbosity {
+        0 => LevelFilter::Off,
+        1 => LevelFilter::Debug,
+        _ => LevelFilter::Trace,
+    }
+}
+
+/// Output format for the exported discussion
+#[derive(ValueEnum, Clone, Copy, Debug, Default, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum OutputFormat {
+    /// Lossless Markdown archive
+    #[default]
+    Markdown,
+    /// Pretty-printed JSON of the fetched discussion
+    Json,
+    /// Newline-delimited JSON: the post, then one line per comment and reply
+    Ndjson,
+}
+
+/// Which representation of post, comment, and reply bodies is rendered
+#[derive(ValueEnum, Clone, Copy, Debug, Default, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum BodyFormat {
+    /// The Markdown source as written
+    #[default]
+    Markdown,
+    /// GitHub's rendered HTML (`bodyHTML`)
+    Html,
+}
+
+impl OutputFormat {
+    /// File extension used for the default output path
+    pub fn extension(self) -> &'static str {
+        match self {
+            OutputFormat::Markdown => "md",
+            OutputFormat::Json => "json",
+            OutputFormat::Ndjson => "ndjson",
+        }
+    }
+
+    /// The `--format` flag selecting this format, for error messages
+    pub fn flag(self) -> &'static str {
+        match self {
+            OutputFormat::Markdown => "--format markdown",
+            OutputFormat::Json => "--format json",
+            OutputFormat::Ndjson => "--format ndjson",
+        }
+    }
+}
+
+/// Write a completion script for `shell` to `out`
+///
+/// Returns Error if writing fails (e.g. a closed pipe).
+pub fn write_completion(shell: Shell, out: &mut dyn std::io::Write) -> Result<()> {
+    let mut command = CliArgs::command();
+    let name = command.get_name().to_string();
+    // Render into a buffer first: `generate` panics on write errors
+    let mut script = Vec::new();
+    clap_complete::generate(shell, &mut command, name, &mut script);
+    out.write_all(&script)?;
+    Ok(())
+}
+
+/// Write a roff man page for the command to `out`
+///
+/// Returns Error if writing fails (e.g. a closed pipe).
+pub fn write_man_page(out: &mut dyn std::io::Write) -> Result<()> {
+    clap_mangen::Man::new(CliArgs::command()).render(out)?;
+    Ok(())
+}
+
 /// Command-line arguments for GitHub Discussion Export
-#[derive(Parser, Debug)]
+#[derive(Parser, Debug, Clone)]
 #[command(name = "gh-discussion-export")]
 #[command(about = "Export GitHub Discussion to Markdown", version = env!("CARGO_PKG_VERSION"))]
 pub struct CliArgs {
-    /// Discussion number
-    #[arg(value_name = "NUMBER", help = "Discussion number", value_parser = validate_positive_number)]
-    pub number: u64,
+    /// Discussion number, or a full discussion URL
+    // Always present unless `--generate-completion` or `--generate-man` is given
+    #[arg(
+        value_name = "NUMBER|URL",
+        help = "Discussion number, or a discussion URL such as https://github.com/OWNER/REPO/discussions/NUMBER",
+        value_parser = parse_discussion_arg,
+        required_unless_present_any = ["generate_completion", "generate_man"]
+    )]
+    pub discussion: Option<DiscussionArg>,
 
     /// GitHub repository in OWNER/REPO format (auto-detected from Git repository if omitted)
     #[arg(
@@ -30,183 +292,1892 @@ pub struct CliArgs {
     )]
     pub repo: Option<String>,
 
-    /// Output file path (default: <number>-discussion.md)
+    /// Git remote whose URL identifies the repository (instead of auto-detection)
+    #[arg(
+        long,
+        value_name = "NAME",
+        help = "Take the repository from the URL of the given git remote (e.g. upstream)"
+    )]
+    pub repo_from_remote: Option<String>,
+
+    /// Output file path (default: <number>-discussion.md, or .json / .ndjson with --format json / ndjson)
     #[arg(
         short = 'o',
         long,
         value_name = "PATH",
-        help = "Output file path (default: <number>-discussion.md)"
+        help = "Output file path (default: <number>-discussion.md, or .json / .ndjson with --format json / ndjson)"
     )]
     pub output: Option<String>,
-}
 
-impl CliArgs {
-    /// Get the output file path, using default if not specified
-    pub fn output_path(&self) -> String {
-        match &self.output {
-            Some(path) => path.clone(),
-            None => format!("{}-discussion.md", self.number),
-        }
-    }
+    /// Directory to write the output file into (created if missing)
+    #[arg(
+        long,
+        value_name = "DIR",
+        help = "Directory to write the output file into, created if missing (a relative --output is resolved against it)"
+    )]
+    pub output_dir: Option<String>,
 
-    /// Get both repository owner and name, avoiding duplicate `gh repo view` calls.
-    ///
-    /// This method should be preferred when you need both owner and name,
-    /// as it only calls `gh repo view` once instead of twice.
-    pub fn repo_components(&self) -> Result<(String, String)> {
-        let repo_str = match &self.repo {
-            Some(repo) => repo.trim().to_string(),
-            None => Self::detect_from_git_with_runner(&crate::command_runner::StdCommandRunner)?,
-        };
+    /// Output format
+    #[arg(
+        long,
+        value_enum,
+        value_name = "FORMAT",
+        default_value_t = OutputFormat::Markdown,
+        help = "Output format"
+    )]
+    pub format: OutputFormat,
 
-        // Parse OWNER/REPO format
-        let repo_without_git = repo_str.strip_suffix(".git").unwrap_or(&repo_str);
-        let parts: Vec<&str> = repo_without_git.split('/').collect();
+    /// Body representation to render
+    #[arg(
+        long,
+        value_enum,
+        value_name = "FORMAT",
+        default_value_t = BodyFormat::Markdown,
+        help = "Render bodies from their Markdown source or from GitHub's rendered HTML"
+    )]
+    pub body_format: BodyFormat,
 
-        // Validate both parts are non-empty (after trimming whitespace)
-        if parts.len() == 2 && !parts[0].trim().is_empty() && !parts[1].trim().is_empty() {
-            Ok((parts[0].trim().to_string(), parts[1].trim().to_string()))
-        } else {
-            Err(Error::InvalidArgs(
-                "Repository must be in OWNER/REPO format".to_string(),
-            ))
-        }
-    }
+    /// Read the GitHub token from standard input instead of the environment or `gh`
+    #[arg(
+        long,
+        help = "Read the GitHub token from standard input instead of GH_TOKEN/GITHUB_TOKEN or `gh auth token`"
+    )]
+    pub token_stdin: bool,
 
-    /// Get the repository owner from explicit --repo flag or auto-detect from Git
-    pub fn repo_owner(&self) -> Result<String> {
-        let (owner, _) = self.repo_components()?;
-        Ok(owner)
-    }
+    /// GitHub host for GitHub Enterprise Server (hostname or full GraphQL endpoint URL)
+    #[arg(
+        long,
+        value_name = "HOST",
+        help = "GitHub host for GitHub Enterprise Server, e.g. ghe.example.com or a full GraphQL endpoint URL (default: github.com)"
+    )]
+    pub github_host: Option<String>,
 
-    /// Get the repository name from explicit --repo flag or auto-detect from Git
-    pub fn repo_name(&self) -> Result<String> {
-        let (_, name) = self.repo_components()?;
-        Ok(name)
-    }
+    /// Emit YAML front matter instead of the plain-text header
+    #[arg(
+        long,
+        help = "Emit YAML front matter (for static site generators) instead of the plain-text header"
+    )]
+    pub front_matter: bool,
 
-    /// Detect repository from current Git directory using gh CLI with a custom command runner.
-    ///
-    /// This function is primarily used for testing with mock command runners.
-    fn detect_from_git_with_runner(command_runner: &dyn CommandRunner) -> Result<String> {
-        // Execute gh repo view command
-        let output = command_runner
-            .run("gh", &["repo", "view", "--json", "owner,name", "--jq", ".owner.login + \"/\" + .name"])
-            .map_err(|e| {
-                if e.kind() == std::io::ErrorKind::NotFound {
-                    Error::GitHubCliNotFound
-                } else {
-                    Error::InvalidArgs(format!(
-                        "Failed to execute 'gh repo view': {}. Specify --repo explicitly or ensure you're in a Git repository.",
-                        e
-                    ))
-                }
-            })?;
+    /// Maximum number of retries when the GitHub API rate limit is exceeded
+    #[arg(
+        long,
+        value_name = "N",
+        default_value_t = 3,
+        value_parser = clap::value_parser!(u32).range(0..=crate::client::MAX_RETRIES as i64),
+        help = "Maximum number of retries when the GitHub API rate limit is exceeded, 0 to 10"
+    )]
+    pub max_retries: u32,
 
-        // Check if command failed
-        if !output.status.success() {
-            let stderr = String::from_utf8_lossy(&output.stderr);
-            let stderr = stderr.trim();
-            // Build base message, handling empty stderr and trailing period
-            let base_message: Cow<str> = if stderr.is_empty() {
-                "Failed to detect repository.".into()
-            } else if stderr.ends_with('.') {
-                stderr.into()
-            } else {
-                format!("{stderr}.").into()
-            };
-            let message = format!("{} Specify --repo explicitly.", base_message);
-            return Err(Error::InvalidArgs(message));
-        }
+    /// Maximum time in seconds to wait before a single rate-limit retry
+    #[arg(
+        long,
+        value_name = "SECONDS",
+        default_value_t = 60,
+        value_parser = clap::value_parser!(u64).range(1..=crate::client::MAX_RETRY_WAIT_SECS),
+        help = "Maximum time in seconds to wait before a single rate-limit retry, 1 to 3600"
+    )]
+    pub retry_max_wait: u64,
 
-        // Parse stdout
-        let repo_str = String::from_utf8(output.stdout).map_err(|_| Error::InvalidArgs(
-            "Failed to parse repository information from 'gh repo view'. Specify --repo explicitly.".to_string()
-        ))?;
+    /// Comments or replies requested per GraphQL page
+    #[arg(
+        long,
+        value_name = "N",
+        value_parser = clap::value_parser!(u32).range(1..=100),
+        help = "Comments or replies requested per GraphQL page, 1 to 100 (default: 100)"
+    )]
+    pub page_size: Option<u32>,
 
-        let repo_str = repo_str.trim();
-        if repo_str.is_empty() {
-            return Err(Error::InvalidArgs(
-                "Could not detect repository. Specify --repo explicitly.".to_string(),
-            ));
-        }
+    /// Comments whose remaining replies are fetched concurrently
+    #[arg(
+        long,
+        value_name = "N",
+        value_parser = clap::value_parser!(u16).range(1..=crate::fetch::MAX_FETCH_PARALLEL as i64),
+        help = "Fetch the remaining replies of up to N comments concurrently, 1 to 8 (default: 1)"
+    )]
+    pub fetch_parallel: Option<u16>,
 
-        Ok(repo_str.to_string())
-    }
-}
+    /// Maximum time in seconds to establish a connection
+    #[arg(
+        long,
+        value_name = "SECONDS",
+        default_value_t = 60,
+        value_parser = clap::value_parser!(u64).range(1..),
+        help = "Maximum time in seconds to establish a connection"
+    )]
+    pub connect_timeout: u64,
 
-#[cfg(test)]
-mod tests {
-    use super::*;
-    use std::ffi::OsString;
+    /// Maximum time in seconds for a single HTTP request
+    #[arg(
+        long,
+        value_name = "SECONDS",
+        default_value_t = 300,
+        value_parser = clap::value_parser!(u64).range(1..),
+        help = "Maximum time in seconds for a single HTTP request"
+    )]
+    pub request_timeout: u64,
 
-    #[test]
-    fn test_parse_valid_positional_number() {
-        let args = vec![
-            OsString::from("gh-discussion-export"),
-            OsString::from("123"),
-        ];
-        let cli = CliArgs::try_parse_from(args).unwrap();
-        assert_eq!(cli.number, 123);
-        assert_eq!(cli.repo, None);
-        assert_eq!(cli.output, None);
+    /// Proxy URL for all requests (overrides HTTPS_PROXY/HTTP_PROXY)
+    #[arg(
+        long,
+        value_name = "URL",
+        help = "Proxy URL for all requests, e.g. http://proxy.example.com:8080 or socks5://proxy.example.com:1080 (overrides HTTPS_PROXY/HTTP_PROXY)"
+    )]
+    pub proxy: Option<String>,
+
+    /// Render authors as links to their GitHub profile
+    #[arg(
+        long,
+        help = "Render authors as Markdown links to their GitHub profile"
+    )]
+    pub author_links: bool,
+
+    /// Insert a table of contents linking to each comment
+    #[arg(long, help = "Insert a table of contents linking to each comment")]
+    pub toc: bool,
+
+    /// Timezone used to render timestamps
+    #[arg(
+        long,
+        value_name = "TZ",
+        value_parser = parse_timezone,
+        help = "IANA timezone used to render timestamps, e.g. Asia/Tokyo (default: UTC)"
+    )]
+    pub timezone: Option<chrono_tz::Tz>,
+
+    /// strftime-style pattern used to render timestamps
+    #[arg(
+        long,
+        value_name = "FMT",
+        value_parser = parse_date_format,
+        help = "strftime-style pattern used to render timestamps, e.g. %Y-%m-%d (default: RFC 3339)"
+    )]
+    pub date_format: Option<String>,
+
+    /// Rewrite links to comments in this export into local anchors
+    #[arg(
+        long,
+        help = "Rewrite links to comments and replies in this export into local #comment-N / #reply-N-M anchors"
+    )]
+    pub local_links: bool,
+
+    /// Render each post, comment, and reply body as a Markdown blockquote
+    #[arg(
+        long,
+        help = "Render each post, comment, and reply body as a Markdown blockquote"
+    )]
+    pub blockquote_bodies: bool,
+
+    /// Emit a permalink to GitHub under each comment and reply
+    #[arg(
+        long,
+        help = "Emit a [permalink] to the comment on GitHub under each comment and reply"
+    )]
+    pub permalinks: bool,
+
+    /// Emit an HTML anchor before each comment and reply heading
+    #[arg(
+        long,
+        help = "Emit <a id=\"comment-N\"> / <a id=\"reply-N-M\"> anchors before each comment and reply heading"
+    )]
+    pub anchor_ids: bool,
+
+    /// Emit a hidden marker with the database ID before each comment and reply body
+    #[arg(
+        long,
+        help = "Emit <!-- comment-db-id: N --> / <!-- reply-db-id: N --> markers before each comment and reply body"
+    )]
+    pub db_id_markers: bool,
+
+    /// Rewrite @username mentions in bodies into links to the user's profile
+    #[arg(
+        long,
+        help = "Rewrite @username mentions in bodies into [@username](https://github.com/username) links (code spans and blocks are left alone)"
+    )]
+    pub link_mentions: bool,
+
+    /// Normalize bodies to Unicode NFC form
+    #[arg(
+        long,
+        help = "Normalize post, comment, and reply bodies to Unicode NFC (composed) form"
+    )]
+    pub normalize_unicode: bool,
+
+    /// Remove HTML comments from bodies
+    #[arg(
+        long,
+        help = "Remove <!-- ... --> HTML comments from post, comment, and reply bodies (code spans and blocks are left alone)"
+    )]
+    pub strip_html_comments: bool,
+
+    /// Which headings in bodies are escaped
+    #[arg(
+        long,
+        value_name = "MODE",
+        value_parser = parse_heading_escape,
+        default_value = "all",
+        help = "Escape Markdown headings in bodies: all, never, or level>=N (only headings of level N and deeper)"
+    )]
+    pub heading_escape: HeadingEscape,
+
+    /// Omit the original post (cannot be combined with --post-only)
+    #[arg(
+        long,
+        help = "Export only the header and comments, omitting the original post"
+    )]
+    pub comments_only: bool,
+
+    /// Omit the comments (cannot be combined with --comments-only)
+    #[arg(
+        long,
+        help = "Export only the header and original post, omitting the comments"
+    )]
+    pub post_only: bool,
+
+    /// Emit a reply count line under each comment
+    #[arg(
+        long,
+        help = "Emit a \"Replies: <N>\" line under each comment that has replies"
+    )]
+    pub reply_counts: bool,
+
+    /// Append a statistics footer
+    #[arg(
+        long,
+        help = "Append a footer with comment, reply, participant, and word counts and the date span of the discussion"
+    )]
+    pub stats: bool,
+
+    /// Merge consecutive comments by the same author
+    #[arg(
+        long,
+        help = "Render consecutive comments by the same author as one block, separated by * * * lines (runs end at replies and at the accepted answer)"
+    )]
+    pub collapse_author_runs: bool,
+
+    /// Indent replies under their headings
+    #[arg(
+        long,
+        help = "Indent each reply's author line and body as a blockquote under its heading, to set replies apart from comments"
+    )]
+    pub indent_replies: bool,
+
+    /// Write the export to standard output instead of a file
+    #[arg(
+        long,
+        help = "Write the export to standard output instead of a file (cannot be combined with --output)"
+    )]
+    pub stdout: bool,
+
+    /// Overwrite the output file if it already exists
+    #[arg(long, help = "Overwrite the output file if it already exists")]
+    pub force: bool,
+
+    /// Append comments newer than the existing export instead of overwriting it
+    #[arg(
+        long,
+        help = "Append only comments newer than those in the existing output file (which must have been written with --append)"
+    )]
+    pub append: bool,
+
+    /// Write the original post to the output file and each comment to its own file
+    #[arg(
+        long,
+        help = "Write the header and original post to the output file and each comment (with its replies) to comment-N.md next to it"
+    )]
+    pub split_files: bool,
+
+    /// Render the Markdown export through a Handlebars template file
+    #[arg(
+        long,
+        value_name = "FILE",
+        help = "Render the Markdown export through a Handlebars template file instead of the built-in layout"
+    )]
+    pub template: Option<String>,
+
+    /// Only include comments created at or after this date
+    #[arg(
+        long,
+        value_name = "DATE",
+        value_parser = parse_since,
+        help = "Only include comments created at or after DATE (YYYY-MM-DD in UTC, or RFC 3339)"
+    )]
+    pub since: Option<chrono::DateTime<chrono::Utc>>,
+
+    /// Only include comments created at or before this date
+    #[arg(
+        long,
+        value_name = "DATE",
+        value_parser = parse_until,
+        help = "Only include comments created at or before DATE (YYYY-MM-DD in UTC includes the whole day, or RFC 3339)"
+    )]
+    pub until: Option<chrono::DateTime<chrono::Utc>>,
+
+    /// Drop comments and replies shorter than this many characters
+    #[arg(
+        long,
+        value_name = "N",
+        help = "Drop comments and replies whose trimmed body is shorter than N characters, such as \"+1\" (replies of a dropped comment are dropped too; the original post is always kept)"
+    )]
+    pub min_comment_length: Option<usize>,
+
+    /// Drop comments and replies by this author (repeatable)
+    #[arg(
+        long,
+        value_name = "LOGIN",
+        help = "Drop comments and replies by LOGIN, case-insensitively and with or without a trailing [bot] (repeatable; replies of a dropped comment are dropped too; the original post is always kept)"
+    )]
+    pub exclude_author: Vec<String>,
+
+    /// Drop comments and replies by bots
+    #[arg(
+        long,
+        help = "Drop comments and replies by bots such as github-actions[bot] (replies of a dropped comment are dropped too; the original post is always kept)"
+    )]
+    pub exclude_bots: bool,
+
+    /// Only keep comments and replies by this author (repeatable)
+    #[arg(
+        long,
+        value_name = "LOGIN",
+        help = "Only keep comments and replies by LOGIN, matched like --exclude-author (repeatable; --exclude-author and --exclude-bots take precedence; a comment by someone else is kept as context for its kept replies; the original post is always kept)"
+    )]
+    pub only_author: Vec<String>,
+
+    /// Only fetch the comments after this GraphQL cursor
+    #[arg(
+        long,
+        value_name = "CURSOR",
+        help = "Append only the comments after CURSOR, as printed when fetching comments for --append failed (requires --append)"
+    )]
+    pub after_comment_cursor: Option<String>,
+
+    /// Warn when fetched comment or reply counts differ from totalCount
+    #[arg(
+        long,
+        help = "Warn when the number of fetched comments or replies differs from the totalCount reported by GitHub"
+    )]
+    pub verify_counts: bool,
+
+    /// Keep going when the remaining replies of a comment cannot be fetched
+    #[arg(
+        long,
+        help = "When further replies of a comment cannot be fetched, warn and keep the replies fetched before the failure instead of failing the export"
+    )]
+    pub lenient: bool,
+
+    /// Print the GitHub API rate limit budget after fetching
+    #[arg(
+        long,
+        help = "Print the remaining GitHub API rate limit and the cost of this export to stderr"
+    )]
+    pub show_rate_limit: bool,
+
+    /// Fetch the discussion and print a summary without writing anything
+    #[arg(
+        long,
+        help = "Fetch the discussion and print a summary of what would be exported without writing any output"
+    )]
+    pub dry_run: bool,
+
+    /// Print a single machine-readable summary line after a successful export
+    #[arg(
+        long,
+        help = "Print a single machine-readable line (exported comments=N replies=N path=PATH) instead of the status message"
+    )]
+    pub porcelain: bool,
+
+    /// Log API requests to stderr (repeat for more detail)
+    #[arg(
+        short = 'v',
+        long,
+        action = ArgAction::Count,
+        help = "Log API requests to stderr; repeat (-vv) for more detail"
+    )]
+    pub verbose: u8,
+
+    /// Suppress progress and status output (errors and warnings are still printed)
+    #[arg(
+        short = 'q',
+        long,
+        help = "Suppress progress and status output (errors and warnings are still printed to stderr)"
+    )]
+    pub quiet: bool,
+
+    /// Print a shell completion script to stdout and exit
+    #[arg(long, value_name = "SHELL", hide = true)]
+    pub generate_completion: Option<Shell>,
+
+    /// Print a roff man page to stdout and exit
+    #[arg(long, hide = true)]
+    pub generate_man: bool,
+
+    // `--no-<flag>` turns off a boolean flag set in the configuration file
+    /// Turn off --exclude-bots set in the configuration file
+    #[arg(long, hide = true, overrides_with = "exclude_bots")]
+    pub no_exclude_bots: bool,
+
+    /// Turn off --verify-counts set in the configuration file
+    #[arg(long, hide = true, overrides_with = "verify_counts")]
+    pub no_verify_counts: bool,
+
+    /// Turn off --lenient set in the configuration file
+    #[arg(long, hide = true, overrides_with = "lenient")]
+    pub no_lenient: bool,
+
+    /// Turn off --front-matter set in the configuration file
+    #[arg(long, hide = true, overrides_with = "front_matter")]
+    pub no_front_matter: bool,
+
+    /// Turn off --author-links set in the configuration file
+    #[arg(long, hide = true, overrides_with = "author_links")]
+    pub no_author_links: bool,
+
+    /// Turn off --toc set in the configuration file
+    #[arg(long, hide = true, overrides_with = "toc")]
+    pub no_toc: bool,
+
+    /// Turn off --local-links set in the configuration file
+    #[arg(long, hide = true, overrides_with = "local_links")]
+    pub no_local_links: bool,
+
+    /// Turn off --blockquote-bodies set in the configuration file
+    #[arg(long, hide = true, overrides_with = "blockquote_bodies")]
+    pub no_blockquote_bodies: bool,
+
+    /// Turn off --link-mentions set in the configuration file
+    #[arg(long, hide = true, overrides_with = "link_mentions")]
+    pub no_link_mentions: bool,
+
+    /// Turn off --normalize-unicode set in the configuration file
+    #[arg(long, hide = true, overrides_with = "normalize_unicode")]
+    pub no_normalize_unicode: bool,
+
+    /// Turn off --strip-html-comments set in the configuration file
+    #[arg(long, hide = true, overrides_with = "strip_html_comments")]
+    pub no_strip_html_comments: bool,
+
+    /// Turn off --permalinks set in the configuration file
+    #[arg(long, hide = true, overrides_with = "permalinks")]
+    pub no_permalinks: bool,
+
+    /// Turn off --anchor-ids set in the configuration file
+    #[arg(long, hide = true, overrides_with = "anchor_ids")]
+    pub no_anchor_ids: bool,
+
+    /// Turn off --db-id-markers set in the configuration file
+    #[arg(long, hide = true, overrides_with = "db_id_markers")]
+    pub no_db_id_markers: bool,
+
+    /// Turn off --reply-counts set in the configuration file
+    #[arg(long, hide = true, overrides_with = "reply_counts")]
+    pub no_reply_counts: bool,
+
+    /// Turn off --stats set in the configuration file
+    #[arg(long, hide = true, overrides_with = "stats")]
+    pub no_stats: bool,
+
+    /// Turn off --collapse-author-runs set in the configuration file
+    #[arg(long, hide = true, overrides_with = "collapse_author_runs")]
+    pub no_collapse_author_runs: bool,
+
+    /// Turn off --indent-replies set in the configuration file
+    #[arg(long, hide = true, overrides_with = "indent_replies")]
+    pub no_indent_replies: bool,
+
+    /// Turn off --quiet set in the configuration file
+    #[arg(long, hide = true, overrides_with = "quiet")]
+    pub no_quiet: bool,
+}
+
+impl CliArgs {
+    /// Get the output file path, using default if not specified
+    ///
+    /// With `--output-dir`, the file name (or a relative `--output`) is
+    /// placed inside that directory.
+    pub fn output_path(&self) -> String {
+        let file = match &self.output {
+            Some(path) => path.clone(),
+            None => format!("{}-discussion.{}", self.number(), self.format.extension()),
+        };
+        compose_output_path(self.output_dir.as_deref(), &file)
+    }
+
+    /// Build the Markdown output options from the command-line flags
+    pub fn output_options(&self) -> OutputOptions {
+        OutputOptions {
+            front_matter: self.front_matter,
+            reply_counts: self.reply_counts,
+            author_links: self.author_links,
+            toc: self.toc,
+            timezone: self.timezone,
+            date_format: self.date_format.clone(),
+            local_links: self.local_links,
+            blockquote_bodies: self.blockquote_bodies,
+            link_mentions: self.link_mentions,
+            normalize_unicode: self.normalize_unicode,
+            strip_html_comments: self.strip_html_comments,
+            heading_escape: self.heading_escape,
+            permalinks: self.permalinks,
+            anchor_ids: self.anchor_ids,
+            comments_only: self.comments_only,
+            post_only: self.post_only,
+            stats: self.stats,
+            collapse_author_runs: self.collapse_author_runs,
+            indent_replies: self.indent_replies,
+            db_id_markers: self.db_id_markers || self.append,
+            // Read by the caller (see `crate::template::load_template`)
+            template: None,
+            body_format: self.body_format,
+        }
+    }
+
+    /// Whether a failed comments page can be resumed later with `--append`
+    ///
+    /// True for an export to a Markdown file in the built-in layout, which
+    /// is the kind of file `--append` extends.
+    pub fn resumable(&self) -> bool {
+        self.append
+            || !(self.stdout
+                || self.dry_run
+                || self.split_files
+                || self.post_only
+                || self.template.is_some()
+                || self.format != OutputFormat::Markdown)
+    }
+
+    /// Validate combinations of arguments that clap cannot check on its own
+    ///
+    /// Returns `Err(Error::InvalidArgs)` describing the first conflict found.
+    pub fn validate(&self) -> Result<()> {
+        if self.repo.is_some() && matches!(self.discussion, Some(DiscussionArg::Url { .. })) {
+            return Err(Error::InvalidArgs(
+                "--repo cannot be combined with a discussion URL".to_string(),
+            ));
+        }
+        if self.repo_from_remote.is_some() {
+            if self.repo.is_some() {
+                return Err(Error::InvalidArgs(
+                    "--repo cannot be combined with --repo-from-remote".to_string(),
+                ));
+            }
+            if matches!(self.discussion, Some(DiscussionArg::Url { .. })) {
+                return Err(Error::InvalidArgs(
+                    "--repo-from-remote cannot be combined with a discussion URL".to_string(),
+                ));
+            }
+        }
+        if let (Some(DiscussionArg::Url { host, .. }), Some(github_host)) =
+            (&self.discussion, &self.github_host)
+        {
+            let url_host = host.split(':').next().unwrap_or_default();
+            if !url_host.eq_ignore_ascii_case(crate::client::host_name(github_host)) {
+                return Err(Error::InvalidArgs(format!(
+                    "discussion URL host '{}' does not match --github-host {}",
+                    host, github_host
+                )));
+            }
+        }
+        if self.comments_only && self.post_only {
+            return Err(Error::InvalidArgs(
+                "--comments-only cannot be combined with --post-only".to_string(),
+            ));
+        }
+        if self.porcelain && self.stdout {
+            return Err(Error::InvalidArgs(
+                "--porcelain cannot be combined with --stdout".to_string(),
+            ));
+        }
+        if self.porcelain && self.dry_run {
+            return Err(Error::InvalidArgs(
+                "--porcelain cannot be combined with --dry-run".to_string(),
+            ));
+        }
+        if self.stdout && self.output.is_some() {
+            return Err(Error::InvalidArgs(
+                "--stdout cannot be combined with --output".to_string(),
+            ));
+        }
+        if self.stdout && self.output_dir.is_some() {
+            return Err(Error::InvalidArgs(
+                "--stdout cannot be combined with --output-dir".to_string(),
+            ));
+        }
+        if self.append {
+            let conflict = if self.stdout {
+                Some("--stdout")
+            } else if self.force {
+                Some("--force")
+            } else if self.dry_run {
+                Some("--dry-run")
+            } else if self.post_only {
+                Some("--post-only")
+            } else if self.toc {
+                // The table of contents already in the file would miss the new comments
+                Some("--toc")
+            } else if self.format != OutputFormat::Markdown {
+                Some(self.format.flag())
+            } else {
+                None
+            };
+            if let Some(conflict) = conflict {
+                return Err(Error::InvalidArgs(format!(
+                    "--append cannot be combined with {}",
+                    conflict
+                )));
+            }
+        }
+        if self.split_files {
+            let conflict = if self.stdout {
+                Some("--stdout")
+            } else if self.append {
+                Some("--append")
+            } else if self.post_only {
+                Some("--post-only")
+            } else if self.toc {
+                Some("--toc")
+            } else if self.format != OutputFormat::Markdown {
+                Some(self.format.flag())
+            } else {
+                None
+            };
+            if let Some(conflict) = conflict {
+                return Err(Error::InvalidArgs(format!(
+                    "--split-files cannot be combined with {}",
+                    conflict
+                )));
+            }
+        }
+        if self.template.is_some() {
+            // Layout flags of the built-in Markdown, which a template replaces
+            let conflict = if self.split_files {
+                Some("--split-files")
+            } else if self.append {
+                Some("--append")
+            } else if self.toc {
+                Some("--toc")
+            } else if self.front_matter {
+                Some("--front-matter")
+            } else if self.comments_only {
+                Some("--comments-only")
+            } else if self.post_only {
+                Some("--post-only")
+            } else if self.permalinks {
+                Some("--permalinks")
+            } else if self.anchor_ids {
+                Some("--anchor-ids")
+            } else if self.reply_counts {
+                Some("--reply-counts")
+            } else if self.db_id_markers {
+                Some("--db-id-markers")
+            } else if self.local_links {
+                // The rewritten links point at anchors only the built-in layout emits
+                Some("--local-links")
+            } else if self.format != OutputFormat::Markdown {
+                Some(self.format.flag())
+            } else {
+                None
+            };
+            if let Some(conflict) = conflict {
+                return Err(Error::InvalidArgs(format!(
+                    "--template cannot be combined with {}",
+                    conflict
+                )));
+            }
+        }
+        if self.stats {
+            let conflict = if self.append {
+                Some("--append")
+            } else if self.template.is_some() {
+                Some("--template")
+            } else if self.format != OutputFormat::Markdown {
+                Some(self.format.flag())
+            } else {
+                None
+            };
+            if let Some(conflict) = conflict {
+                return Err(Error::InvalidArgs(format!(
+                    "--stats cannot be combined with {}",
+                    conflict
+                )));
+            }
+        }
+        if self.after_comment_cursor.is_some() && !self.append {
+            return Err(Error::InvalidArgs(
+                "--after-comment-cursor requires --append, so the comments exported before the cursor are kept"
+                    .to_string(),
+            ));
+        }
+        if self.collapse_author_runs {
+            let conflict = if self.split_files {
+                Some("--split-files")
+            } else if self.template.is_some() {
+                Some("--template")
+            } else if self.format != OutputFormat::Markdown {
+                Some(self.format.flag())
+            } else {
+                None
+            };
+            if let Some(conflict) = conflict {
+                return Err(Error::InvalidArgs(format!(
+                    "--collapse-author-runs cannot be combined with {}",
+                    conflict
+                )));
+            }
+        }
+        if self.indent_replies {
+            let conflict = if self.template.is_some() {
+                Some("--template")
+            } else if self.format != OutputFormat::Markdown {
+                Some(self.format.flag())
+            } else {
+                None
+            };
+            if let Some(conflict) = conflict {
+                return Err(Error::InvalidArgs(format!(
+                    "--indent-replies cannot be combined with {}",
+                    conflict
+                )));
+            }
+        }
+        if self.link_mentions && self.body_format == BodyFormat::Html {
+            return Err(Error::InvalidArgs(
+                "--link-mentions cannot be combined with --body-format html, which already links mentions"
+                    .to_string(),
+            ));
+        }
+        if let (Some(since), Some(until)) = (self.since, self.until)
+            && since > until
+        {
+            return Err(Error::InvalidArgs(
+                "--since must not be later than --until".to_string(),
+            ));
+        }
+        if let (Some(output), Some(_)) = (&self.output, &self.output_dir)
+            && std::path::Path::new(output).is_absolute()
+        {
+            return Err(Error::InvalidArgs(format!(
+                "--output '{}' is an absolute path and conflicts with --output-dir",
+                output
+            )));
+        }
+        Ok(())
+    }
+
+    /// Build the fetch options from the command-line flags
+    pub fn fetch_options(&self) -> FetchOptions {
+        FetchOptions {
+            show_progress: !self.quiet,
+            since: self.since,
+            until: self.until,
+            page_size: self.page_size,
+            parallel: self.fetch_parallel.map(usize::from),
+            body_html: self.body_format == BodyFormat::Html,
+            min_comment_length: self.min_comment_length,
+            exclude_authors: self.exclude_author.clone(),
+            exclude_bots: self.exclude_bots,
+            only_authors: self.only_author.clone(),
+            after_comment_cursor: self.after_comment_cursor.clone(),
+            verify_counts: self.verify_counts,
+            lenient: self.lenient,
+        }
+    }
+
+    /// Build the library export options from the command-line flags
+    pub fn export_options(&self) -> ExportOptions {
+        ExportOptions {
+            format: self.format,
+            github_host: self.github_host.clone(),
+            retry_policy: self.retry_policy(),
+            http: self.http_options(),
+            fetch: self.fetch_options(),
+            output: self.output_options(),
+        }
+    }
+
+    /// Log level filter for the `-v/--verbose` count
+    pub fn log_level(&self) -> LevelFilter {
+        log_level_filter(self.verbose)
+    }
+
+    /// Build the rate-limit retry policy from the command-line flags
+    pub fn retry_policy(&self) -> RetryPolicy {
+        RetryPolicy {
+            max_retries: self.max_retries,
+            max_wait: std::time::Duration::from_secs(self.retry_max_wait),
+        }
+    }
+
+    /// Build the HTTP connect and request timeouts from the command-line flags
+    pub fn timeouts(&self) -> Timeouts {
+        Timeouts {
+            connect: std::time::Duration::from_secs(self.connect_timeout),
+            request: std::time::Duration::from_secs(self.request_timeout),
+        }
+    }
+
+    /// Build the HTTP client options from the command-line flags
+    pub fn http_options(&self) -> HttpOptions {
+        HttpOptions {
+            timeouts: self.timeouts(),
+            proxy: self.proxy.clone(),
+        }
+    }
+
+    /// The discussion argument; clap requires it unless generating completions
+    fn discussion(&self) -> &DiscussionArg {
+        self.discussion
+            .as_ref()
+            .expect("discussion argument is required")
+    }
+
+    /// The GitHub host to query, as accepted by `graphql_endpoint`
+    ///
+    /// `--github-host` if given, otherwise the host of a discussion URL other
+    /// than github.com. `None` means github.com.
+    pub fn api_host(&self) -> Option<&str> {
+        match (&self.github_host, &self.discussion) {
+            (Some(github_host), _) => Some(github_host),
+            (None, Some(DiscussionArg::Url { host, .. }))
+                if !host.eq_ignore_ascii_case("github.com") =>
+            {
+                Some(host)
+            }
+            _ => None,
+        }
+    }
+
+    /// The discussion number, from the positional number or URL
+    pub fn number(&self) -> u64 {
+        match *self.discussion() {
+            DiscussionArg::Number(number) | DiscussionArg::Url { number, .. } => number,
+        }
+    }
+
+    /// Get both repository owner and name, avoiding duplicate `gh repo view` calls.
+    ///
+    /// This method should be preferred when you need both owner and name,
+    /// as it only calls `gh repo view` once instead of twice. A discussion
+    /// URL takes precedence over `--repo` and auto-detection.
+    pub fn repo_components(&self) -> Result<(String, String)> {
+        if let DiscussionArg::Url { owner, repo, .. } = self.discussion() {
+            return Ok((owner.clone(), repo.clone()));
+        }
+        let runner = crate::command_runner::StdCommandRunner;
+        let repo_str = match (&self.repo, &self.repo_from_remote) {
+            (Some(repo), _) => repo.trim().to_string(),
+            (None, Some(remote)) => {
+                Self::detect_from_remote_with_runner(&runner, remote, self.github_host.as_deref())?
+            }
+            (None, None) => {
+                Self::detect_from_git_with_runner(&runner, self.github_host.as_deref())?
+            }
+        };
+
+        // Parse OWNER/REPO format
+        let repo_without_git = repo_str.strip_suffix(".git").unwrap_or(&repo_str);
+        let parts: Vec<&str> = repo_without_git.split('/').collect();
+
+        // Validate both parts are non-empty (after trimming whitespace)
+        if parts.len() == 2 && !parts[0].trim().is_empty() && !parts[1].trim().is_empty() {
+            Ok((parts[0].trim().to_string(), parts[1].trim().to_string()))
+        } else {
+            Err(Error::InvalidArgs(
+                "Repository must be in OWNER/REPO format".to_string(),
+            ))
+        }
+    }
+
+    /// Get the repository owner from explicit --repo flag or auto-detect from Git
+    pub fn repo_owner(&self) -> Result<String> {
+        let (owner, _) = self.repo_components()?;
+        Ok(owner)
+    }
+
+    /// Get the repository name from explicit --repo flag or auto-detect from Git
+    pub fn repo_name(&self) -> Result<String> {
+        let (_, name) = self.repo_components()?;
+        Ok(name)
+    }
+
+    /// Detect repository from current Git directory using gh CLI with a custom command runner.
+    ///
+    /// Asks `gh repo view` for the `nameWithOwner` of the current repository.
+    /// When `gh` is not installed, or fails (e.g. several remotes and no
+    /// default set with `gh repo set-default`), falls back to the URL of the
+    /// `origin` remote from `git remote get-url origin`, which must be on
+    /// github.com or `github_host`.
+    ///
+    /// This function is primarily used for testing with mock command runners.
+    fn detect_from_git_with_runner(
+        command_runner: &dyn CommandRunner,
+        github_host: Option<&str>,
+    ) -> Result<String> {
+        match Self::detect_with_gh(command_runner) {
+            Ok(repo) => Ok(repo),
+            Err(err @ (Error::GitHubCliNotFound | Error::InvalidArgs(_))) => {
+                Self::detect_from_origin_remote(command_runner, github_host).ok_or(err)
+            }
+            Err(err) => Err(err),
+        }
+    }
+
+    /// Detect repository with `gh repo view --json nameWithOwner`
+    fn detect_with_gh(command_runner: &dyn CommandRunner) -> Result<String> {
+        // Execute gh repo view command
+        let output = command_runner
+            .run("gh", &["repo", "view", "--json", "nameWithOwner", "--jq", ".nameWithOwner"])
+            .map_err(|e| {
+                if e.kind() == std::io::ErrorKind::NotFound {
+                    Error::GitHubCliNotFound
+                } else {
+                    Error::InvalidArgs(format!(
+                        "Failed to execute 'gh repo view': {}. Specify --repo explicitly or ensure you're in a Git repository.",
+                        e
+                    ))
+                }
+            })?;
+
+        // Check if command failed
+        if !output.status.success() {
+            let stderr = String::from_utf8_lossy(&output.stderr);
+            let stderr = stderr.trim();
+            // Build base message, handling empty stderr and trailing period
+            let base_message: Cow<str> = if stderr.is_empty() {
+                "Failed to detect repository.".into()
+            } else if stderr.ends_with('.') {
+                stderr.into()
+            } else {
+                format!("{stderr}.").into()
+            };
+            let message = format!("{} Specify --repo explicitly.", base_message);
+            return Err(Error::InvalidArgs(message));
+        }
+
+        // Parse stdout
+        let repo_str = String::from_utf8(output.stdout).map_err(|_| Error::InvalidArgs(
+            "Failed to parse repository information from 'gh repo view'. Specify --repo explicitly.".to_string()
+        ))?;
+
+        let repo_str = repo_str.trim();
+        if repo_str.is_empty() {
+            return Err(Error::InvalidArgs(
+                "Could not detect repository. Specify --repo explicitly.".to_string(),
+            ));
+        }
+
+        Ok(repo_str.to_string())
+    }
+
+    /// Detect repository from the URL of the `origin` remote
+    ///
+    /// Returns `None` if git is unavailable, there is no `origin` remote,
+    /// or its URL is not recognized.
+    fn detect_from_origin_remote(
+        command_runner: &dyn CommandRunner,
+        github_host: Option<&str>,
+    ) -> Option<String> {
+        Self::detect_from_remote_with_runner(command_runner, "origin", github_host).ok()
+    }
+
+    /// Detect repository from the URL of the named git remote
+    ///
+    /// Runs `git remote get-url <remote>` and extracts `OWNER/REPO` from
+    /// an SSH or HTTPS remote URL (see `parse_remote_url_with_host`).
+    fn detect_from_remote_with_runner(
+        command_runner: &dyn CommandRunner,
+        remote: &str,
+        github_host: Option<&str>,
+    ) -> Result<String> {
+        let output = command_runner
+            .run("git", &["remote", "get-url", remote])
+            .map_err(|e| {
+                Error::InvalidArgs(format!(
+                    "Failed to execute 'git remote get-url {}': {}. Specify --repo explicitly.",
+                    remote, e
+                ))
+            })?;
+
+        if !output.status.success() {
+            let stderr = String::from_utf8_lossy(&output.stderr);
+            let stderr = stderr.trim().trim_end_matches('.');
+            let reason = if stderr.is_empty() {
+                "not found".to_string()
+            } else {
+                stderr.to_string()
+            };
+            return Err(Error::InvalidArgs(format!(
+                "Git remote '{}' could not be read ({}). Check `git remote -v` or specify --repo explicitly.",
+                remote, reason
+            )));
+        }
+
+        let url = String::from_utf8_lossy(&output.stdout);
+        let (owner, repo) = parse_remote_url_with_host(&url, github_host)?;
+        Ok(format!("{}/{}", owner, repo))
+    }
+}
+
+/// Parse a git remote URL into repository owner and name
+///
+/// Accepts SCP-like SSH (`git@github.com:OWNER/REPO.git`), `ssh://` and
+/// `https://` remote URLs, with an optional `.git` suffix or trailing slash.
+/// Only github.com remotes are accepted; use `parse_remote_url_with_host`
+/// to also accept a GitHub Enterprise Server host.
+pub fn parse_remote_url(url: &str) -> Result<(String, String)> {
+    parse_remote_url_with_host(url, None)
+}
+
+/// Parse a git remote URL, also accepting remotes on `github_host`
+///
+/// `github_host` takes the same values as `--github-host`: a hostname or a
+/// GraphQL endpoint URL, whose host is compared with the remote's host.
+pub fn parse_remote_url_with_host(
+    url: &str,
+    github_host: Option<&str>,
+) -> Result<(String, String)> {
+    let invalid = || {
+        Error::InvalidArgs(format!(
+            "Unrecognized git remote URL '{}'. Expected git@github.com:OWNER/REPO.git or https://github.com/OWNER/REPO.git",
+            url
+        ))
+    };
+
+    let url = url.trim();
+    let (authority, path) = match url.split_once("://") {
+        Some((_, rest)) => rest.split_once('/').ok_or_else(invalid)?,
+        None => url.split_once(':').ok_or_else(invalid)?,
+    };
+    // Drop the user (`git@`) and port (`:22`)
+    let host = authority.rsplit('@').next().unwrap_or_default();
+    let host = host.split(':').next().unwrap_or_default();
+    if host.is_empty() {
+        return Err(invalid());
+    }
+    if !is_github_host(host, github_host) {
+        return Err(Error::InvalidArgs(format!(
+            "Git remote URL '{}' is not a GitHub repository (host '{}'). Use --github-host {} for GitHub Enterprise Server, or specify --repo explicitly.",
+            url, host, host
+        )));
+    }
+
+    let path = path.trim_end_matches('/');
+    let path = path.strip_suffix(".git").unwrap_or(path);
+    match path.split('/').collect::<Vec<_>>()[..] {
+        [owner, repo] if !owner.is_empty() && !repo.is_empty() => {
+            Ok((owner.to_string(), repo.to_string()))
+        }
+        _ => Err(invalid()),
+    }
+}
+
+/// Whether `host` is github.com or the host given with `--github-host`
+fn is_github_host(host: &str, github_host: Option<&str>) -> bool {
+    if host.eq_ignore_ascii_case("github.com") {
+        return true;
+    }
+    github_host
+        .is_some_and(|configured| crate::client::host_name(configured).eq_ignore_ascii_case(host))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::ffi::OsString;
+
+    #[test]
+    fn test_parse_valid_positional_number() {
+        let args = vec![
+            OsString::from("gh-discussion-export"),
+            OsString::from("123"),
+        ];
+        let cli = CliArgs::try_parse_from(args).unwrap();
+        assert_eq!(cli.number(), 123);
+        assert_eq!(cli.repo, None);
+        assert_eq!(cli.repo_from_remote, None);
+        assert_eq!(cli.page_size, None);
+        assert_eq!(cli.fetch_parallel, None);
+        assert_eq!(cli.generate_completion, None);
+        assert!(!cli.generate_man);
+        assert!(!cli.append);
+        assert!(!cli.split_files);
+        assert_eq!(cli.template, None);
+        assert_eq!(cli.output, None);
+        assert!(!cli.token_stdin);
+        assert_eq!(cli.github_host, None);
+        assert!(!cli.front_matter);
+        assert!(!cli.reply_counts);
+        assert!(!cli.author_links);
+        assert!(!cli.toc);
+        assert!(cli.timezone.is_none());
+        assert!(cli.date_format.is_none());
+        assert!(!cli.local_links);
+        assert!(!cli.blockquote_bodies);
+        assert!(!cli.permalinks);
+        assert!(!cli.anchor_ids);
+        assert!(!cli.db_id_markers);
+        assert!(!cli.link_mentions);
+        assert!(!cli.normalize_unicode);
+        assert!(!cli.strip_html_comments);
+        assert!(!cli.stats);
+        assert!(!cli.collapse_author_runs);
+        assert!(!cli.indent_replies);
+        assert_eq!(cli.min_comment_length, None);
+        assert_eq!(cli.after_comment_cursor, None);
+        assert!(!cli.verify_counts);
+        assert!(!cli.lenient);
+        assert!(cli.exclude_author.is_empty());
+        assert!(!cli.exclude_bots);
+        assert!(cli.only_author.is_empty());
+        assert_eq!(cli.heading_escape, HeadingEscape::All);
+        assert!(!cli.comments_only);
+        assert!(!cli.post_only);
+        assert_eq!(cli.format, OutputFormat::Markdown);
+        assert_eq!(cli.body_format, BodyFormat::Markdown);
+        assert!(!cli.fetch_options().body_html);
+        assert_eq!(cli.max_retries, 3);
+        assert_eq!(cli.retry_max_wait, 60);
+        assert!(!cli.quiet);
+    }
+
+    #[test]
+    fn test_parse_valid_with_repo_flag() {
+        let args = vec![
+            OsString::from("gh-discussion-export"),
+            OsString::from("456"),
+            OsString::from("--repo"),
+            OsString::from("rust-lang/rust"),
+        ];
+        let cli = CliArgs::try_parse_from(args).unwrap();
+        assert_eq!(cli.number(), 456);
+        assert_eq!(cli.repo, Some("rust-lang/rust".to_string()));
+        assert_eq!(cli.output, None);
+    }
+
+    #[test]
+    fn test_parse_valid_with_output_flag() {
+        let args = vec![
+            OsString::from("gh-discussion-export"),
+            OsString::from("789"),
+            OsString::from("--repo"),
+            OsString::from("owner/repo"),
+            OsString::from("--output"),
+            OsString::from("custom.md"),
+        ];
+        let cli = CliArgs::try_parse_from(args).unwrap();
+        assert_eq!(cli.number(), 789);
+        assert_eq!(cli.repo, Some("owner/repo".to_string()));
+        assert_eq!(cli.output, Some("custom.md".to_string()));
+    }
+
+    #[test]
+    fn test_parse_valid_with_short_output_flag() {
+        let args = vec![
+            OsString::from("gh-discussion-export"),
+            OsString::from("999"),
+            OsString::from("-o"),
+            OsString::from("output.md"),
+        ];
+        let cli = CliArgs::try_parse_from(args).unwrap();
+        assert_eq!(cli.number(), 999);
+        assert_eq!(cli.repo, None);
+        assert_eq!(cli.output, Some("output.md".to_string()));
+    }
+
+    #[test]
+    fn test_parse_valid_repo_with_git_suffix() {
+        let args = vec![
+            OsString::from("gh-discussion-export"),
+            OsString::from("111"),
+            OsString::from("--repo"),
+            OsString::from("rust-lang/rust.git"),
+        ];
+        let cli = CliArgs::try_parse_from(args).unwrap();
+        assert_eq!(cli.number(), 111);
+        assert_eq!(cli.repo, Some("rust-lang/rust.git".to_string()));
+    }
+
+    #[test]
+    fn test_parse_token_stdin_flag() {
+        let args = vec![
+            OsString::from("gh-discussion-export"),
+            OsString::from("123"),
+            OsString::from("--token-stdin"),
+        ];
+        let cli = CliArgs::try_parse_from(args).unwrap();
+        assert!(cli.token_stdin);
+    }
+
+    #[test]
+    fn test_parse_github_host_flag() {
+        let args = vec![
+            OsString::from("gh-discussion-export"),
+            OsString::from("123"),
+            OsString::from("--github-host"),
+            OsString::from("ghe.mycorp.com"),
+        ];
+        let cli = CliArgs::try_parse_from(args).unwrap();
+        assert_eq!(cli.github_host, Some("ghe.mycorp.com".to_string()));
+    }
+
+    #[test]
+    fn test_parse_front_matter_flag() {
+        let args = vec![
+            OsString::from("gh-discussion-export"),
+            OsString::from("123"),
+            OsString::from("--front-matter"),
+        ];
+        let cli = CliArgs::try_parse_from(args).unwrap();
+        assert!(cli.front_matter);
+        assert!(cli.output_options().front_matter);
+    }
+
+    #[test]
+    fn test_parse_author_links_flag() {
+        let args = vec![
+            OsString::from("gh-discussion-export"),
+            OsString::from("123"),
+            OsString::from("--author-links"),
+        ];
+        let cli = CliArgs::try_parse_from(args).unwrap();
+        assert!(cli.author_links);
+        assert!(cli.output_options().author_links);
+    }
+
+    #[test]
+    fn test_parse_toc_flag() {
+        let args = vec![
+            OsString::from("gh-discussion-export"),
+            OsString::from("123"),
+            OsString::from("--toc"),
+        ];
+        let cli = CliArgs::try_parse_from(args).unwrap();
+        assert!(cli.toc);
+        assert!(cli.output_options().toc);
+    }
+
+    #[test]
+    fn test_parse_timezone() {
+        let args = vec![
+            OsString::from("gh-discussion-export"),
+            OsString::from("123"),
+            OsString::from("--timezone"),
+            OsString::from("Asia/Tokyo"),
+        ];
+        let cli = CliArgs::try_parse_from(args).unwrap();
+        assert_eq!(cli.timezone, Some(chrono_tz::Asia::Tokyo));
+        assert_eq!(cli.output_options().timezone, Some(chrono_tz::Asia::Tokyo));
+    }
+
+    #[test]
+    fn test_parse_unknown_timezone() {
+        let args = vec![
+            OsString::from("gh-discussion-export"),
+            OsString::from("123"),
+            OsString::from("--timezone"),
+            OsString::from("Mars/Olympus_Mons"),
+        ];
+        let err = CliArgs::try_parse_from(args).unwrap_err();
+        assert_eq!(err.kind(), clap::error::ErrorKind::ValueValidation);
+        assert!(
+            err.to_string()
+                .contains("Unknown timezone 'Mars/Olympus_Mons'")
+        );
+    }
+
+    #[test]
+    fn test_parse_date_format() {
+        let args = vec![
+            OsString::from("gh-discussion-export"),
+            OsString::from("123"),
+            OsString::from("--date-format"),
+            OsString::from("%Y-%m-%d"),
+        ];
+        let cli = CliArgs::try_parse_from(args).unwrap();
+        assert_eq!(cli.date_format.as_deref(), Some("%Y-%m-%d"));
+        assert_eq!(
+            cli.output_options().date_format.as_deref(),
+            Some("%Y-%m-%d")
+        );
+    }
+
+    #[test]
+    fn test_parse_invalid_date_format() {
+        for fmt in ["%Y-%Q", "%"] {
+            let args = vec![
+                OsString::from("gh-discussion-export"),
+                OsString::from("123"),
+                OsString::from("--date-format"),
+                OsString::from(fmt),
+            ];
+            let err = CliArgs::try_parse_from(args).unwrap_err();
+            assert_eq!(err.kind(), clap::error::ErrorKind::ValueValidation);
+            assert!(err.to_string().contains("Invalid date format"));
+        }
+    }
+
+    #[test]
+    fn test_parse_local_links_flag() {
+        let args = vec![
+            OsString::from("gh-discussion-export"),
+            OsString::from("123"),
+            OsString::from("--local-links"),
+        ];
+        let cli = CliArgs::try_parse_from(args).unwrap();
+        assert!(cli.local_links);
+        assert!(cli.output_options().local_links);
+    }
+
+    #[test]
+    fn test_parse_blockquote_bodies_flag() {
+        let args = vec![
+            OsString::from("gh-discussion-export"),
+            OsString::from("123"),
+            OsString::from("--blockquote-bodies"),
+        ];
+        let cli = CliArgs::try_parse_from(args).unwrap();
+        assert!(cli.blockquote_bodies);
+        assert!(cli.output_options().blockquote_bodies);
+    }
+
+    #[test]
+    fn test_parse_permalinks_flag() {
+        let args = vec![
+            OsString::from("gh-discussion-export"),
+            OsString::from("123"),
+            OsString::from("--permalinks"),
+        ];
+        let cli = CliArgs::try_parse_from(args).unwrap();
+        assert!(cli.permalinks);
+        assert!(cli.output_options().permalinks);
+    }
+
+    #[test]
+    fn test_parse_anchor_ids_flag() {
+        let args = vec![
+            OsString::from("gh-discussion-export"),
+            OsString::from("123"),
+            OsString::from("--anchor-ids"),
+        ];
+        let cli = CliArgs::try_parse_from(args).unwrap();
+        assert!(cli.anchor_ids);
+        assert!(cli.output_options().anchor_ids);
+    }
+
+    #[test]
+    fn test_parse_reply_counts_flag() {
+        let args = vec![
+            OsString::from("gh-discussion-export"),
+            OsString::from("123"),
+            OsString::from("--reply-counts"),
+        ];
+        let cli = CliArgs::try_parse_from(args).unwrap();
+        assert!(cli.reply_counts);
+        assert!(cli.output_options().reply_counts);
+    }
+
+    #[test]
+    fn test_parse_retry_flags() {
+        let args = vec![
+            OsString::from("gh-discussion-export"),
+            OsString::from("123"),
+            OsString::from("--max-retries"),
+            OsString::from("5"),
+            OsString::from("--retry-max-wait"),
+            OsString::from("120"),
+        ];
+        let cli = CliArgs::try_parse_from(args).unwrap();
+        let policy = cli.retry_policy();
+        assert_eq!(policy.max_retries, 5);
+        assert_eq!(policy.max_wait, std::time::Duration::from_secs(120));
+
+        for invalid in [["--max-retries", "11"], ["--retry-max-wait", "0"]] {
+            let mut argv = vec!["gh-discussion-export", "123"];
+            argv.extend_from_slice(&invalid);
+            assert!(CliArgs::try_parse_from(argv).is_err());
+        }
+        assert!(
+            CliArgs::try_parse_from(["gh-discussion-export", "123", "--max-retries", "0"]).is_ok()
+        );
+    }
+
+    #[test]
+    fn test_parse_timeout_flags() {
+        let cli = CliArgs::try_parse_from(["gh-discussion-export", "123"]).unwrap();
+        assert_eq!(cli.timeouts(), Timeouts::default());
+
+        let args = vec![
+            OsString::from("gh-discussion-export"),
+            OsString::from("123"),
+            OsString::from("--connect-timeout"),
+            OsString::from("10"),
+            OsString::from("--request-timeout"),
+            OsString::from("90"),
+        ];
+        let cli = CliArgs::try_parse_from(args).unwrap();
+        let timeouts = cli.timeouts();
+        assert_eq!(timeouts.connect, std::time::Duration::from_secs(10));
+        assert_eq!(timeouts.request, std::time::Duration::from_secs(90));
+    }
+
+    #[test]
+    fn test_parse_proxy_flag() {
+        let cli = CliArgs::try_parse_from(["gh-discussion-export", "123"]).unwrap();
+        assert_eq!(cli.http_options().proxy, None);
+
+        let cli = CliArgs::try_parse_from([
+            "gh-discussion-export",
+            "123",
+            "--proxy",
+            "http://proxy.example.com:8080",
+        ])
+        .unwrap();
+        assert_eq!(
+            cli.http_options().proxy.as_deref(),
+            Some("http://proxy.example.com:8080")
+        );
+    }
+
+    #[test]
+    fn test_parse_zero_timeout_rejected() {
+        for flag in ["--connect-timeout", "--request-timeout"] {
+            let result = CliArgs::try_parse_from(["gh-discussion-export", "123", flag, "0"]);
+            assert!(result.is_err(), "{} 0 should be rejected", flag);
+        }
+    }
+
+    #[test]
+    fn test_parse_quiet_flag() {
+        for flag in ["-q", "--quiet"] {
+            let args = vec![
+                OsString::from("gh-discussion-export"),
+                OsString::from("123"),
+                OsString::from(flag),
+            ];
+            let cli = CliArgs::try_parse_from(args).unwrap();
+            assert!(cli.quiet);
+            assert!(!cli.fetch_options().show_progress);
+        }
+    }
+
+    #[test]
+    fn test_validate_stdout() {
+        let args = vec![
+            OsString::from("gh-discussion-export"),
+            OsString::from("123"),
+            OsString::from("--stdout"),
+        ];
+        let cli = CliArgs::try_parse_from(args).unwrap();
+        assert!(cli.stdout);
+        assert!(cli.validate().is_ok());
+    }
+
+    #[test]
+    fn test_parse_comments_only_and_post_only_flags() {
+        let cli =
+            CliArgs::try_parse_from(["gh-discussion-export", "123", "--comments-only"]).unwrap();
+        assert!(cli.validate().is_ok());
+        assert!(cli.output_options().comments_only);
+
+        let cli = CliArgs::try_parse_from(["gh-discussion-export", "123", "--post-only"]).unwrap();
+        assert!(cli.validate().is_ok());
+        assert!(cli.output_options().post_only);
+    }
+
+    #[test]
+    fn test_validate_comments_only_conflicts_with_post_only() {
+        let cli = CliArgs::try_parse_from([
+            "gh-discussion-export",
+            "123",
+            "--comments-only",
+            "--post-only",
+        ])
+        .unwrap();
+        match cli.validate() {
+            Err(Error::InvalidArgs(msg)) => {
+                assert!(msg.contains("--comments-only") && msg.contains("--post-only"))
+            }
+            _ => panic!("Expected Error::InvalidArgs"),
+        }
+    }
+
+    #[test]
+    fn test_parse_porcelain_flag() {
+        let cli = CliArgs::try_parse_from(["gh-discussion-export", "123", "--porcelain"]).unwrap();
+        assert!(cli.porcelain);
+        assert!(cli.validate().is_ok());
+    }
+
+    #[test]
+    fn test_validate_porcelain_conflicts() {
+        for flag in ["--stdout", "--dry-run"] {
+            let cli = CliArgs::try_parse_from(["gh-discussion-export", "123", "--porcelain", flag])
+                .unwrap();
+            match cli.validate() {
+                Err(Error::InvalidArgs(msg)) => {
+                    assert!(msg.contains("--porcelain") && msg.contains(flag))
+                }
+                _ => panic!("Expected Error::InvalidArgs for {}", flag),
+            }
+        }
+    }
+
+    #[test]
+    fn test_log_level_filter() {
+        assert_eq!(log_level_filter(0), LevelFilter::Off);
+        assert_eq!(log_level_filter(1), LevelFilter::Debug);
+        assert_eq!(log_level_filter(2), LevelFilter::Trace);
+        assert_eq!(log_level_filter(5), LevelFilter::Trace);
+    }
+
+    #[test]
+    fn test_parse_verbose_flag() {
+        let cli = CliArgs::try_parse_from(["gh-discussion-export", "123"]).unwrap();
+        assert_eq!(cli.verbose, 0);
+        assert_eq!(cli.log_level(), LevelFilter::Off);
+
+        let cli = CliArgs::try_parse_from(["gh-discussion-export", "123", "-v"]).unwrap();
+        assert_eq!(cli.log_level(), LevelFilter::Debug);
+
+        let cli = CliArgs::try_parse_from(["gh-discussion-export", "123", "-vv"]).unwrap();
+        assert_eq!(cli.verbose, 2);
+        assert_eq!(cli.log_level(), LevelFilter::Trace);
+
+        let cli =
+            CliArgs::try_parse_from(["gh-discussion-export", "123", "--verbose", "--verbose"])
+                .unwrap();
+        assert_eq!(cli.verbose, 2);
+    }
+
+    #[test]
+    fn test_validate_stdout_conflicts_with_output() {
+        let args = vec![
+            OsString::from("gh-discussion-export"),
+            OsString::from("123"),
+            OsString::from("--stdout"),
+            OsString::from("-o"),
+            OsString::from("out.md"),
+        ];
+        let cli = CliArgs::try_parse_from(args).unwrap();
+        match cli.validate() {
+            Err(Error::InvalidArgs(msg)) => assert!(msg.contains("--stdout")),
+            _ => panic!("Expected Error::InvalidArgs"),
+        }
+    }
+
+    #[test]
+    fn test_validate_absolute_output_conflicts_with_output_dir() {
+        let absolute = std::env::temp_dir().join("out.md");
+        let args = vec![
+            OsString::from("gh-discussion-export"),
+            OsString::from("123"),
+            OsString::from("--output-dir"),
+            OsString::from("exports"),
+            OsString::from("-o"),
+            absolute.into_os_string(),
+        ];
+        let cli = CliArgs::try_parse_from(args).unwrap();
+        match cli.validate() {
+            Err(Error::InvalidArgs(msg)) => assert!(msg.contains("--output-dir")),
+            _ => panic!("Expected Error::InvalidArgs"),
+        }
+    }
+
+    #[test]
+    fn test_validate_stdout_conflicts_with_output_dir() {
+        let args = vec![
+            OsString::from("gh-discussion-export"),
+            OsString::from("123"),
+            OsString::from("--stdout"),
+            OsString::from("--output-dir"),
+            OsString::from("exports"),
+        ];
+        let cli = CliArgs::try_parse_from(args).unwrap();
+        assert!(matches!(cli.validate(), Err(Error::InvalidArgs(_))));
+    }
+
+    #[test]
+    fn test_validate_defaults() {
+        let args = vec![
+            OsString::from("gh-discussion-export"),
+            OsString::from("123"),
+        ];
+        let cli = CliArgs::try_parse_from(args).unwrap();
+        assert!(!cli.stdout);
+        assert!(!cli.dry_run);
+        assert!(!cli.force);
+        assert!(cli.since.is_none());
+        assert!(cli.until.is_none());
+        assert!(!cli.show_rate_limit);
+        assert!(cli.validate().is_ok());
+    }
+
+    #[test]
+    fn test_parse_force_flag() {
+        let args = vec![
+            OsString::from("gh-discussion-export"),
+            OsString::from("123"),
+            OsString::from("--force"),
+        ];
+        let cli = CliArgs::try_parse_from(args).unwrap();
+        assert!(cli.force);
+    }
+
+    #[test]
+    fn test_parse_since_until_dates() {
+        let args = vec![
+            OsString::from("gh-discussion-export"),
+            OsString::from("123"),
+            OsString::from("--since"),
+            OsString::from("2024-01-10"),
+            OsString::from("--until"),
+            OsString::from("2024-01-20"),
+        ];
+        let cli = CliArgs::try_parse_from(args).unwrap();
+        assert!(cli.validate().is_ok());
+        assert_eq!(cli.since.unwrap().to_rfc3339(), "2024-01-10T00:00:00+00:00");
+        assert_eq!(
+            cli.until.unwrap().to_rfc3339(),
+            "2024-01-20T23:59:59.999999999+00:00"
+        );
+        assert_eq!(cli.fetch_options().since, cli.since);
+        assert_eq!(cli.fetch_options().until, cli.until);
+    }
+
+    #[test]
+    fn test_parse_since_rfc3339() {
+        let args = vec![
+            OsString::from("gh-discussion-export"),
+            OsString::from("123"),
+            OsString::from("--since"),
+            OsString::from("2024-01-10T09:00:00+09:00"),
+        ];
+        let cli = CliArgs::try_parse_from(args).unwrap();
+        assert_eq!(cli.since.unwrap().to_rfc3339(), "2024-01-10T00:00:00+00:00");
+    }
+
+    #[test]
+    fn test_parse_invalid_date() {
+        let args = vec![
+            OsString::from("gh-discussion-export"),
+            OsString::from("123"),
+            OsString::from("--until"),
+            OsString::from("yesterday"),
+        ];
+        let err = CliArgs::try_parse_from(args).unwrap_err();
+        assert_eq!(err.kind(), clap::error::ErrorKind::ValueValidation);
+        assert!(err.to_string().contains("Invalid date 'yesterday'"));
+    }
+
+    #[test]
+    fn test_validate_since_after_until() {
+        let args = vec![
+            OsString::from("gh-discussion-export"),
+            OsString::from("123"),
+            OsString::from("--since"),
+            OsString::from("2024-02-01"),
+            OsString::from("--until"),
+            OsString::from("2024-01-01"),
+        ];
+        let cli = CliArgs::try_parse_from(args).unwrap();
+        assert!(matches!(cli.validate(), Err(Error::InvalidArgs(_))));
+    }
+
+    #[test]
+    fn test_parse_show_rate_limit_flag() {
+        let args = vec![
+            OsString::from("gh-discussion-export"),
+            OsString::from("123"),
+            OsString::from("--show-rate-limit"),
+        ];
+        let cli = CliArgs::try_parse_from(args).unwrap();
+        assert!(cli.show_rate_limit);
+    }
+
+    #[test]
+    fn test_export_options() {
+        let args = vec![
+            OsString::from("gh-discussion-export"),
+            OsString::from("123"),
+            OsString::from("--format"),
+            OsString::from("json"),
+            OsString::from("--github-host"),
+            OsString::from("ghe.example.com"),
+            OsString::from("--toc"),
+            OsString::from("-q"),
+        ];
+        let cli = CliArgs::try_parse_from(args).unwrap();
+        let options = cli.export_options();
+        assert_eq!(options.format, OutputFormat::Json);
+        assert_eq!(options.github_host.as_deref(), Some("ghe.example.com"));
+        assert_eq!(options.retry_policy, cli.retry_policy());
+        assert!(!options.fetch.show_progress);
+        assert!(options.output.toc);
     }
 
     #[test]
-    fn test_parse_valid_with_repo_flag() {
+    fn test_parse_dry_run_flag() {
         let args = vec![
             OsString::from("gh-discussion-export"),
-            OsString::from("456"),
-            OsString::from("--repo"),
-            OsString::from("rust-lang/rust"),
+            OsString::from("123"),
+            OsString::from("--dry-run"),
         ];
         let cli = CliArgs::try_parse_from(args).unwrap();
-        assert_eq!(cli.number, 456);
-        assert_eq!(cli.repo, Some("rust-lang/rust".to_string()));
-        assert_eq!(cli.output, None);
+        assert!(cli.dry_run);
     }
 
     #[test]
-    fn test_parse_valid_with_output_flag() {
+    fn test_parse_discussion_url() {
+        assert_eq!(
+            parse_discussion_url("https://github.com/cli/cli/discussions/1041").unwrap(),
+            (
+                "github.com".to_string(),
+                "cli".to_string(),
+                "cli".to_string(),
+                1041
+            )
+        );
+        assert_eq!(
+            parse_discussion_url("https://github.com/cli/cli/discussions/1041/").unwrap(),
+            (
+                "github.com".to_string(),
+                "cli".to_string(),
+                "cli".to_string(),
+                1041
+            )
+        );
+        assert_eq!(
+            parse_discussion_url(
+                "https://github.com/cli/cli/discussions/1041#discussioncomment-456"
+            )
+            .unwrap(),
+            (
+                "github.com".to_string(),
+                "cli".to_string(),
+                "cli".to_string(),
+                1041
+            )
+        );
+        assert_eq!(
+            parse_discussion_url("http://ghe.example.com/org/project/discussions/7?sort=new")
+                .unwrap(),
+            (
+                "ghe.example.com".to_string(),
+                "org".to_string(),
+                "project".to_string(),
+                7
+            )
+        );
+    }
+
+    #[test]
+    fn test_api_host_from_discussion_url() {
+        let host = |argv: &[&str]| {
+            let mut args = vec!["gh-discussion-export"];
+            args.extend_from_slice(argv);
+            let cli = CliArgs::try_parse_from(args).unwrap();
+            cli.validate().map(|()| cli.api_host().map(str::to_string))
+        };
+        let ghe_url = "https://ghe.example.com/org/project/discussions/7";
+
+        assert_eq!(host(&["7"]).unwrap(), None);
+        assert_eq!(
+            host(&["https://github.com/cli/cli/discussions/1041"]).unwrap(),
+            None
+        );
+        assert_eq!(
+            host(&[ghe_url]).unwrap().as_deref(),
+            Some("ghe.example.com")
+        );
+        assert_eq!(
+            host(&[
+                ghe_url,
+                "--github-host",
+                "https://GHE.example.com/api/graphql"
+            ])
+            .unwrap()
+            .as_deref(),
+            Some("https://GHE.example.com/api/graphql")
+        );
+        for conflict in [
+            &[ghe_url, "--github-host", "other.example.com"][..],
+            &[
+                "https://github.com/cli/cli/discussions/1041",
+                "--github-host",
+                "ghe.example.com",
+            ],
+        ] {
+            match host(conflict) {
+                Err(Error::InvalidArgs(msg)) => assert!(msg.contains("--github-host")),
+                other => panic!(
+                    "Expected Error::InvalidArgs for {:?}, got {:?}",
+                    conflict, other
+                ),
+            }
+        }
+    }
+
+    #[test]
+    fn test_parse_discussion_url_malformed() {
+        for url in [
+            "github.com/cli/cli/discussions/1041",
+            "https://github.com/cli/cli/issues/1041",
+            "https://github.com/cli/cli/discussions",
+            "https://github.com/cli/cli/discussions/",
+            "https://github.com/cli/cli/discussions/0",
+            "https://github.com/cli/cli/discussions/abc",
+            "https://github.com/cli/cli/discussions/1041/extra",
+            "https://github.com/cli/discussions/1041",
+            "https://github.com//cli/discussions/1041",
+            "https:///cli/cli/discussions/1041",
+            "https://github.com",
+        ] {
+            match parse_discussion_url(url) {
+                Err(Error::InvalidArgs(msg)) => {
+                    assert!(msg.contains("Invalid discussion URL"), "{}: {}", url, msg)
+                }
+                other => panic!("{} should be rejected, got {:?}", url, other),
+            }
+        }
+    }
+
+    #[test]
+    fn test_parse_positional_url() {
         let args = vec![
             OsString::from("gh-discussion-export"),
-            OsString::from("789"),
-            OsString::from("--repo"),
-            OsString::from("owner/repo"),
-            OsString::from("--output"),
-            OsString::from("custom.md"),
+            OsString::from("https://github.com/cli/cli/discussions/1041"),
         ];
         let cli = CliArgs::try_parse_from(args).unwrap();
-        assert_eq!(cli.number, 789);
-        assert_eq!(cli.repo, Some("owner/repo".to_string()));
-        assert_eq!(cli.output, Some("custom.md".to_string()));
+        assert_eq!(cli.number(), 1041);
+        assert_eq!(
+            cli.repo_components().unwrap(),
+            ("cli".to_string(), "cli".to_string())
+        );
+        assert_eq!(cli.output_path(), "1041-discussion.md");
+        assert!(cli.validate().is_ok());
     }
 
     #[test]
-    fn test_parse_valid_with_short_output_flag() {
+    fn test_parse_malformed_positional_url() {
         let args = vec![
             OsString::from("gh-discussion-export"),
-            OsString::from("999"),
-            OsString::from("-o"),
-            OsString::from("output.md"),
+            OsString::from("https://github.com/cli/cli/pull/1041"),
         ];
-        let cli = CliArgs::try_parse_from(args).unwrap();
-        assert_eq!(cli.number, 999);
-        assert_eq!(cli.repo, None);
-        assert_eq!(cli.output, Some("output.md".to_string()));
+        let err = CliArgs::try_parse_from(args).unwrap_err();
+        assert_eq!(err.kind(), clap::error::ErrorKind::ValueValidation);
+        assert!(err.to_string().contains("Invalid discussion URL"));
     }
 
     #[test]
-    fn test_parse_valid_repo_with_git_suffix() {
+    fn test_validate_rejects_url_with_repo() {
         let args = vec![
             OsString::from("gh-discussion-export"),
-            OsString::from("111"),
+            OsString::from("https://github.com/cli/cli/discussions/1041"),
             OsString::from("--repo"),
-            OsString::from("rust-lang/rust.git"),
+            OsString::from("other/repo"),
         ];
         let cli = CliArgs::try_parse_from(args).unwrap();
-        assert_eq!(cli.number, 111);
-        assert_eq!(cli.repo, Some("rust-lang/rust.git".to_string()));
+        assert!(matches!(cli.validate(), Err(Error::InvalidArgs(_))));
     }
 
     #[test]
@@ -260,6 +2231,86 @@ mod tests {
         assert_eq!(cli.output_path(), "my-discussion.md");
     }
 
+    #[test]
+    fn test_parse_format_json() {
+        let args = vec![
+            OsString::from("gh-discussion-export"),
+            OsString::from("42"),
+            OsString::from("--format"),
+            OsString::from("json"),
+        ];
+        let cli = CliArgs::try_parse_from(args).unwrap();
+        assert_eq!(cli.format, OutputFormat::Json);
+        assert_eq!(cli.output_path(), "42-discussion.json");
+    }
+
+    #[test]
+    fn test_parse_format_ndjson() {
+        let cli =
+            CliArgs::try_parse_from(["gh-discussion-export", "42", "--format", "ndjson"]).unwrap();
+        assert_eq!(cli.format, OutputFormat::Ndjson);
+        assert_eq!(cli.output_path(), "42-discussion.ndjson");
+
+        // Markdown layout flags are rejected like with --format json
+        let cli = CliArgs::try_parse_from([
+            "gh-discussion-export",
+            "42",
+            "--format",
+            "ndjson",
+            "--stats",
+        ])
+        .unwrap();
+        assert!(matches!(
+            cli.validate(),
+            Err(Error::InvalidArgs(msg)) if msg == "--stats cannot be combined with --format ndjson"
+        ));
+    }
+
+    #[test]
+    fn test_compose_output_path() {
+        assert_eq!(
+            compose_output_path(None, "42-discussion.md"),
+            "42-discussion.md"
+        );
+        let composed = compose_output_path(Some("exports"), "42-discussion.md");
+        assert_eq!(
+            std::path::Path::new(&composed),
+            std::path::Path::new("exports").join("42-discussion.md")
+        );
+        let nested = compose_output_path(Some("exports"), "sub/custom.md");
+        assert_eq!(
+            std::path::Path::new(&nested),
+            std::path::Path::new("exports").join("sub/custom.md")
+        );
+    }
+
+    #[test]
+    fn test_output_path_with_output_dir() {
+        let args = vec![
+            OsString::from("gh-discussion-export"),
+            OsString::from("42"),
+            OsString::from("--output-dir"),
+            OsString::from("exports"),
+        ];
+        let cli = CliArgs::try_parse_from(args).unwrap();
+        assert!(cli.validate().is_ok());
+        assert_eq!(
+            std::path::Path::new(&cli.output_path()),
+            std::path::Path::new("exports").join("42-discussion.md")
+        );
+    }
+
+    #[test]
+    fn test_parse_format_invalid() {
+        let args = vec![
+            OsString::from("gh-discussion-export"),
+            OsString::from("42"),
+            OsString::from("--format"),
+            OsString::from("html"),
+        ];
+        assert!(CliArgs::try_parse_from(args).is_err());
+    }
+
     #[test]
     fn test_repo_owner_with_explicit_repo() {
         let args = vec![
@@ -478,7 +2529,7 @@ mod tests {
             .times(1)
             .returning(|_, _| Ok(mock_success_output("tatsuya6502/gh-discussion-export")));
 
-        let result = CliArgs::detect_from_git_with_runner(&mock);
+        let result = CliArgs::detect_from_git_with_runner(&mock, None);
         assert_eq!(result.unwrap(), "tatsuya6502/gh-discussion-export");
     }
 
@@ -491,23 +2542,35 @@ mod tests {
             .times(1)
             .returning(|_, _| Ok(mock_success_output("  owner/repo  \n")));
 
-        let result = CliArgs::detect_from_git_with_runner(&mock);
+        let result = CliArgs::detect_from_git_with_runner(&mock, None);
         assert_eq!(result.unwrap(), "owner/repo");
     }
 
+    // Expect the `git remote get-url origin` fallback and make it fail
+    fn expect_no_origin_remote(mock: &mut crate::command_runner::MockCommandRunner) {
+        mock.expect_run()
+            .withf(|program, args| program == "git" && args == ["remote", "get-url", "origin"])
+            .times(1)
+            .returning(|_, _| Ok(mock_failure_output("error: No such remote 'origin'")));
+    }
+
     #[test]
     fn test_detect_from_git_not_found() {
         use crate::command_runner::MockCommandRunner;
 
         let mut mock = MockCommandRunner::new();
-        mock.expect_run().times(1).returning(|_, _| {
-            Err(std::io::Error::new(
-                std::io::ErrorKind::NotFound,
-                "gh not found",
-            ))
-        });
-
-        let result = CliArgs::detect_from_git_with_runner(&mock);
+        mock.expect_run()
+            .withf(|program, _| program == "gh")
+            .times(1)
+            .returning(|_, _| {
+                Err(std::io::Error::new(
+                    std::io::ErrorKind::NotFound,
+                    "gh not found",
+                ))
+            });
+        expect_no_origin_remote(&mut mock);
+
+        let result = CliArgs::detect_from_git_with_runner(&mock, None);
         assert!(matches!(result, Err(Error::GitHubCliNotFound)));
     }
 
@@ -516,13 +2579,17 @@ mod tests {
         use crate::command_runner::MockCommandRunner;
 
         let mut mock = MockCommandRunner::new();
-        mock.expect_run().times(1).returning(|_, _| {
-            Ok(mock_failure_output(
-                "not a git repository (or any of the parent directories): .git",
-            ))
-        });
-
-        let result = CliArgs::detect_from_git_with_runner(&mock);
+        mock.expect_run()
+            .withf(|program, _| program == "gh")
+            .times(1)
+            .returning(|_, _| {
+                Ok(mock_failure_output(
+                    "not a git repository (or any of the parent directories): .git",
+                ))
+            });
+        expect_no_origin_remote(&mut mock);
+
+        let result = CliArgs::detect_from_git_with_runner(&mock, None);
         assert!(result.is_err());
         if let Err(Error::InvalidArgs(msg)) = result {
             assert!(msg.contains("not a git repository"));
@@ -538,10 +2605,12 @@ mod tests {
 
         let mut mock = MockCommandRunner::new();
         mock.expect_run()
+            .withf(|program, _| program == "gh")
             .times(1)
             .returning(|_, _| Ok(mock_failure_output("not a git repository.")));
+        expect_no_origin_remote(&mut mock);
 
-        let result = CliArgs::detect_from_git_with_runner(&mock);
+        let result = CliArgs::detect_from_git_with_runner(&mock, None);
         assert!(result.is_err());
         if let Err(Error::InvalidArgs(msg)) = result {
             // Should not have double period
@@ -559,10 +2628,12 @@ mod tests {
 
         let mut mock = MockCommandRunner::new();
         mock.expect_run()
+            .withf(|program, _| program == "gh")
             .times(1)
             .returning(|_, _| Ok(mock_failure_output("")));
+        expect_no_origin_remote(&mut mock);
 
-        let result = CliArgs::detect_from_git_with_runner(&mock);
+        let result = CliArgs::detect_from_git_with_runner(&mock, None);
         assert!(result.is_err());
         if let Err(Error::InvalidArgs(msg)) = result {
             // Should not start with a period
@@ -580,10 +2651,12 @@ mod tests {
 
         let mut mock = MockCommandRunner::new();
         mock.expect_run()
+            .withf(|program, _| program == "gh")
             .times(1)
             .returning(|_, _| Ok(mock_success_output("   \n  ")));
+        expect_no_origin_remote(&mut mock);
 
-        let result = CliArgs::detect_from_git_with_runner(&mock);
+        let result = CliArgs::detect_from_git_with_runner(&mock, None);
         assert!(result.is_err());
         if let Err(Error::InvalidArgs(msg)) = result {
             assert!(msg.contains("Could not detect repository"));
@@ -597,15 +2670,19 @@ mod tests {
         use crate::command_runner::MockCommandRunner;
 
         let mut mock = MockCommandRunner::new();
-        mock.expect_run().times(1).returning(|_, _| {
-            Ok(std::process::Output {
-                status: exit_status(0),
-                stdout: vec![0xFF, 0xFE, 0xFD], // Invalid UTF-8
-                stderr: Vec::new(),
-            })
-        });
-
-        let result = CliArgs::detect_from_git_with_runner(&mock);
+        mock.expect_run()
+            .withf(|program, _| program == "gh")
+            .times(1)
+            .returning(|_, _| {
+                Ok(std::process::Output {
+                    status: exit_status(0),
+                    stdout: vec![0xFF, 0xFE, 0xFD], // Invalid UTF-8
+                    stderr: Vec::new(),
+                })
+            });
+        expect_no_origin_remote(&mut mock);
+
+        let result = CliArgs::detect_from_git_with_runner(&mock, None);
         assert!(result.is_err());
         if let Err(Error::InvalidArgs(msg)) = result {
             assert!(msg.contains("Failed to parse repository information"));
@@ -613,4 +2690,648 @@ mod tests {
             panic!("Expected Error::InvalidArgs");
         }
     }
+
+    #[test]
+    fn test_detect_from_git_requests_name_with_owner() {
+        use crate::command_runner::MockCommandRunner;
+
+        let mut mock = MockCommandRunner::new();
+        mock.expect_run()
+            .withf(|program, args| {
+                program == "gh"
+                    && args
+                        == [
+                            "repo",
+                            "view",
+                            "--json",
+                            "nameWithOwner",
+                            "--jq",
+                            ".nameWithOwner",
+                        ]
+            })
+            .times(1)
+            .returning(|_, _| Ok(mock_success_output("owner/repo\n")));
+
+        let result = CliArgs::detect_from_git_with_runner(&mock, None);
+        assert_eq!(result.unwrap(), "owner/repo");
+    }
+
+    #[test]
+    fn test_detect_from_git_falls_back_to_origin_without_gh() {
+        use crate::command_runner::MockCommandRunner;
+
+        let mut mock = MockCommandRunner::new();
+        mock.expect_run()
+            .withf(|program, _| program == "gh")
+            .times(1)
+            .returning(|_, _| {
+                Err(std::io::Error::new(
+                    std::io::ErrorKind::NotFound,
+                    "gh not found",
+                ))
+            });
+        mock.expect_run()
+            .withf(|program, args| program == "git" && args == ["remote", "get-url", "origin"])
+            .times(1)
+            .returning(|_, _| Ok(mock_success_output("git@github.com:owner/repo.git\n")));
+
+        let result = CliArgs::detect_from_git_with_runner(&mock, None);
+        assert_eq!(result.unwrap(), "owner/repo");
+    }
+
+    #[test]
+    fn test_detect_from_git_prefers_origin_when_gh_fails() {
+        use crate::command_runner::MockCommandRunner;
+
+        let mut mock = MockCommandRunner::new();
+        mock.expect_run()
+            .withf(|program, _| program == "gh")
+            .times(1)
+            .returning(|_, _| {
+                Ok(mock_failure_output(
+                    "X No default remote repository has been set for this directory.",
+                ))
+            });
+        mock.expect_run()
+            .withf(|program, _| program == "git")
+            .times(1)
+            .returning(|_, _| Ok(mock_success_output("https://github.com/owner/repo\n")));
+
+        let result = CliArgs::detect_from_git_with_runner(&mock, None);
+        assert_eq!(result.unwrap(), "owner/repo");
+    }
+
+    #[test]
+    fn test_parse_remote_url_formats() {
+        let cases = [
+            "git@github.com:owner/repo.git",
+            "git@github.com:owner/repo",
+            "https://github.com/owner/repo.git",
+            "https://github.com/owner/repo",
+            "https://github.com/owner/repo/",
+            "https://github.com/owner/repo.git/",
+            "https://user@github.com/owner/repo.git",
+            "ssh://git@github.com/owner/repo",
+            "ssh://git@github.com/owner/repo.git",
+            "ssh://git@github.com:22/owner/repo.git",
+            "  https://GitHub.com/owner/repo\n",
+        ];
+        for url in cases {
+            assert_eq!(
+                parse_remote_url(url).unwrap(),
+                ("owner".to_string(), "repo".to_string()),
+                "{}",
+                url
+            );
+        }
+    }
+
+    #[test]
+    fn test_parse_remote_url_invalid() {
+        let cases = [
+            "",
+            "/local/path/repo",
+            "https://github.com/owner",
+            "https://github.com/owner/repo/extra",
+            "git@github.com:owner/",
+            "https://github.com",
+        ];
+        for url in cases {
+            assert!(
+                matches!(parse_remote_url(url), Err(Error::InvalidArgs(_))),
+                "{}",
+                url
+            );
+        }
+    }
+
+    #[test]
+    fn test_parse_remote_url_rejects_other_hosts() {
+        match parse_remote_url("git@gitlab.com:owner/repo.git") {
+            Err(Error::InvalidArgs(msg)) => {
+                assert!(msg.contains("not a GitHub repository"));
+                assert!(msg.contains("--github-host gitlab.com"));
+            }
+            other => panic!("Expected Error::InvalidArgs, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_parse_remote_url_with_github_host() {
+        let expected = ("owner".to_string(), "repo".to_string());
+        for host in ["ghe.example.com", "https://ghe.example.com/api/graphql"] {
+            assert_eq!(
+                parse_remote_url_with_host("git@ghe.example.com:owner/repo.git", Some(host))
+                    .unwrap(),
+                expected
+            );
+        }
+        assert_eq!(
+            parse_remote_url_with_host("https://github.com/owner/repo", Some("ghe.example.com"))
+                .unwrap(),
+            expected
+        );
+        assert!(
+            parse_remote_url_with_host(
+                "https://other.example.com/owner/repo",
+                Some("ghe.example.com")
+            )
+            .is_err()
+        );
+    }
+
+    #[test]
+    fn test_parse_repo_from_remote() {
+        let cli = CliArgs::try_parse_from([
+            "gh-discussion-export",
+            "--repo-from-remote",
+            "upstream",
+            "1",
+        ])
+        .unwrap();
+        assert_eq!(cli.repo_from_remote.as_deref(), Some("upstream"));
+        assert!(cli.validate().is_ok());
+    }
+
+    #[test]
+    fn test_validate_repo_from_remote_conflicts() {
+        let cli = CliArgs::try_parse_from([
+            "gh-discussion-export",
+            "--repo",
+            "owner/repo",
+            "--repo-from-remote",
+            "upstream",
+            "1",
+        ])
+        .unwrap();
+        assert!(matches!(cli.validate(), Err(Error::InvalidArgs(_))));
+
+        let cli = CliArgs::try_parse_from([
+            "gh-discussion-export",
+            "--repo-from-remote",
+            "upstream",
+            "https://github.com/owner/repo/discussions/1",
+        ])
+        .unwrap();
+        assert!(matches!(cli.validate(), Err(Error::InvalidArgs(_))));
+    }
+
+    #[test]
+    fn test_detect_from_remote_ssh_and_https() {
+        use crate::command_runner::MockCommandRunner;
+
+        let mut mock = MockCommandRunner::new();
+        mock.expect_run()
+            .withf(|program, args| program == "git" && args == ["remote", "get-url", "upstream"])
+            .times(1)
+            .returning(|_, _| Ok(mock_success_output("git@github.com:canonical/repo.git\n")));
+        mock.expect_run()
+            .withf(|program, args| program == "git" && args == ["remote", "get-url", "origin"])
+            .times(1)
+            .returning(|_, _| Ok(mock_success_output("https://github.com/fork/repo.git\n")));
+
+        assert_eq!(
+            CliArgs::detect_from_remote_with_runner(&mock, "upstream", None).unwrap(),
+            "canonical/repo"
+        );
+        assert_eq!(
+            CliArgs::detect_from_remote_with_runner(&mock, "origin", None).unwrap(),
+            "fork/repo"
+        );
+    }
+
+    #[test]
+    fn test_detect_from_remote_unknown_name() {
+        use crate::command_runner::MockCommandRunner;
+
+        let mut mock = MockCommandRunner::new();
+        mock.expect_run()
+            .times(1)
+            .returning(|_, _| Ok(mock_failure_output("error: No such remote 'nope'")));
+
+        match CliArgs::detect_from_remote_with_runner(&mock, "nope", None) {
+            Err(Error::InvalidArgs(msg)) => {
+                assert!(msg.contains("Git remote 'nope' could not be read"));
+                assert!(msg.contains("No such remote 'nope'"));
+                assert!(msg.contains("specify --repo explicitly"));
+            }
+            other => panic!("Expected Error::InvalidArgs, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_parse_generate_completion_without_number() {
+        let cli =
+            CliArgs::try_parse_from(["gh-discussion-export", "--generate-completion", "bash"])
+                .unwrap();
+        assert_eq!(cli.generate_completion, Some(Shell::Bash));
+        assert!(cli.discussion.is_none());
+
+        // The discussion number is still required otherwise
+        assert!(CliArgs::try_parse_from(["gh-discussion-export"]).is_err());
+    }
+
+    #[test]
+    fn test_write_bash_completion() {
+        let mut out = Vec::new();
+        write_completion(Shell::Bash, &mut out).unwrap();
+        let script = String::from_utf8(out).unwrap();
+        assert!(!script.is_empty());
+        assert!(script.contains("gh-discussion-export"));
+        assert!(script.contains("--repo"));
+    }
+
+    #[test]
+    fn test_parse_generate_man_without_number() {
+        let cli = CliArgs::try_parse_from(["gh-discussion-export", "--generate-man"]).unwrap();
+        assert!(cli.generate_man);
+        assert!(cli.discussion.is_none());
+    }
+
+    #[test]
+    fn test_write_man_page() {
+        let mut out = Vec::new();
+        write_man_page(&mut out).unwrap();
+        let page = String::from_utf8(out).unwrap();
+        assert!(page.contains(".TH gh-discussion-export 1"));
+        assert!(page.contains(".SH NAME"));
+        assert!(page.contains("\\-\\-repo"));
+    }
+
+    #[test]
+    fn test_parse_page_size() {
+        let cli =
+            CliArgs::try_parse_from(["gh-discussion-export", "--page-size", "25", "1"]).unwrap();
+        assert_eq!(cli.page_size, Some(25));
+        assert_eq!(cli.fetch_options().page_size(), 25);
+
+        for invalid in ["0", "101", "abc"] {
+            assert!(
+                CliArgs::try_parse_from(["gh-discussion-export", "--page-size", invalid, "1"])
+                    .is_err()
+            );
+        }
+    }
+
+    #[test]
+    fn test_parse_fetch_parallel() {
+        let cli = CliArgs::try_parse_from(["gh-discussion-export", "--fetch-parallel", "4", "1"])
+            .unwrap();
+        assert_eq!(cli.fetch_parallel, Some(4));
+        assert_eq!(cli.fetch_options().parallel(), 4);
+
+        for invalid in ["0", "9"] {
+            assert!(
+                CliArgs::try_parse_from(["gh-discussion-export", "--fetch-parallel", invalid, "1"])
+                    .is_err()
+            );
+        }
+    }
+
+    #[test]
+    fn test_parse_append() {
+        let cli = CliArgs::try_parse_from(["gh-discussion-export", "123", "--append"]).unwrap();
+        assert!(cli.append);
+        assert!(cli.output_options().db_id_markers);
+        assert!(cli.validate().is_ok());
+
+        let cli = CliArgs::try_parse_from(["gh-discussion-export", "123"]).unwrap();
+        assert!(!cli.output_options().db_id_markers);
+    }
+
+    #[test]
+    fn test_validate_append_conflicts() {
+        for flags in [
+            &["--stdout"][..],
+            &["--force"],
+            &["--dry-run"],
+            &["--post-only"],
+            &["--toc"],
+            &["--format", "json"],
+        ] {
+            let mut argv = vec!["gh-discussion-export", "123", "--append"];
+            argv.extend_from_slice(flags);
+            let cli = CliArgs::try_parse_from(argv).unwrap();
+            match cli.validate() {
+                Err(Error::InvalidArgs(msg)) => {
+                    assert!(msg.contains("--append") && msg.contains(flags[0]))
+                }
+                _ => panic!("Expected Error::InvalidArgs for {:?}", flags),
+            }
+        }
+    }
+
+    #[test]
+    fn test_resumable() {
+        let cli = CliArgs::try_parse_from(["gh-discussion-export", "123", "-o", "out.md"]).unwrap();
+        assert!(cli.resumable());
+        let cli = CliArgs::try_parse_from(["gh-discussion-export", "123", "--append"]).unwrap();
+        assert!(cli.resumable());
+        for flags in [
+            &["--stdout"][..],
+            &["--dry-run"],
+            &["--split-files"],
+            &["--post-only"],
+            &["--template", "t.hbs"],
+            &["--format", "json"],
+        ] {
+            let mut argv = vec!["gh-discussion-export", "123"];
+            argv.extend_from_slice(flags);
+            let cli = CliArgs::try_parse_from(argv).unwrap();
+            assert!(!cli.resumable(), "{:?}", flags);
+        }
+    }
+
+    #[test]
+    fn test_parse_db_id_markers() {
+        let cli =
+            CliArgs::try_parse_from(["gh-discussion-export", "123", "--db-id-markers"]).unwrap();
+        assert!(cli.db_id_markers);
+        assert!(cli.output_options().db_id_markers);
+    }
+
+    #[test]
+    fn test_validate_split_files_conflicts() {
+        let cli =
+            CliArgs::try_parse_from(["gh-discussion-export", "123", "--split-files"]).unwrap();
+        assert!(cli.split_files);
+        assert!(cli.validate().is_ok());
+
+        for flags in [
+            &["--stdout"][..],
+            &["--append"],
+            &["--post-only"],
+            &["--toc"],
+            &["--format", "json"],
+        ] {
+            let mut argv = vec!["gh-discussion-export", "123", "--split-files"];
+            argv.extend_from_slice(flags);
+            let cli = CliArgs::try_parse_from(argv).unwrap();
+            match cli.validate() {
+                Err(Error::InvalidArgs(msg)) => {
+                    assert!(msg.contains("--split-files") && msg.contains(flags[0]))
+                }
+                _ => panic!("Expected Error::InvalidArgs for {:?}", flags),
+            }
+        }
+    }
+
+    #[test]
+    fn test_validate_template_conflicts() {
+        let cli = CliArgs::try_parse_from(["gh-discussion-export", "123", "--template", "t.hbs"])
+            .unwrap();
+        assert_eq!(cli.template.as_deref(), Some("t.hbs"));
+        assert!(cli.validate().is_ok());
+
+        for flags in [
+            &["--split-files"][..],
+            &["--append"],
+            &["--toc"],
+            &["--front-matter"],
+            &["--comments-only"],
+            &["--post-only"],
+            &["--permalinks"],
+            &["--anchor-ids"],
+            &["--reply-counts"],
+            &["--db-id-markers"],
+            &["--local-links"],
+            &["--format", "json"],
+        ] {
+            let mut argv = vec!["gh-discussion-export", "123", "--template", "t.hbs"];
+            argv.extend_from_slice(flags);
+            let cli = CliArgs::try_parse_from(argv).unwrap();
+            match cli.validate() {
+                Err(Error::InvalidArgs(msg)) => {
+                    assert!(msg.contains("--template") && msg.contains(flags[0]))
+                }
+                _ => panic!("Expected Error::InvalidArgs for {:?}", flags),
+            }
+        }
+    }
+
+    #[test]
+    fn test_parse_body_format() {
+        let cli = CliArgs::try_parse_from(["gh-discussion-export", "123", "--body-format", "html"])
+            .unwrap();
+        assert_eq!(cli.body_format, BodyFormat::Html);
+        assert!(cli.fetch_options().body_html);
+        assert_eq!(cli.output_options().body_format, BodyFormat::Html);
+
+        assert!(
+            CliArgs::try_parse_from(["gh-discussion-export", "123", "--body-format", "text"])
+                .is_err()
+        );
+    }
+
+    #[test]
+    fn test_parse_link_mentions() {
+        let cli =
+            CliArgs::try_parse_from(["gh-discussion-export", "123", "--link-mentions"]).unwrap();
+        assert!(cli.output_options().link_mentions);
+        assert!(cli.validate().is_ok());
+
+        let cli = CliArgs::try_parse_from([
+            "gh-discussion-export",
+            "123",
+            "--link-mentions",
+            "--body-format",
+            "html",
+        ])
+        .unwrap();
+        assert!(
+            matches!(cli.validate(), Err(Error::InvalidArgs(msg)) if msg.contains("--link-mentions"))
+        );
+    }
+
+    #[test]
+    fn test_parse_normalize_unicode() {
+        let cli = CliArgs::try_parse_from(["gh-discussion-export", "123", "--normalize-unicode"])
+            .unwrap();
+        assert!(cli.normalize_unicode);
+        assert!(cli.output_options().normalize_unicode);
+    }
+
+    #[test]
+    fn test_parse_strip_html_comments() {
+        let cli = CliArgs::try_parse_from(["gh-discussion-export", "123", "--strip-html-comments"])
+            .unwrap();
+        assert!(cli.strip_html_comments);
+        assert!(cli.output_options().strip_html_comments);
+    }
+
+    #[test]
+    fn test_validate_stats_conflicts() {
+        let cli = CliArgs::try_parse_from(["gh-discussion-export", "123", "--stats"]).unwrap();
+        assert!(cli.validate().is_ok());
+        assert!(cli.output_options().stats);
+
+        for extra in [
+            &["--append"][..],
+            &["--template", "export.hbs"],
+            &["--format", "json"],
+        ] {
+            let mut argv = vec!["gh-discussion-export", "123", "--stats"];
+            argv.extend_from_slice(extra);
+            let cli = CliArgs::try_parse_from(argv).unwrap();
+            assert!(
+                matches!(cli.validate(), Err(Error::InvalidArgs(msg)) if msg.starts_with("--stats cannot be combined with")),
+                "{:?}",
+                extra
+            );
+        }
+    }
+
+    #[test]
+    fn test_parse_min_comment_length() {
+        let cli =
+            CliArgs::try_parse_from(["gh-discussion-export", "123", "--min-comment-length", "10"])
+                .unwrap();
+        assert_eq!(cli.fetch_options().min_comment_length, Some(10));
+        assert!(
+            CliArgs::try_parse_from(["gh-discussion-export", "123", "--min-comment-length", "-1"])
+                .is_err()
+        );
+    }
+
+    #[test]
+    fn test_validate_collapse_author_runs_conflicts() {
+        let cli =
+            CliArgs::try_parse_from(["gh-discussion-export", "123", "--collapse-author-runs"])
+                .unwrap();
+        assert!(cli.validate().is_ok());
+        assert!(cli.output_options().collapse_author_runs);
+
+        for extra in [
+            &["--split-files"][..],
+            &["--template", "export.hbs"],
+            &["--format", "json"],
+        ] {
+            let mut argv = vec!["gh-discussion-export", "123", "--collapse-author-runs"];
+            argv.extend_from_slice(extra);
+            let cli = CliArgs::try_parse_from(argv).unwrap();
+            assert!(
+                matches!(cli.validate(), Err(Error::InvalidArgs(msg)) if msg.starts_with("--collapse-author-runs cannot be combined with")),
+                "{:?}",
+                extra
+            );
+        }
+    }
+
+    #[test]
+    fn test_parse_after_comment_cursor() {
+        let cli = CliArgs::try_parse_from([
+            "gh-discussion-export",
+            "123",
+            "--after-comment-cursor",
+            "Y3Vyc29yOjE=",
+            "--append",
+        ])
+        .unwrap();
+        assert!(cli.validate().is_ok());
+        assert_eq!(
+            cli.fetch_options().after_comment_cursor.as_deref(),
+            Some("Y3Vyc29yOjE=")
+        );
+
+        // Without --append the comments before the cursor would be lost
+        let cli = CliArgs::try_parse_from([
+            "gh-discussion-export",
+            "123",
+            "--after-comment-cursor",
+            "Y3Vyc29yOjE=",
+        ])
+        .unwrap();
+        assert!(matches!(
+            cli.validate(),
+            Err(Error::InvalidArgs(msg)) if msg.starts_with("--after-comment-cursor requires --append")
+        ));
+    }
+
+    #[test]
+    fn test_parse_verify_counts() {
+        let cli =
+            CliArgs::try_parse_from(["gh-discussion-export", "123", "--verify-counts"]).unwrap();
+        assert!(cli.fetch_options().verify_counts);
+    }
+
+    #[test]
+    fn test_parse_lenient() {
+        let cli = CliArgs::try_parse_from(["gh-discussion-export", "123", "--lenient"]).unwrap();
+        assert!(cli.fetch_options().lenient);
+    }
+
+    #[test]
+    fn test_parse_exclude_author() {
+        let cli = CliArgs::try_parse_from([
+            "gh-discussion-export",
+            "123",
+            "--exclude-author",
+            "renovate",
+            "--exclude-author",
+            "github-actions[bot]",
+            "--exclude-bots",
+        ])
+        .unwrap();
+        let options = cli.fetch_options();
+        assert_eq!(options.exclude_authors, ["renovate", "github-actions[bot]"]);
+        assert!(options.exclude_bots);
+    }
+
+    #[test]
+    fn test_parse_only_author() {
+        let cli = CliArgs::try_parse_from([
+            "gh-discussion-export",
+            "123",
+            "--only-author",
+            "alice",
+            "--only-author",
+            "bob",
+        ])
+        .unwrap();
+        assert_eq!(cli.fetch_options().only_authors, ["alice", "bob"]);
+    }
+
+    #[test]
+    fn test_validate_indent_replies_conflicts() {
+        let cli =
+            CliArgs::try_parse_from(["gh-discussion-export", "123", "--indent-replies"]).unwrap();
+        assert!(cli.validate().is_ok());
+        assert!(cli.output_options().indent_replies);
+
+        for extra in [&["--template", "export.hbs"][..], &["--format", "json"]] {
+            let mut argv = vec!["gh-discussion-export", "123", "--indent-replies"];
+            argv.extend_from_slice(extra);
+            let cli = CliArgs::try_parse_from(argv).unwrap();
+            assert!(
+                matches!(cli.validate(), Err(Error::InvalidArgs(msg)) if msg.starts_with("--indent-replies cannot be combined with")),
+                "{:?}",
+                extra
+            );
+        }
+    }
+
+    #[test]
+    fn test_parse_heading_escape() {
+        assert_eq!(parse_heading_escape("never"), Ok(HeadingEscape::Never));
+        assert_eq!(parse_heading_escape("all"), Ok(HeadingEscape::All));
+        assert_eq!(
+            parse_heading_escape("level>=3"),
+            Ok(HeadingEscape::MinLevel(3))
+        );
+        for invalid in ["", "some", "level>=0", "level>=7", "level>3", "level>=x"] {
+            assert!(parse_heading_escape(invalid).is_err(), "{}", invalid);
+        }
+
+        let cli = CliArgs::try_parse_from([
+            "gh-discussion-export",
+            "123",
+            "--heading-escape",
+            "level>=2",
+        ])
+        .unwrap();
+        assert_eq!(
+            cli.output_options().heading_escape,
+            HeadingEscape::MinLevel(2)
+        );
+    }
 }
@@ -1,10 +1,128 @@
 use crate::error::{Error, Result};
-use crate::models::Discussion;
+use crate::models::{Discussion, GraphQLError, RateLimitInfo};
 #[cfg(test)]
 use mockall::automock;
+use std::sync::Mutex;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
 
 const GITHUB_GRAPHQL_URL: &str = "https://api.github.com/graphql";
 
+/// Derive the GraphQL endpoint URL for a GitHub host
+///
+/// - `None` or `github.com` resolves to `https://api.github.com/graphql`
+/// - A value starting with `http://` or `https://` is used verbatim as the endpoint
+/// - Any other hostname (GitHub Enterprise Server) resolves to `https://<host>/api/graphql`
+pub fn graphql_endpoint(host: Option<&str>) -> String {
+    let host = match host.map(str::trim) {
+        Some(host) if !host.is_empty() => host.trim_end_matches('/'),
+        _ => return GITHUB_GRAPHQL_URL.to_string(),
+    };
+
+    if host.starts_with("https://") || host.starts_with("http://") {
+        host.to_string()
+    } else if host.eq_ignore_ascii_case("github.com") {
+        GITHUB_GRAPHQL_URL.to_string()
+    } else {
+        format!("https://{}/api/graphql", host)
+    }
+}
+
+/// The hostname of a `--github-host` value (a hostname or an endpoint URL)
+pub fn host_name(github_host: &str) -> &str {
+    let value = github_host.trim();
+    let value = value.split_once("://").map_or(value, |(_, rest)| rest);
+    value.split(['/', ':']).next().unwrap_or_default()
+}
+
+/// Compute how long to wait before retrying a rate-limited request
+///
+/// Prefers the `Retry-After` header (seconds to wait), then falls back to
+/// `X-RateLimit-Reset` (UTC epoch seconds when the quota resets) relative to `now`.
+/// Returns `None` when neither header yields a usable value.
+fn rate_limit_wait(
+    retry_after: Option<&str>,
+    reset: Option<&str>,
+    now: SystemTime,
+) -> Option<Duration> {
+    if let Some(secs) = retry_after.and_then(|v| v.trim().parse::<u64>().ok()) {
+        return Some(Duration::from_secs(secs));
+    }
+
+    let reset_epoch = reset.and_then(|v| v.trim().parse::<u64>().ok())?;
+    let now_epoch = now.duration_since(UNIX_EPOCH).ok()?.as_secs();
+    Some(Duration::from_secs(reset_epoch.saturating_sub(now_epoch)))
+}
+
+/// Upper bound for `--max-retries`, since every retry may wait up to
+/// `RetryPolicy::max_wait`
+pub const MAX_RETRIES: u32 = 10;
+
+/// Upper bound for `--retry-max-wait` in seconds; GitHub's rate limits reset
+/// within an hour
+pub const MAX_RETRY_WAIT_SECS: u64 = 3600;
+
+/// Retry behavior for rate-limited GraphQL requests
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct RetryPolicy {
+    /// Maximum number of retries after the initial attempt (0 disables retrying)
+    pub max_retries: u32,
+    /// Upper bound on the time to wait before a single retry
+    pub max_wait: Duration,
+}
+
+impl Default for RetryPolicy {
+    /// No retries; rate limit errors are returned immediately
+    fn default() -> Self {
+        Self {
+            max_retries: 0,
+            max_wait: Duration::from_secs(60),
+        }
+    }
+}
+
+impl RetryPolicy {
+    /// Compute the wait before retry number `attempt` (0-based)
+    ///
+    /// Uses the server-suggested wait when available, otherwise exponential
+    /// backoff starting at one second. The result is capped at `max_wait`.
+    fn wait_for(&self, attempt: u32, retry_after: Option<Duration>) -> Duration {
+        let wait = retry_after
+            .unwrap_or_else(|| Duration::from_secs(1u64.checked_shl(attempt).unwrap_or(u64::MAX)));
+        wait.min(self.max_wait)
+    }
+}
+
+/// Connection and request timeouts for the HTTP client
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Timeouts {
+    /// Maximum time to establish a connection
+    pub connect: Duration,
+    /// Maximum time for a whole request, from connecting to reading the body
+    pub request: Duration,
+}
+
+impl Default for Timeouts {
+    /// 60 seconds to connect, 5 minutes per request
+    fn default() -> Self {
+        Self {
+            connect: Duration::from_secs(60),
+            request: Duration::from_secs(300),
+        }
+    }
+}
+
+/// Configuration for the production HTTP client
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct HttpOptions {
+    /// Connect and request timeouts
+    pub timeouts: Timeouts,
+    /// Proxy URL for all requests
+    ///
+    /// When `None`, the `HTTPS_PROXY`/`HTTP_PROXY`/`ALL_PROXY` environment
+    /// variables are used. `NO_PROXY` is honored in both cases.
+    pub proxy: Option<String>,
+}
+
 /// HTTP client trait for making POST requests
 ///
 /// This trait allows mocking HTTP requests in tests without starting a real server.
@@ -21,11 +139,29 @@ pub struct ReqwestClient {
 }
 
 impl ReqwestClient {
-    /// Create a new ReqwestClient with the given GitHub token
+    /// Create a new ReqwestClient with the given GitHub token and default options
     pub fn new(token: String) -> Result<Self> {
-        let client = reqwest::blocking::Client::builder()
+        Self::with_options(token, &HttpOptions::default())
+    }
+
+    /// Create a new ReqwestClient with the given GitHub token and options
+    ///
+    /// Returns Error::Http if the proxy URL is invalid.
+    pub fn with_options(token: String, options: &HttpOptions) -> Result<Self> {
+        let mut builder = reqwest::blocking::Client::builder()
             .user_agent("gh-discussion-export")
-            .connect_timeout(std::time::Duration::from_secs(60))
+            .connect_timeout(options.timeouts.connect)
+            .timeout(options.timeouts.request);
+
+        // An explicit proxy replaces the environment proxies; keep honoring NO_PROXY
+        if let Some(url) = &options.proxy {
+            let proxy = reqwest::Proxy::all(url)
+                .map_err(|e| Error::Http(format!("Invalid proxy URL '{}': {}", url, e)))?
+                .no_proxy(reqwest::NoProxy::from_env());
+            builder = builder.proxy(proxy);
+        }
+
+        let client = builder
             .build()
             .map_err(|e| Error::Http(format!("Failed to create HTTP client: {}", e)))?;
 
@@ -48,14 +184,23 @@ impl HttpClient for ReqwestClient {
         let status = response.status();
 
         // Try to extract rate limit information from headers before consuming response
+        let header = |name: &str| {
+            response
+                .headers()
+                .get(name)
+                .and_then(|v| v.to_str().ok())
+                .map(|v| v.to_string())
+        };
+        let retry_after = header("Retry-After");
         let is_rate_limit = status.as_u16() == 429
             || (status.as_u16() == 403
-                && response
-                    .headers()
-                    .get("X-RateLimit-Remaining")
-                    .and_then(|v| v.to_str().ok())
-                    .map(|v| v == "0")
-                    .unwrap_or(false));
+                && (header("X-RateLimit-Remaining").is_some_and(|v| v == "0")
+                    || retry_after.is_some()));
+        let wait = rate_limit_wait(
+            retry_after.as_deref(),
+            header("X-RateLimit-Reset").as_deref(),
+            SystemTime::now(),
+        );
 
         let response_text = response
             .text()
@@ -65,7 +210,7 @@ impl HttpClient for ReqwestClient {
         if status.as_u16() == 401 {
             return Err(Error::Authentication);
         } else if is_rate_limit {
-            return Err(Error::RateLimit);
+            return Err(Error::RateLimit { retry_after: wait });
         } else if status.as_u16() == 403 {
             return Err(Error::PermissionDenied(format!(
                 "Access denied: {}",
@@ -83,38 +228,195 @@ impl HttpClient for ReqwestClient {
     }
 }
 
+/// Parse the `data.rateLimit` block of a GraphQL response
+///
+/// Returns `None` if the block is absent or null (e.g. rate limiting is
+/// disabled on a GitHub Enterprise Server instance).
+pub(crate) fn parse_rate_limit(response: &serde_json::Value) -> Option<RateLimitInfo> {
+    let value = response.get("data")?.get("rateLimit")?;
+    serde_json::from_value(value.clone()).ok()
+}
+
+/// Rate limit budget used across all queries sent by a GitHubClient
+#[derive(Debug, Clone, PartialEq)]
+pub struct RateLimitUsage {
+    /// Sum of the cost of every query
+    pub total_cost: u64,
+    /// Budget reported by the most recent query
+    pub latest: RateLimitInfo,
+}
+
+impl RateLimitUsage {
+    /// Fold the budget reported by another query into the usage
+    fn record(usage: Option<Self>, info: RateLimitInfo) -> Self {
+        let total_cost = usage.map_or(0, |u| u.total_cost) + info.cost;
+        Self {
+            total_cost,
+            latest: info,
+        }
+    }
+
+    /// Format a one-line summary, e.g.
+    /// `Rate limit: 4850/5000 remaining, resets at 2024-01-15T11:00:00Z (cost: 3)`
+    pub fn summary(&self) -> String {
+        format!(
+            "Rate limit: {}/{} remaining, resets at {} (cost: {})",
+            self.latest.remaining,
+            self.latest.limit,
+            self.latest
+                .reset_at
+                .to_rfc3339_opts(chrono::SecondsFormat::Secs, true),
+            self.total_cost
+        )
+    }
+}
+
+/// Convert the `errors` array of a GraphQL response into an Error, if any
+///
+/// Returns `Error::NotFound` when any error has type `NOT_FOUND` (e.g.
+/// "Could not resolve to a Repository"), otherwise `Error::GraphQL`.
+/// Each error is rendered with its code and path (see `GraphQLError`'s
+/// `Display`), joined with `; `.
+pub(crate) fn graphql_error(response: &serde_json::Value) -> Option<Error> {
+    let errors = response.get("errors").filter(|e| !e.is_null())?;
+    let errors: Vec<GraphQLError> = match serde_json::from_value(errors.clone()) {
+        Ok(errors) => errors,
+        Err(_) => return Some(Error::GraphQL(errors.to_string())),
+    };
+    if errors.is_empty() {
+        return None;
+    }
+
+    let message = errors
+        .iter()
+        .map(|e| e.to_string())
+        .collect::<Vec<_>>()
+        .join("; ");
+
+    Some(if errors.iter().any(GraphQLError::is_not_found) {
+        Error::NotFound(message)
+    } else {
+        Error::GraphQL(message)
+    })
+}
+
+/// Read a string variable (e.g. `owner`) from query variables, or `""` if absent
+fn string_variable(variables: &serde_json::Value, key: &str) -> String {
+    variables
+        .get(key)
+        .and_then(|v| v.as_str())
+        .unwrap_or_default()
+        .to_string()
+}
+
+/// Build `Error::RepositoryNotFound` from the discussion query variables
+fn repository_not_found(variables: &serde_json::Value) -> Error {
+    Error::RepositoryNotFound {
+        owner: string_variable(variables, "owner"),
+        repo: string_variable(variables, "repo"),
+    }
+}
+
+/// Build `Error::DiscussionNotFound` from the discussion query variables
+fn discussion_not_found(variables: &serde_json::Value) -> Error {
+    Error::DiscussionNotFound {
+        owner: string_variable(variables, "owner"),
+        repo: string_variable(variables, "repo"),
+        number: variables
+            .get("number")
+            .and_then(|v| v.as_u64())
+            .unwrap_or_default(),
+    }
+}
+
 /// GraphQL client for GitHub's API
 pub struct GitHubClient {
     http_client: Box<dyn HttpClient>,
+    endpoint: String,
+    retry_policy: RetryPolicy,
+    rate_limit_usage: Mutex<Option<RateLimitUsage>>,
 }
 
 impl GitHubClient {
-    /// Create a new GitHubClient with the given HTTP client
+    /// Create a new GitHubClient with the given HTTP client, targeting github.com
     pub fn new(http_client: Box<dyn HttpClient>) -> Self {
-        Self { http_client }
+        Self::with_endpoint(http_client, GITHUB_GRAPHQL_URL.to_string())
+    }
+
+    /// Create a new GitHubClient that sends queries to the given GraphQL endpoint
+    ///
+    /// Use `graphql_endpoint` to derive the endpoint for a GitHub Enterprise Server host.
+    pub fn with_endpoint(http_client: Box<dyn HttpClient>, endpoint: String) -> Self {
+        Self {
+            http_client,
+            endpoint,
+            retry_policy: RetryPolicy::default(),
+            rate_limit_usage: Mutex::new(None),
+        }
+    }
+
+    /// Set the retry behavior for rate-limited requests
+    pub fn with_retry_policy(mut self, retry_policy: RetryPolicy) -> Self {
+        self.retry_policy = retry_policy;
+        self
+    }
+
+    /// Rate limit budget used by the queries sent so far
+    ///
+    /// Returns `None` if no response included a `rateLimit` block.
+    pub fn rate_limit_usage(&self) -> Option<RateLimitUsage> {
+        self.rate_limit_usage
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .clone()
+    }
+
+    /// Send a request body to the endpoint, retrying on rate limit errors
+    ///
+    /// Waits for the server-suggested time (or exponential backoff), capped at
+    /// `RetryPolicy::max_wait`, up to `RetryPolicy::max_retries` times.
+    fn post_with_retry(&self, body: &str) -> Result<String> {
+        let mut attempt = 0;
+        loop {
+            match self.http_client.post(&self.endpoint, body) {
+                Err(Error::RateLimit { retry_after })
+                    if attempt < self.retry_policy.max_retries =>
+                {
+                    let wait = self.retry_policy.wait_for(attempt, retry_after);
+                    attempt += 1;
+                    eprintln!(
+                        "Warning: GitHub API rate limit exceeded; retrying in {}s (attempt {}/{})",
+                        wait.as_secs(),
+                        attempt,
+                        self.retry_policy.max_retries
+                    );
+                    std::thread::sleep(wait);
+                }
+                result => return result,
+            }
+        }
     }
 
     /// Execute a GraphQL query and return the Discussion data
     ///
     /// # Arguments
     /// * `query` - GraphQL query string
-    /// * `variables` - Query variables as a JSON value
+    /// * `variables` - Query variables as a JSON value (`owner`, `repo`, `number`)
     ///
     /// # Returns
-    /// The Discussion object from the response
+    /// The Discussion object from the response. Returns
+    /// `Error::RepositoryNotFound` if the repository does not resolve, and
+    /// `Error::DiscussionNotFound` if it has no discussion with that number.
     pub fn execute_query(&self, query: &str, variables: serde_json::Value) -> Result<Discussion> {
-        let response = self.execute_query_raw(query, variables)?;
-
-        // Check for GraphQL errors
-        if let Some(errors) = response.get("errors").and_then(|e| e.as_array())
-            && !errors.is_empty()
-        {
-            let error_messages: Vec<String> = errors
-                .iter()
-                .filter_map(|e| e.get("message").and_then(|m| m.as_str()))
-                .map(|s| s.to_string())
-                .collect();
-            return Err(Error::GraphQL(error_messages.join("; ")));
+        let response = self.execute_query_raw(query, variables.clone())?;
+
+        // Check for GraphQL errors; a NOT_FOUND error alongside partial data
+        // is classified from the data below
+        let has_data = response.get("data").is_some_and(|d| !d.is_null());
+        match graphql_error(&response) {
+            Some(Error::NotFound(_)) if has_data => {}
+            Some(err) => return Err(err),
+            None => {}
         }
 
         // Extract the data
@@ -125,12 +427,12 @@ impl GitHubClient {
         let repository = data
             .get("repository")
             .filter(|v| !v.is_null())
-            .ok_or_else(|| Error::JsonParse("Repository not found".to_string()))?;
+            .ok_or_else(|| repository_not_found(&variables))?;
 
         let discussion_value = repository
             .get("discussion")
             .filter(|v| !v.is_null())
-            .ok_or_else(|| Error::JsonParse("Discussion not found".to_string()))?;
+            .ok_or_else(|| discussion_not_found(&variables))?;
 
         // Parse the Discussion object
         let discussion: Discussion = serde_json::from_value(discussion_value.clone())
@@ -161,12 +463,23 @@ impl GitHubClient {
         let body_str = request_body.to_string();
 
         // Send the request
-        let response_text = self.http_client.post(GITHUB_GRAPHQL_URL, &body_str)?;
+        log::trace!("POST {} ({} bytes)", self.endpoint, body_str.len());
+        let response_text = self.post_with_retry(&body_str)?;
+        log::trace!("Received {} bytes", response_text.len());
 
         // Parse the response
         let response: serde_json::Value = serde_json::from_str(&response_text)
             .map_err(|e| Error::JsonParse(format!("Failed to parse JSON: {}", e)))?;
 
+        // Accumulate the query cost reported by the API
+        if let Some(info) = parse_rate_limit(&response) {
+            let mut usage = self
+                .rate_limit_usage
+                .lock()
+                .unwrap_or_else(|e| e.into_inner());
+            *usage = Some(RateLimitUsage::record(usage.take(), info));
+        }
+
         Ok(response)
     }
 }
@@ -181,6 +494,60 @@ mod tests {
         assert!(client.is_ok());
     }
 
+    #[test]
+    fn test_reqwest_client_with_custom_timeouts() {
+        let timeouts = Timeouts {
+            connect: Duration::from_secs(5),
+            request: Duration::from_secs(30),
+        };
+        let options = HttpOptions {
+            timeouts,
+            ..Default::default()
+        };
+        let client = ReqwestClient::with_options("test_token".to_string(), &options);
+        assert!(client.is_ok());
+    }
+
+    #[test]
+    fn test_reqwest_client_with_proxy() {
+        let options = HttpOptions {
+            proxy: Some("http://proxy.example.com:8080".to_string()),
+            ..Default::default()
+        };
+        let client = ReqwestClient::with_options("test_token".to_string(), &options);
+        assert!(client.is_ok());
+    }
+
+    #[test]
+    fn test_reqwest_client_with_socks_proxy() {
+        let options = HttpOptions {
+            proxy: Some("socks5://proxy.example.com:1080".to_string()),
+            ..Default::default()
+        };
+        let client = ReqwestClient::with_options("test_token".to_string(), &options);
+        assert!(client.is_ok());
+    }
+
+    #[test]
+    fn test_reqwest_client_with_invalid_proxy() {
+        let options = HttpOptions {
+            proxy: Some("not a url".to_string()),
+            ..Default::default()
+        };
+        let result = ReqwestClient::with_options("test_token".to_string(), &options);
+        match result {
+            Err(Error::Http(msg)) => assert!(msg.contains("Invalid proxy URL 'not a url'")),
+            _ => panic!("expected Error::Http"),
+        }
+    }
+
+    #[test]
+    fn test_timeouts_default() {
+        let timeouts = Timeouts::default();
+        assert_eq!(timeouts.connect, Duration::from_secs(60));
+        assert_eq!(timeouts.request, Duration::from_secs(300));
+    }
+
     #[test]
     fn test_github_client_creation() {
         let mock_http = Box::new(MockHttpClient::new());
@@ -188,6 +555,57 @@ mod tests {
         // Test passes if we can create a GitHubClient with a mock
     }
 
+    #[test]
+    fn test_graphql_endpoint_default() {
+        assert_eq!(graphql_endpoint(None), "https://api.github.com/graphql");
+        assert_eq!(graphql_endpoint(Some("")), "https://api.github.com/graphql");
+        assert_eq!(
+            graphql_endpoint(Some("github.com")),
+            "https://api.github.com/graphql"
+        );
+    }
+
+    #[test]
+    fn test_graphql_endpoint_enterprise_host() {
+        assert_eq!(
+            graphql_endpoint(Some("ghe.mycorp.com")),
+            "https://ghe.mycorp.com/api/graphql"
+        );
+        assert_eq!(
+            graphql_endpoint(Some("ghe.mycorp.com/")),
+            "https://ghe.mycorp.com/api/graphql"
+        );
+    }
+
+    #[test]
+    fn test_graphql_endpoint_full_url() {
+        assert_eq!(
+            graphql_endpoint(Some("https://ghe.mycorp.com/api/graphql")),
+            "https://ghe.mycorp.com/api/graphql"
+        );
+        assert_eq!(
+            graphql_endpoint(Some("http://localhost:8080/graphql/")),
+            "http://localhost:8080/graphql"
+        );
+    }
+
+    #[test]
+    fn test_github_client_uses_configured_endpoint() {
+        let mut mock_http = MockHttpClient::new();
+        mock_http
+            .expect_post()
+            .withf(|url, _body| url == "https://ghe.mycorp.com/api/graphql")
+            .times(1)
+            .returning(|_url, _body| Ok(serde_json::json!({"data": {}}).to_string()));
+
+        let client = GitHubClient::with_endpoint(
+            Box::new(mock_http),
+            graphql_endpoint(Some("ghe.mycorp.com")),
+        );
+        let result = client.execute_query_raw("query {}", serde_json::json!({}));
+        assert!(result.is_ok());
+    }
+
     #[test]
     fn test_successful_query_execution() {
         let mut mock_http = MockHttpClient::new();
@@ -255,11 +673,148 @@ mod tests {
         let result = client.execute_query("query {}", serde_json::json!({}));
         assert!(result.is_err());
         match result {
-            Err(Error::GraphQL(msg)) => assert!(msg.contains("invalid")),
+            Err(Error::GraphQL(msg)) => assert_eq!(
+                msg,
+                "Field 'invalid' doesn't exist on type 'Query' (path: repository.discussion.invalid)"
+            ),
             _ => panic!("Expected GraphQL error"),
         }
     }
 
+    #[test]
+    fn test_graphql_error_with_validation_code() {
+        let response = serde_json::json!({
+            "errors": [
+                {
+                    "message": "Variable $number of type Int! was provided invalid value",
+                    "extensions": {"code": "GRAPHQL_VALIDATION_FAILED"}
+                },
+                {"message": "Second problem"}
+            ]
+        });
+
+        match graphql_error(&response) {
+            Some(Error::GraphQL(msg)) => assert_eq!(
+                msg,
+                "Variable $number of type Int! was provided invalid value \
+                 (code: GRAPHQL_VALIDATION_FAILED); Second problem"
+            ),
+            other => panic!("Expected GraphQL error, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_graphql_error_without_errors() {
+        assert!(graphql_error(&serde_json::json!({"data": {}})).is_none());
+        assert!(graphql_error(&serde_json::json!({"data": {}, "errors": null})).is_none());
+        assert!(graphql_error(&serde_json::json!({"data": {}, "errors": []})).is_none());
+    }
+
+    #[test]
+    fn test_graphql_error_unexpected_shape() {
+        let response = serde_json::json!({"errors": "boom"});
+        assert!(
+            matches!(graphql_error(&response), Some(Error::GraphQL(msg)) if msg.contains("boom"))
+        );
+    }
+
+    #[test]
+    fn test_graphql_not_found_error() {
+        let mut mock_http = MockHttpClient::new();
+        mock_http.expect_post().times(1).returning(|_url, _body| {
+            Ok(serde_json::json!({
+                "data": { "repository": null },
+                "errors": [
+                    {
+                        "type": "NOT_FOUND",
+                        "message": "Could not resolve to a Repository with the name 'owner/missing'."
+                    }
+                ]
+            })
+            .to_string())
+        });
+
+        let client = GitHubClient::new(Box::new(mock_http));
+        let variables = serde_json::json!({"owner": "owner", "repo": "missing", "number": 1});
+        let result = client.execute_query("query {}", variables);
+        match result {
+            Err(Error::RepositoryNotFound { owner, repo }) => {
+                assert_eq!(owner, "owner");
+                assert_eq!(repo, "missing");
+            }
+            _ => panic!("Expected RepositoryNotFound error"),
+        }
+    }
+
+    #[test]
+    fn test_parse_rate_limit() {
+        let response = serde_json::json!({
+            "data": {
+                "repository": null,
+                "rateLimit": {
+                    "cost": 1,
+                    "limit": 5000,
+                    "remaining": 4850,
+                    "resetAt": "2024-01-15T11:00:00Z"
+                }
+            }
+        });
+
+        let info = parse_rate_limit(&response).unwrap();
+        assert_eq!(info.cost, 1);
+        assert_eq!(info.limit, 5000);
+        assert_eq!(info.remaining, 4850);
+    }
+
+    #[test]
+    fn test_parse_rate_limit_absent_or_null() {
+        assert!(parse_rate_limit(&serde_json::json!({"data": {}})).is_none());
+        assert!(parse_rate_limit(&serde_json::json!({"data": {"rateLimit": null}})).is_none());
+        assert!(parse_rate_limit(&serde_json::json!({"errors": []})).is_none());
+    }
+
+    #[test]
+    fn test_rate_limit_usage_accumulates_cost() {
+        let mut mock_http = MockHttpClient::new();
+        let mut seq = mockall::Sequence::new();
+        for (cost, remaining) in [(1, 4999), (2, 4997)] {
+            mock_http
+                .expect_post()
+                .times(1)
+                .in_sequence(&mut seq)
+                .returning(move |_url, _body| {
+                    Ok(serde_json::json!({
+                        "data": {
+                            "rateLimit": {
+                                "cost": cost,
+                                "limit": 5000,
+                                "remaining": remaining,
+                                "resetAt": "2024-01-15T11:00:00Z"
+                            }
+                        }
+                    })
+                    .to_string())
+                });
+        }
+
+        let client = GitHubClient::new(Box::new(mock_http));
+        assert!(client.rate_limit_usage().is_none());
+        client
+            .execute_query_raw("query {}", serde_json::json!({}))
+            .unwrap();
+        client
+            .execute_query_raw("query {}", serde_json::json!({}))
+            .unwrap();
+
+        let usage = client.rate_limit_usage().unwrap();
+        assert_eq!(usage.total_cost, 3);
+        assert_eq!(usage.latest.remaining, 4997);
+        assert_eq!(
+            usage.summary(),
+            "Rate limit: 4997/5000 remaining, resets at 2024-01-15T11:00:00Z (cost: 3)"
+        );
+    }
+
     #[test]
     fn test_http_401_error() {
         let mut mock_http = MockHttpClient::new();
@@ -283,17 +838,122 @@ mod tests {
         mock_http
             .expect_post()
             .times(1)
-            .returning(|_url, _body| Err(Error::RateLimit));
+            .returning(|_url, _body| Err(Error::RateLimit { retry_after: None }));
 
         let client = GitHubClient::new(Box::new(mock_http));
         let result = client.execute_query("query {}", serde_json::json!({}));
         assert!(result.is_err());
         match result {
-            Err(Error::RateLimit) => {}
+            Err(Error::RateLimit { .. }) => {}
             _ => panic!("Expected RateLimit error"),
         }
     }
 
+    /// Retry policy that retries without actually sleeping
+    fn no_wait_retry_policy(max_retries: u32) -> RetryPolicy {
+        RetryPolicy {
+            max_retries,
+            max_wait: Duration::ZERO,
+        }
+    }
+
+    #[test]
+    fn test_rate_limit_retry_then_success() {
+        let mut mock_http = MockHttpClient::new();
+        let mut seq = mockall::Sequence::new();
+        mock_http
+            .expect_post()
+            .times(2)
+            .in_sequence(&mut seq)
+            .returning(|_url, _body| {
+                Err(Error::RateLimit {
+                    retry_after: Some(Duration::from_secs(30)),
+                })
+            });
+        mock_http
+            .expect_post()
+            .times(1)
+            .in_sequence(&mut seq)
+            .returning(|_url, _body| Ok(serde_json::json!({"data": {}}).to_string()));
+
+        let client =
+            GitHubClient::new(Box::new(mock_http)).with_retry_policy(no_wait_retry_policy(3));
+        let result = client.execute_query_raw("query {}", serde_json::json!({}));
+        assert!(result.is_ok());
+    }
+
+    #[test]
+    fn test_rate_limit_retries_exhausted() {
+        let mut mock_http = MockHttpClient::new();
+        mock_http
+            .expect_post()
+            .times(3)
+            .returning(|_url, _body| Err(Error::RateLimit { retry_after: None }));
+
+        let client =
+            GitHubClient::new(Box::new(mock_http)).with_retry_policy(no_wait_retry_policy(2));
+        let result = client.execute_query_raw("query {}", serde_json::json!({}));
+        assert!(matches!(result, Err(Error::RateLimit { .. })));
+    }
+
+    #[test]
+    fn test_non_rate_limit_errors_are_not_retried() {
+        let mut mock_http = MockHttpClient::new();
+        mock_http
+            .expect_post()
+            .times(1)
+            .returning(|_url, _body| Err(Error::Authentication));
+
+        let client =
+            GitHubClient::new(Box::new(mock_http)).with_retry_policy(no_wait_retry_policy(3));
+        let result = client.execute_query_raw("query {}", serde_json::json!({}));
+        assert!(matches!(result, Err(Error::Authentication)));
+    }
+
+    #[test]
+    fn test_retry_policy_wait_for() {
+        let policy = RetryPolicy {
+            max_retries: 5,
+            max_wait: Duration::from_secs(10),
+        };
+        // Exponential backoff without a server hint
+        assert_eq!(policy.wait_for(0, None), Duration::from_secs(1));
+        assert_eq!(policy.wait_for(2, None), Duration::from_secs(4));
+        // Capped at max_wait
+        assert_eq!(policy.wait_for(5, None), Duration::from_secs(10));
+        assert_eq!(policy.wait_for(100, None), Duration::from_secs(10));
+        // Server hint takes precedence, still capped
+        assert_eq!(
+            policy.wait_for(0, Some(Duration::from_secs(7))),
+            Duration::from_secs(7)
+        );
+        assert_eq!(
+            policy.wait_for(0, Some(Duration::from_secs(3600))),
+            Duration::from_secs(10)
+        );
+    }
+
+    #[test]
+    fn test_rate_limit_wait_from_headers() {
+        let now = UNIX_EPOCH + Duration::from_secs(1_700_000_000);
+
+        assert_eq!(
+            rate_limit_wait(Some("30"), Some("1700000100"), now),
+            Some(Duration::from_secs(30))
+        );
+        assert_eq!(
+            rate_limit_wait(None, Some("1700000100"), now),
+            Some(Duration::from_secs(100))
+        );
+        // Reset time already passed
+        assert_eq!(
+            rate_limit_wait(None, Some("1699999999"), now),
+            Some(Duration::ZERO)
+        );
+        assert_eq!(rate_limit_wait(Some("soon"), None, now), None);
+        assert_eq!(rate_limit_wait(None, None, now), None);
+    }
+
     #[test]
     fn test_missing_data_field() {
         let mut mock_http = MockHttpClient::new();
@@ -324,10 +984,27 @@ mod tests {
         let client = GitHubClient::new(Box::new(mock_http));
         let result = client.execute_query("query {}", serde_json::json!({}));
         assert!(result.is_err());
-        match result {
-            Err(Error::JsonParse(msg)) => assert!(msg.to_lowercase().contains("repository")),
-            _ => panic!("Expected JsonParse error"),
-        }
+        assert!(matches!(result, Err(Error::RepositoryNotFound { .. })));
+    }
+
+    #[test]
+    fn test_null_repository_field() {
+        let mut mock_http = MockHttpClient::new();
+        mock_http.expect_post().times(1).returning(|_url, _body| {
+            Ok(serde_json::json!({
+                "data": {
+                    "repository": null
+                }
+            })
+            .to_string())
+        });
+
+        let client = GitHubClient::new(Box::new(mock_http));
+        let variables = serde_json::json!({"owner": "owner", "repo": "typo", "number": 1});
+        let err = client.execute_query("query {}", variables).unwrap_err();
+        assert!(matches!(err, Error::RepositoryNotFound { .. }));
+        assert!(err.to_string().contains("owner/typo"));
+        assert!(err.to_string().contains("--repo"));
     }
 
     #[test]
@@ -345,14 +1022,52 @@ mod tests {
         });
 
         let client = GitHubClient::new(Box::new(mock_http));
-        let result = client.execute_query("query {}", serde_json::json!({}));
-        assert!(result.is_err());
+        let variables = serde_json::json!({"owner": "owner", "repo": "repo", "number": 999});
+        let result = client.execute_query("query {}", variables);
         match result {
-            Err(Error::JsonParse(msg)) => assert!(msg.to_lowercase().contains("discussion")),
-            _ => panic!("Expected JsonParse error"),
+            Err(Error::DiscussionNotFound {
+                owner,
+                repo,
+                number,
+            }) => {
+                assert_eq!(owner, "owner");
+                assert_eq!(repo, "repo");
+                assert_eq!(number, 999);
+            }
+            _ => panic!("Expected DiscussionNotFound error"),
         }
     }
 
+    #[test]
+    fn test_discussion_not_found_graphql_error() {
+        let mut mock_http = MockHttpClient::new();
+        mock_http.expect_post().times(1).returning(|_url, _body| {
+            Ok(serde_json::json!({
+                "data": {
+                    "repository": {
+                        "discussion": null
+                    }
+                },
+                "errors": [
+                    {
+                        "type": "NOT_FOUND",
+                        "path": ["repository", "discussion"],
+                        "message": "Could not resolve to a Discussion with the number of 999."
+                    }
+                ]
+            })
+            .to_string())
+        });
+
+        let client = GitHubClient::new(Box::new(mock_http));
+        let variables = serde_json::json!({"owner": "owner", "repo": "repo", "number": 999});
+        let result = client.execute_query("query {}", variables);
+        assert!(matches!(
+            result,
+            Err(Error::DiscussionNotFound { number: 999, .. })
+        ));
+    }
+
     #[test]
     fn test_http_403_permission_denied_error() {
         let mut mock_http = MockHttpClient::new();
@@ -0,0 +1,495 @@
+// Configuration file with default flag values
+//
+// A `gh-discussion-export.toml` in the current directory (or, failing that,
+// in `$XDG_CONFIG_HOME`) supplies defaults for frequently used flags.
+// Precedence: command-line argument > config file > built-in default.
+// Configured values that conflict with explicit arguments are dropped, and
+// `--no-<flag>` turns off a boolean key for a single run.
+
+use std::path::{Path, PathBuf};
+
+use clap::ArgMatches;
+use clap::parser::ValueSource;
+use serde::Deserialize;
+
+use crate::cli::{
+    BodyFormat, CliArgs, OutputFormat, parse_date_format, parse_heading_escape, parse_timezone,
+};
+use crate::client::{MAX_RETRIES, MAX_RETRY_WAIT_SECS};
+use crate::error::{Error, Result};
+use crate::fetch::MAX_FETCH_PARALLEL;
+
+/// File name of the configuration file
+pub const CONFIG_FILE_NAME: &str = "gh-discussion-export.toml";
+
+/// Default flag values read from the configuration file
+///
+/// Keys are the long flag names without the leading `--`, e.g.
+/// `author-links = true` or `timezone = "Asia/Tokyo"`. Every key is optional.
+#[derive(Debug, Default, Clone, PartialEq, Eq, Deserialize)]
+#[serde(default, rename_all = "kebab-case", deny_unknown_fields)]
+pub struct Config {
+    pub format: Option<OutputFormat>,
+    pub body_format: Option<BodyFormat>,
+    pub output_dir: Option<String>,
+    pub github_host: Option<String>,
+    pub proxy: Option<String>,
+    pub max_retries: Option<u32>,
+    pub retry_max_wait: Option<u64>,
+    pub connect_timeout: Option<u64>,
+    pub request_timeout: Option<u64>,
+    pub fetch_parallel: Option<u16>,
+    pub min_comment_length: Option<usize>,
+    pub exclude_author: Option<Vec<String>>,
+    pub exclude_bots: Option<bool>,
+    pub only_author: Option<Vec<String>>,
+    pub verify_counts: Option<bool>,
+    pub lenient: Option<bool>,
+    pub timezone: Option<String>,
+    pub date_format: Option<String>,
+    pub front_matter: Option<bool>,
+    pub author_links: Option<bool>,
+    pub toc: Option<bool>,
+    pub local_links: Option<bool>,
+    pub blockquote_bodies: Option<bool>,
+    pub link_mentions: Option<bool>,
+    pub normalize_unicode: Option<bool>,
+    pub strip_html_comments: Option<bool>,
+    pub heading_escape: Option<String>,
+    pub permalinks: Option<bool>,
+    pub anchor_ids: Option<bool>,
+    pub db_id_markers: Option<bool>,
+    pub reply_counts: Option<bool>,
+    pub stats: Option<bool>,
+    pub collapse_author_runs: Option<bool>,
+    pub indent_replies: Option<bool>,
+    pub quiet: Option<bool>,
+}
+
+impl Config {
+    /// Load the first configuration file found, if any
+    ///
+    /// Looks for `gh-discussion-export.toml` in the current directory, then in
+    /// `$XDG_CONFIG_HOME` (`~/.config` when unset).
+    pub fn load() -> Result<Option<Config>> {
+        let paths = config_paths(
+            std::env::var_os("XDG_CONFIG_HOME").map(PathBuf::from),
+            std::env::var_os("HOME").map(PathBuf::from),
+        );
+        match paths.iter().find(|path| path.is_file()) {
+            Some(path) => Self::from_file(path).map(Some),
+            None => Ok(None),
+        }
+    }
+
+    /// Read and parse a configuration file
+    pub fn from_file(path: &Path) -> Result<Config> {
+        let text = std::fs::read_to_string(path)?;
+        parse_contents(&text).map_err(|e| Error::Config(format!("{}: {}", path.display(), e)))
+    }
+
+    /// Parse configuration file contents
+    pub fn parse(text: &str) -> Result<Config> {
+        parse_contents(text).map_err(Error::Config)
+    }
+
+    /// Fill in arguments that were not given on the command line
+    ///
+    /// `matches` are the matches `args` was built from; they tell which
+    /// arguments were passed explicitly (including `--no-<flag>`) and must not
+    /// be overridden. A value that `CliArgs::validate` rejects in combination
+    /// with the explicit arguments is dropped too, since those take
+    /// precedence; conflicts within the configuration file are still reported.
+    pub fn apply(&self, args: &mut CliArgs, matches: &ArgMatches) {
+        let given = |id: &str| {
+            matches.ids().any(|known| known == id)
+                && matches.value_source(id) == Some(ValueSource::CommandLine)
+        };
+        let explicit = |id: &str| given(id) || given(&format!("no_{}", id));
+
+        let baseline = args.clone();
+        let baseline_valid = baseline.validate().is_ok();
+        let conflicts = |id: &str| {
+            let mut candidate = baseline.clone();
+            self.merge_into(&mut candidate, &|other| other != id || explicit(other));
+            candidate.validate().is_err()
+        };
+        self.merge_into(args, &|id| {
+            explicit(id) || (baseline_valid && conflicts(id))
+        });
+    }
+
+    /// Copy the configured values into `args`, except for arguments `skip`
+    /// returns true for
+    fn merge_into(&self, args: &mut CliArgs, skip: &dyn Fn(&str) -> bool) {
+        merge(&mut args.format, self.format, skip("format"));
+        merge(&mut args.body_format, self.body_format, skip("body_format"));
+        merge(&mut args.max_retries, self.max_retries, skip("max_retries"));
+        merge(
+            &mut args.retry_max_wait,
+            self.retry_max_wait,
+            skip("retry_max_wait"),
+        );
+        merge(
+            &mut args.connect_timeout,
+            self.connect_timeout,
+            skip("connect_timeout"),
+        );
+        merge(
+            &mut args.request_timeout,
+            self.request_timeout,
+            skip("request_timeout"),
+        );
+        merge_option(
+            &mut args.fetch_parallel,
+            &self.fetch_parallel,
+            skip("fetch_parallel"),
+        );
+        merge_option(
+            &mut args.min_comment_length,
+            &self.min_comment_length,
+            skip("min_comment_length"),
+        );
+        merge(
+            &mut args.exclude_author,
+            self.exclude_author.clone(),
+            skip("exclude_author"),
+        );
+        merge(
+            &mut args.exclude_bots,
+            self.exclude_bots,
+            skip("exclude_bots"),
+        );
+        merge(
+            &mut args.only_author,
+            self.only_author.clone(),
+            skip("only_author"),
+        );
+        merge_option(&mut args.output_dir, &self.output_dir, skip("output_dir"));
+        merge_option(
+            &mut args.github_host,
+            &self.github_host,
+            skip("github_host"),
+        );
+        merge_option(&mut args.proxy, &self.proxy, skip("proxy"));
+        merge_option(
+            &mut args.date_format,
+            &self.date_format,
+            skip("date_format"),
+        );
+        // Validated by `parse_contents`
+        let timezone = self
+            .timezone
+            .as_deref()
+            .and_then(|tz| parse_timezone(tz).ok());
+        merge_option(&mut args.timezone, &timezone, skip("timezone"));
+        // Validated by `parse_contents`
+        let heading_escape = self
+            .heading_escape
+            .as_deref()
+            .and_then(|mode| parse_heading_escape(mode).ok());
+        merge(
+            &mut args.heading_escape,
+            heading_escape,
+            skip("heading_escape"),
+        );
+
+        merge(
+            &mut args.front_matter,
+            self.front_matter,
+            skip("front_matter"),
+        );
+        merge(
+            &mut args.author_links,
+            self.author_links,
+            skip("author_links"),
+        );
+        merge(&mut args.toc, self.toc, skip("toc"));
+        merge(&mut args.local_links, self.local_links, skip("local_links"));
+        merge(
+            &mut args.blockquote_bodies,
+            self.blockquote_bodies,
+            skip("blockquote_bodies"),
+        );
+        merge(
+            &mut args.link_mentions,
+            self.link_mentions,
+            skip("link_mentions"),
+        );
+        merge(
+            &mut args.normalize_unicode,
+            self.normalize_unicode,
+            skip("normalize_unicode"),
+        );
+        merge(
+            &mut args.strip_html_comments,
+            self.strip_html_comments,
+            skip("strip_html_comments"),
+        );
+        merge(&mut args.permalinks, self.permalinks, skip("permalinks"));
+        merge(&mut args.anchor_ids, self.anchor_ids, skip("anchor_ids"));
+        merge(
+            &mut args.db_id_markers,
+            self.db_id_markers,
+            skip("db_id_markers"),
+        );
+        merge(
+            &mut args.reply_counts,
+            self.reply_counts,
+            skip("reply_counts"),
+        );
+        merge(&mut args.stats, self.stats, skip("stats"));
+        merge(
+            &mut args.collapse_author_runs,
+            self.collapse_author_runs,
+            skip("collapse_author_runs"),
+        );
+        merge(
+            &mut args.indent_replies,
+            self.indent_replies,
+            skip("indent_replies"),
+        );
+        merge(
+            &mut args.verify_counts,
+            self.verify_counts,
+            skip("verify_counts"),
+        );
+        merge(&mut args.lenient, self.lenient, skip("lenient"));
+        merge(&mut args.quiet, self.quiet, skip("quiet"));
+    }
+}
+
+/// Parse and validate configuration file contents
+fn parse_contents(text: &str) -> std::result::Result<Config, String> {
+    let config: Config = toml::from_str(text).map_err(|e| e.message().to_string())?;
+    if let Some(timezone) = &config.timezone {
+        parse_timezone(timezone)?;
+    }
+    if let Some(date_format) = &config.date_format {
+        parse_date_format(date_format)?;
+    }
+    if let Some(heading_escape) = &config.heading_escape {
+        parse_heading_escape(heading_escape)?;
+    }
+    if config.connect_timeout == Some(0) || config.request_timeout == Some(0) {
+        return Err("timeouts must be at least 1 second".to_string());
+    }
+    if config.max_retries.is_some_and(|n| n > MAX_RETRIES) {
+        return Err(format!("max-retries must be between 0 and {}", MAX_RETRIES));
+    }
+    if config
+        .retry_max_wait
+        .is_some_and(|secs| !(1..=MAX_RETRY_WAIT_SECS).contains(&secs))
+    {
+        return Err(format!(
+            "retry-max-wait must be between 1 and {} seconds",
+            MAX_RETRY_WAIT_SECS
+        ));
+    }
+    if config
+        .fetch_parallel
+        .is_some_and(|n| !(1..=MAX_FETCH_PARALLEL).contains(&usize::from(n)))
+    {
+        return Err(format!(
+            "fetch-parallel must be between 1 and {}",
+            MAX_FETCH_PARALLEL
+        ));
+    }
+    Ok(config)
+}
+
+/// Candidate configuration file locations, in order of precedence
+fn config_paths(xdg_config_home: Option<PathBuf>, home: Option<PathBuf>) -> Vec<PathBuf> {
+    let mut paths = vec![PathBuf::from(CONFIG_FILE_NAME)];
+    // Relative XDG paths are invalid per the spec and are ignored
+    let config_dir = xdg_config_home
+        .filter(|dir| dir.is_absolute())
+        .or_else(|| home.map(|home| home.join(".config")));
+    if let Some(dir) = config_dir {
+        paths.push(dir.join(CONFIG_FILE_NAME));
+    }
+    paths
+}
+
+/// Use the config value unless the argument was given explicitly
+fn merge<T>(target: &mut T, value: Option<T>, explicit: bool) {
+    if let Some(value) = value
+        && !explicit
+    {
+        *target = value;
+    }
+}
+
+/// Like `merge`, for arguments that are optional themselves
+fn merge_option<T: Clone>(target: &mut Option<T>, value: &Option<T>, explicit: bool) {
+    if value.is_some() && !explicit {
+        target.clone_from(value);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use clap::{CommandFactory, FromArgMatches};
+
+    /// Parse command-line arguments and apply the config file contents
+    fn args_with_config(argv: &[&str], config: &str) -> CliArgs {
+        let matches = CliArgs::command().try_get_matches_from(argv).unwrap();
+        let mut args = CliArgs::from_arg_matches(&matches).unwrap();
+        Config::parse(config).unwrap().apply(&mut args, &matches);
+        args
+    }
+
+    #[test]
+    fn test_parse_config() {
+        let config = Config::parse(
+            "format = \"json\"\n\
+             timezone = \"Asia/Tokyo\"\n\
+             author-links = true\n\
+             max-retries = 5\n",
+        )
+        .unwrap();
+        assert_eq!(config.format, Some(OutputFormat::Json));
+        assert_eq!(config.timezone.as_deref(), Some("Asia/Tokyo"));
+        assert_eq!(config.author_links, Some(true));
+        assert_eq!(config.max_retries, Some(5));
+        assert_eq!(config.toc, None);
+    }
+
+    #[test]
+    fn test_parse_config_errors() {
+        assert!(Config::parse("unknown-flag = true").is_err());
+        assert!(Config::parse("toc = \"yes\"").is_err());
+        assert!(Config::parse("timezone = \"Mars/Base\"").is_err());
+        assert!(Config::parse("date-format = \"%Q\"").is_err());
+        assert!(Config::parse("heading-escape = \"some\"").is_err());
+        assert!(Config::parse("connect-timeout = 0").is_err());
+        assert!(Config::parse("fetch-parallel = 9").is_err());
+        assert!(Config::parse("max-retries = 11").is_err());
+        assert!(Config::parse("retry-max-wait = 0").is_err());
+        assert!(Config::parse("retry-max-wait = 3601").is_err());
+    }
+
+    #[test]
+    fn test_config_used_when_flag_absent() {
+        let args = args_with_config(
+            &["gh-discussion-export", "1"],
+            "timezone = \"Asia/Tokyo\"\n\
+             author-links = true\n\
+             max-retries = 7\n\
+             body-format = \"html\"\n\
+             fetch-parallel = 4\n\
+             exclude-author = [\"renovate\", \"dependabot\"]\n\
+             github-host = \"ghe.example.com\"\n",
+        );
+        assert_eq!(args.timezone, Some(chrono_tz::Asia::Tokyo));
+        assert!(args.author_links);
+        assert_eq!(args.max_retries, 7);
+        assert_eq!(args.fetch_parallel, Some(4));
+        assert_eq!(args.exclude_author, ["renovate", "dependabot"]);
+        assert_eq!(args.body_format, BodyFormat::Html);
+        assert_eq!(args.github_host.as_deref(), Some("ghe.example.com"));
+        // Keys absent from the config keep their built-in defaults
+        assert!(!args.toc);
+        assert_eq!(args.retry_max_wait, 60);
+    }
+
+    #[test]
+    fn test_config_ignored_when_flag_present() {
+        let args = args_with_config(
+            &[
+                "gh-discussion-export",
+                "--timezone",
+                "UTC",
+                "--max-retries",
+                "3",
+                "--format",
+                "markdown",
+                "--exclude-author",
+                "octocat",
+                "1",
+            ],
+            "timezone = \"Asia/Tokyo\"\n\
+             max-retries = 7\n\
+             exclude-author = [\"renovate\"]\n\
+             format = \"json\"\n",
+        );
+        assert_eq!(args.timezone, Some(chrono_tz::UTC));
+        assert_eq!(args.max_retries, 3);
+        assert_eq!(args.format, OutputFormat::Markdown);
+        // Command-line logins replace the configured list
+        assert_eq!(args.exclude_author, ["octocat"]);
+    }
+
+    #[test]
+    fn test_config_dropped_when_conflicting_with_flag() {
+        for (argv, config) in [
+            (&["--stdout"][..], "output-dir = \"exports\""),
+            (&["--split-files"], "toc = true"),
+            (&["--append"], "format = \"json\""),
+        ] {
+            let mut full = vec!["gh-discussion-export", "1"];
+            full.extend_from_slice(argv);
+            let args = args_with_config(&full, &format!("{}\nauthor-links = true\n", config));
+            assert!(args.validate().is_ok(), "{:?} with {:?}", argv, config);
+            assert!(!args.toc);
+            assert_eq!(args.format, OutputFormat::Markdown);
+            // Values that do not conflict still apply
+            assert!(args.author_links, "{:?}", argv);
+        }
+        let args = args_with_config(
+            &["gh-discussion-export", "1", "--stdout"],
+            "output-dir = \"exports\"\n",
+        );
+        assert_eq!(args.output_dir, None);
+
+        // Conflicts within the configuration file are still reported
+        let args = args_with_config(
+            &["gh-discussion-export", "1"],
+            "format = \"json\"\nstats = true\n",
+        );
+        assert!(args.validate().is_err());
+    }
+
+    #[test]
+    fn test_config_boolean_turned_off_by_negation() {
+        let args = args_with_config(
+            &["gh-discussion-export", "1", "--no-toc", "--no-author-links"],
+            "toc = true\nauthor-links = true\nstats = true\n",
+        );
+        assert!(!args.toc);
+        assert!(!args.author_links);
+        assert!(args.stats);
+
+        // The last of a flag and its negation wins
+        let args = args_with_config(
+            &["gh-discussion-export", "1", "--no-toc", "--toc"],
+            "toc = false\n",
+        );
+        assert!(args.toc);
+        let args = args_with_config(&["gh-discussion-export", "1", "--toc", "--no-toc"], "");
+        assert!(!args.toc);
+    }
+
+    #[test]
+    fn test_config_paths() {
+        assert_eq!(
+            config_paths(Some("/xdg".into()), Some("/home/user".into())),
+            vec![
+                PathBuf::from(CONFIG_FILE_NAME),
+                PathBuf::from("/xdg").join(CONFIG_FILE_NAME)
+            ]
+        );
+        assert_eq!(
+            config_paths(Some("relative".into()), Some("/home/user".into())),
+            vec![
+                PathBuf::from(CONFIG_FILE_NAME),
+                PathBuf::from("/home/user/.config").join(CONFIG_FILE_NAME)
+            ]
+        );
+        assert_eq!(
+            config_paths(None, None),
+            vec![PathBuf::from(CONFIG_FILE_NAME)]
+        );
+    }
+}
@@ -1,5 +1,10 @@
 use thiserror::Error;
 
+use crate::models::Discussion;
+
+/// Exit code for invalid command-line usage (`EX_USAGE` from sysexits.h)
+pub const EXIT_USAGE: i32 = 64;
+
 /// Application-specific error types
 #[derive(Error, Debug)]
 pub enum Error {
@@ -15,10 +20,42 @@ pub enum Error {
     #[error("Invalid arguments: {0}")]
     InvalidArgs(String),
 
+    /// Invalid configuration file
+    #[error("Invalid configuration file: {0}")]
+    Config(String),
+
+    /// Invalid `--template` file
+    #[error("Invalid template: {0}")]
+    Template(String),
+
     /// I/O error
     #[error("I/O error: {0}")]
     Io(#[from] std::io::Error),
 
+    /// Repository or discussion does not exist (or is not visible to the token)
+    #[error("Not found: {0}")]
+    NotFound(String),
+
+    /// The repository does not exist or is not visible to the token
+    #[error(
+        "Repository {owner}/{repo} not found or not accessible. Check --repo (or the discussion URL) and that your token can access it."
+    )]
+    RepositoryNotFound { owner: String, repo: String },
+
+    /// The repository exists but has no discussion with this number
+    #[error(
+        "Discussion #{number} not found in {owner}/{repo}. Check the discussion number (and that discussions are enabled)."
+    )]
+    DiscussionNotFound {
+        owner: String,
+        repo: String,
+        number: u64,
+    },
+
+    /// Output file already exists and `--force` was not given
+    #[error("Output file already exists: {0}. Use --force to overwrite it.")]
+    OutputExists(String),
+
     /// HTTP request error
     #[error("HTTP request failed: {0}")]
     Http(String),
@@ -31,9 +68,18 @@ pub enum Error {
     #[error("Failed to parse response: {0}")]
     JsonParse(String),
 
+    /// Output serialization error
+    #[error("Failed to serialize output: {0}")]
+    Serialize(String),
+
     /// Rate limit exceeded
+    ///
+    /// `retry_after` is the wait time suggested by the `Retry-After` or
+    /// `X-RateLimit-Reset` response headers, if present.
     #[error("GitHub API rate limit exceeded. Please wait before trying again.")]
-    RateLimit,
+    RateLimit {
+        retry_after: Option<std::time::Duration>,
+    },
 
     /// Permission denied
     #[error("Permission denied: {0}")]
@@ -42,6 +88,56 @@ pub enum Error {
     /// API invariant violation - indicates API returned inconsistent state
     #[error("API invariant violation: {0}")]
     ApiInvariant(String),
+
+    /// Fetching a page of comments or replies failed
+    ///
+    /// `comment_id` is the node ID of the comment whose replies were being
+    /// fetched, or `None` for the comments of the discussion. `after` is the
+    /// cursor of the last page fetched successfully (`None` if it was the
+    /// first page). When the comments failed, `partial` holds the discussion
+    /// with the comments fetched before the failure, so it can be written and
+    /// the rest appended by resuming with `--append --after-comment-cursor`.
+    #[error("{source}")]
+    PaginationFailed {
+        comment_id: Option<String>,
+        after: Option<String>,
+        source: Box<Error>,
+        partial: Option<Box<Discussion>>,
+    },
+}
+
+impl Error {
+    /// Process exit code for this error
+    ///
+    /// | Code | Meaning |
+    /// |------|---------|
+    /// | 1 | Other failure (HTTP, GraphQL, parsing, serialization) |
+    /// | 2 | Authentication failure (`gh` missing, invalid token) |
+    /// | 3 | Rate limit exceeded |
+    /// | 4 | Repository or resource not found |
+    /// | 5 | I/O error, or the output file already exists |
+    /// | 6 | Permission denied |
+    /// | 7 | Discussion not found in an existing repository |
+    /// | 64 | Invalid command-line arguments, configuration file, or template |
+    ///
+    /// `PaginationFailed` uses the exit code of the underlying error.
+    pub fn exit_code(&self) -> i32 {
+        match self {
+            Error::PaginationFailed { source, .. } => source.exit_code(),
+            Error::GitHubCliNotFound | Error::Authentication => 2,
+            Error::RateLimit { .. } => 3,
+            Error::NotFound(_) | Error::RepositoryNotFound { .. } => 4,
+            Error::Io(_) | Error::OutputExists(_) => 5,
+            Error::PermissionDenied(_) => 6,
+            Error::DiscussionNotFound { .. } => 7,
+            Error::InvalidArgs(_) | Error::Config(_) | Error::Template(_) => EXIT_USAGE,
+            Error::Http(_)
+            | Error::GraphQL(_)
+            | Error::JsonParse(_)
+            | Error::Serialize(_)
+            | Error::ApiInvariant(_) => 1,
+        }
+    }
 }
 
 /// Convenient Result type alias for application errors
@@ -89,6 +185,21 @@ mod tests {
         assert!(matches!(err, Error::Io(_)));
     }
 
+    #[test]
+    fn test_error_not_found_display() {
+        let err = Error::NotFound("discussion #123 in owner/repo".to_string());
+        assert_eq!(err.to_string(), "Not found: discussion #123 in owner/repo");
+    }
+
+    #[test]
+    fn test_error_output_exists_display() {
+        let err = Error::OutputExists("123-discussion.md".to_string());
+        assert_eq!(
+            err.to_string(),
+            "Output file already exists: 123-discussion.md. Use --force to overwrite it."
+        );
+    }
+
     #[test]
     fn test_result_type_alias() {
         fn test_function() -> Result<String> {
@@ -126,9 +237,43 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_error_serialize_display() {
+        let err = Error::Serialize("key must be a string".to_string());
+        assert_eq!(
+            err.to_string(),
+            "Failed to serialize output: key must be a string"
+        );
+    }
+
+    #[test]
+    fn test_error_repository_not_found_display() {
+        let err = Error::RepositoryNotFound {
+            owner: "owner".to_string(),
+            repo: "missing".to_string(),
+        };
+        assert_eq!(
+            err.to_string(),
+            "Repository owner/missing not found or not accessible. Check --repo (or the discussion URL) and that your token can access it."
+        );
+    }
+
+    #[test]
+    fn test_error_discussion_not_found_display() {
+        let err = Error::DiscussionNotFound {
+            owner: "owner".to_string(),
+            repo: "repo".to_string(),
+            number: 999,
+        };
+        assert_eq!(
+            err.to_string(),
+            "Discussion #999 not found in owner/repo. Check the discussion number (and that discussions are enabled)."
+        );
+    }
+
     #[test]
     fn test_error_rate_limit_display() {
-        let err = Error::RateLimit;
+        let err = Error::RateLimit { retry_after: None };
         assert_eq!(
             err.to_string(),
             "GitHub API rate limit exceeded. Please wait before trying again."
@@ -152,4 +297,65 @@ mod tests {
             "API invariant violation: hasNextPage was true but endCursor was null"
         );
     }
+
+    #[test]
+    fn test_error_pagination_failed_display() {
+        let err = Error::PaginationFailed {
+            comment_id: Some("C_1".to_string()),
+            after: Some("cursor".to_string()),
+            source: Box::new(Error::Http("Connection failed".to_string())),
+            partial: None,
+        };
+        assert_eq!(err.to_string(), "HTTP request failed: Connection failed");
+        assert!(std::error::Error::source(&err).is_some());
+    }
+
+    #[test]
+    fn test_exit_codes() {
+        let io_err = std::io::Error::new(std::io::ErrorKind::NotFound, "test file");
+        let cases = [
+            (Error::GitHubCliNotFound, 2),
+            (Error::Authentication, 2),
+            (Error::RateLimit { retry_after: None }, 3),
+            (Error::NotFound("discussion".to_string()), 4),
+            (
+                Error::RepositoryNotFound {
+                    owner: "owner".to_string(),
+                    repo: "repo".to_string(),
+                },
+                4,
+            ),
+            (
+                Error::DiscussionNotFound {
+                    owner: "owner".to_string(),
+                    repo: "repo".to_string(),
+                    number: 999,
+                },
+                7,
+            ),
+            (Error::Io(io_err), 5),
+            (Error::OutputExists("out.md".to_string()), 5),
+            (Error::PermissionDenied("denied".to_string()), 6),
+            (Error::InvalidArgs("bad".to_string()), 64),
+            (Error::Config("bad".to_string()), 64),
+            (Error::Template("bad".to_string()), 64),
+            (Error::Http("failed".to_string()), 1),
+            (Error::GraphQL("failed".to_string()), 1),
+            (Error::JsonParse("failed".to_string()), 1),
+            (Error::Serialize("failed".to_string()), 1),
+            (Error::ApiInvariant("failed".to_string()), 1),
+            (
+                Error::PaginationFailed {
+                    comment_id: None,
+                    after: Some("cursor".to_string()),
+                    source: Box::new(Error::RateLimit { retry_after: None }),
+                    partial: None,
+                },
+                3,
+            ),
+        ];
+        for (err, code) in cases {
+            assert_eq!(err.exit_code(), code, "exit code for {:?}", err);
+        }
+    }
 }
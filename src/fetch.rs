@@ -1,14 +1,170 @@
-use crate::client::GitHubClient;
+use crate::client::{GitHubClient, graphql_error};
 use crate::error::{Error, Result};
-use crate::graphql::{COMMENTS_QUERY, DISCUSSION_QUERY, REPLIES_QUERY};
-use crate::models::{Comment, Discussion, Reply};
+use crate::graphql::{COMMENTS_QUERY, DISCUSSION_QUERY, MAX_PAGE_SIZE, REPLIES_QUERY};
+use crate::models::{Author, Comment, Discussion, Reply};
+use crate::progress::ProgressReporter;
+use chrono::{DateTime, Utc};
+use log::{debug, trace};
 use serde_json::Value;
 
+/// Upper bound for concurrent reply fetches, to stay clear of GitHub's
+/// secondary rate limits on concurrent requests
+pub const MAX_FETCH_PARALLEL: usize = 8;
+
+/// Options controlling how a discussion is fetched
+#[derive(Debug, Clone, Default)]
+pub struct FetchOptions {
+    /// Show comment/reply fetch progress on stderr
+    pub show_progress: bool,
+    /// Only keep comments created at or after this time
+    pub since: Option<DateTime<Utc>>,
+    /// Only keep comments created at or before this time
+    pub until: Option<DateTime<Utc>>,
+    /// Comments or replies requested per page (`None` for the maximum, 100)
+    pub page_size: Option<u32>,
+    /// Comments whose remaining replies are fetched concurrently (`None` for one at a time)
+    pub parallel: Option<usize>,
+    /// Also fetch the rendered `bodyHTML` of the post, comments, and replies
+    pub body_html: bool,
+    /// Drop comments and replies whose trimmed body has fewer characters
+    pub min_comment_length: Option<usize>,
+    /// Drop comments and replies by these logins (case-insensitive)
+    pub exclude_authors: Vec<String>,
+    /// Drop comments and replies by bots
+    pub exclude_bots: bool,
+    /// Only keep comments and replies by these logins (case-insensitive; all
+    /// authors when empty). Exclusions take precedence.
+    pub only_authors: Vec<String>,
+    /// Only fetch the comments after this comments cursor, to resume an
+    /// export that failed with `Error::PaginationFailed`
+    pub after_comment_cursor: Option<String>,
+    /// Warn on stderr when the number of fetched comments or replies differs
+    /// from the `totalCount` reported by the API
+    pub verify_counts: bool,
+    /// Keep going when the remaining replies of a comment cannot be fetched,
+    /// instead of failing the whole fetch
+    pub lenient: bool,
+}
+
+impl FetchOptions {
+    /// Page size to request, clamped to the 1..=100 range GitHub accepts
+    pub fn page_size(&self) -> u32 {
+        self.page_size
+            .unwrap_or(MAX_PAGE_SIZE)
+            .clamp(1, MAX_PAGE_SIZE)
+    }
+
+    /// Concurrent reply fetches, clamped to 1..=MAX_FETCH_PARALLEL
+    pub fn parallel(&self) -> usize {
+        self.parallel.unwrap_or(1).clamp(1, MAX_FETCH_PARALLEL)
+    }
+}
+
+/// Check whether a timestamp falls within an optional, inclusive date range
+pub(crate) fn in_date_range(
+    timestamp: &DateTime<Utc>,
+    since: Option<&DateTime<Utc>>,
+    until: Option<&DateTime<Utc>>,
+) -> bool {
+    since.is_none_or(|since| timestamp >= since) && until.is_none_or(|until| timestamp <= until)
+}
+
+/// Check whether a body is long enough to keep under an optional minimum
+///
+/// Counts the characters of the body with surrounding whitespace trimmed, so
+/// `"  +1\n"` has length 2.
+pub(crate) fn meets_min_length(body: &str, min_length: Option<usize>) -> bool {
+    min_length.is_none_or(|min| body.trim().chars().count() >= min)
+}
+
+/// Strip a trailing `[bot]` (in any case) from a login
+fn strip_bot_suffix(login: &str) -> &str {
+    const SUFFIX: &str = "[bot]";
+    login
+        .len()
+        .checked_sub(SUFFIX.len())
+        .filter(|&i| {
+            login
+                .get(i..)
+                .is_some_and(|s| s.eq_ignore_ascii_case(SUFFIX))
+        })
+        .map_or(login, |i| &login[..i])
+}
+
+/// Check whether an author is filtered out by the excluded logins or bots
+///
+/// Logins are compared case-insensitively, ignoring a trailing `[bot]`: the
+/// API reports the `github-actions[bot]` app as `github-actions`, so either
+/// spelling matches it. With `exclude_bots`, `Bot` actors and logins ending
+/// in `[bot]` are excluded. Content of deleted accounts is never excluded.
+pub(crate) fn is_excluded_author(
+    author: Option<&Author>,
+    exclude_authors: &[String],
+    exclude_bots: bool,
+) -> bool {
+    let Some(login) = author.and_then(|a| a.login.as_deref()) else {
+        return false;
+    };
+    let is_bot = author.and_then(|a| a.typename.as_deref()) == Some("Bot")
+        || strip_bot_suffix(login).len() < login.len();
+    (exclude_bots && is_bot)
+        || exclude_authors
+            .iter()
+            .any(|excluded| login_matches(excluded, login))
+}
+
+/// Check whether an author is among the included logins
+///
+/// Everyone is included when `only_authors` is empty. Logins are compared
+/// like in `is_excluded_author`. Content of deleted accounts is only
+/// included when the list is empty.
+pub(crate) fn is_included_author(author: Option<&Author>, only_authors: &[String]) -> bool {
+    if only_authors.is_empty() {
+        return true;
+    }
+    author
+        .and_then(|a| a.login.as_deref())
+        .is_some_and(|login| {
+            only_authors
+                .iter()
+                .any(|included| login_matches(included, login))
+        })
+}
+
+/// Check whether content by an author is kept under the author filters
+///
+/// Excluded authors are dropped even when they are also included.
+pub(crate) fn keeps_author(author: Option<&Author>, options: &FetchOptions) -> bool {
+    !is_excluded_author(author, &options.exclude_authors, options.exclude_bots)
+        && is_included_author(author, &options.only_authors)
+}
+
+/// Compare two logins case-insensitively, ignoring a trailing `[bot]`
+fn login_matches(pattern: &str, login: &str) -> bool {
+    strip_bot_suffix(pattern).eq_ignore_ascii_case(strip_bot_suffix(login))
+}
+
+/// Compare the number of nodes fetched from a connection with its `totalCount`
+///
+/// `fetched` includes `null` nodes (deleted content), which `totalCount`
+/// counts too. Returns a warning describing the mismatch, or `None` if the
+/// counts agree or the API reported no `totalCount`.
+pub(crate) fn count_mismatch(
+    what: &str,
+    total_count: Option<u64>,
+    fetched: usize,
+) -> Option<String> {
+    let total = total_count?;
+    (total != fetched as u64)
+        .then(|| format!("{}: totalCount is {}, fetched {}", what, total, fetched))
+}
+
 /// Response structure for comments query
 #[derive(Debug)]
 struct CommentsResponse {
     nodes: Option<Vec<Option<Comment>>>,
     page_info: crate::models::PageInfo,
+    total_count: Option<u64>,
 }
 
 /// Response structure for replies query
@@ -32,8 +188,10 @@ struct RepliesResponse {
 /// # Behavior
 /// - Fetches discussion metadata using DISCUSSION_QUERY
 /// - Extracts discussion ID from response
-/// - Fetches all comments using pagination
-/// - For each comment, fetches all replies using pagination
+/// - Keeps the first page of comments returned with the discussion and
+///   fetches further pages only when there are more
+/// - For each comment, keeps the first page of replies returned with it and
+///   fetches further pages only when there are more
 /// - Replaces null authors with `<deleted>` placeholder
 /// - Sorts comments by createdAt ascending
 /// - Sorts replies for each comment by createdAt ascending
@@ -43,57 +201,202 @@ pub fn fetch_discussion(
     owner: &str,
     repo: &str,
     number: u64,
+) -> Result<Discussion> {
+    fetch_discussion_with_options(client, owner, repo, number, &FetchOptions::default())
+}
+
+/// Fetch a complete discussion with all comments and replies using the given options
+///
+/// Behaves like `fetch_discussion`; additionally reports progress on stderr
+/// when `options.show_progress` is set.
+///
+/// With `options.since` / `options.until`, only comments created within the
+/// (inclusive) range are kept. Replies follow their parent comment: all
+/// replies of a kept comment are included regardless of their own date.
+/// Replies of dropped comments are not fetched.
+///
+/// With `options.min_comment_length`, comments and replies whose trimmed
+/// body is shorter are dropped; replies of a dropped comment are dropped
+/// with it. The original post is never filtered.
+///
+/// With `options.exclude_authors` / `options.exclude_bots`, comments and
+/// replies by those authors (see `is_excluded_author`) are dropped the same
+/// way, together with the replies of a dropped comment. With
+/// `options.only_authors`, comments and replies by anyone else are dropped
+/// too, except that a comment by someone else is kept as context for its
+/// included replies; an author both included and excluded is dropped (see
+/// `keeps_author`).
+///
+/// With `options.after_comment_cursor`, the first page of comments returned
+/// with the discussion is discarded and only the comments after the cursor
+/// are fetched. If a page of comments or replies fails, the error is
+/// `Error::PaginationFailed` carrying the cursor of the last page fetched.
+/// For a failed page of comments, it also carries the discussion with the
+/// comments fetched before it, processed like a complete fetch.
+///
+/// With `options.verify_counts`, a warning is printed on stderr for each
+/// connection whose fetched node count (before any filtering) differs from
+/// its `totalCount`. Comments are not checked when resuming after a cursor.
+///
+/// With `options.lenient`, a comment whose further reply pages fail to fetch
+/// keeps the replies fetched before the failure, and a warning is printed on
+/// stderr instead of failing the fetch.
+pub fn fetch_discussion_with_options(
+    client: &GitHubClient,
+    owner: &str,
+    repo: &str,
+    number: u64,
+    options: &FetchOptions,
 ) -> Result<Discussion> {
     // Step 1: Fetch discussion metadata (task 4.2)
     let variables = serde_json::json!({
         "owner": owner,
         "repo": repo,
-        "number": number
+        "number": number,
+        "first": options.page_size(),
+        "bodyHtml": options.body_html
     });
 
+    debug!("Fetching discussion {}/{}#{}", owner, repo, number);
     let mut discussion = client.execute_query(DISCUSSION_QUERY, variables)?;
 
     // Step 2: Get discussion ID from response (task 4.3)
     let discussion_id = discussion.id.clone();
 
-    // Step 3: Fetch all comments using pagination (task 4.4)
-    let mut comments = fetch_all_comments(client, &discussion_id)?;
+    // Step 3: Collect all comments (task 4.4)
+    // DISCUSSION_QUERY returns the first page of comments inline. Those are
+    // kept, and COMMENTS_QUERY is only used to continue after its endCursor.
+    let mut comment_progress = ProgressReporter::new("Fetching comments", options.show_progress);
+    let first_page = std::mem::take(&mut discussion.comments);
+    let mut nodes: Vec<Option<Comment>> = Vec::new();
+    let resume_after = match &options.after_comment_cursor {
+        Some(cursor) => {
+            debug!("Resuming comments after cursor {}", cursor);
+            Some(cursor.clone())
+        }
+        None => {
+            if let Some(total) = first_page.total_count {
+                comment_progress.set_total(total);
+            }
+            nodes.extend(first_page.nodes.unwrap_or_default());
+            comment_progress.inc(nodes.len() as u64);
+            next_page_cursor(&first_page.page_info)?
+        }
+    };
+    let mut comments_error = None;
+    if let Some(after) = resume_after {
+        match fetch_comments_after(
+            client,
+            &discussion_id,
+            Some(after),
+            options,
+            &mut comment_progress,
+            &mut nodes,
+        ) {
+            Ok(()) => {}
+            // Keep the comments fetched so far for a partial export
+            Err(e @ Error::PaginationFailed { .. }) => comments_error = Some(e),
+            Err(e) => return Err(e),
+        }
+    }
+    comment_progress.finish();
+    // Warnings about counts and (with `options.lenient`) incomplete replies
+    let mut count_warnings = Vec::new();
+    if options.verify_counts && options.after_comment_cursor.is_none() && comments_error.is_none() {
+        count_warnings.extend(count_mismatch(
+            "Comments",
+            first_page.total_count,
+            nodes.len(),
+        ));
+    }
+    let mut comments: Vec<Comment> = nodes.into_iter().flatten().collect();
+    comments.retain(|c| {
+        in_date_range(
+            &c.created_at,
+            options.since.as_ref(),
+            options.until.as_ref(),
+        ) && meets_min_length(&c.body, options.min_comment_length)
+            && !is_excluded_author(
+                c.author.as_ref(),
+                &options.exclude_authors,
+                options.exclude_bots,
+            )
+    });
+
+    // Step 4: Complete the replies of each comment (task 4.5)
+    // The comments pages already return the first page of reply nodes. Those are
+    // kept, and REPLIES_QUERY is only used to continue after the first page's
+    // endCursor for comments with more replies than fit on it.
+    let mut pending = Vec::new();
+    for (index, comment) in comments.iter().enumerate() {
+        if let Some(after) = next_page_cursor(&comment.replies.page_info)? {
+            pending.push((index, after));
+        }
+    }
+    let mut reply_progress =
+        ProgressReporter::new("Fetching replies (comments)", options.show_progress);
+    reply_progress.set_total(pending.len() as u64);
+    let remaining = fetch_remaining_replies(
+        client,
+        &comments,
+        &pending,
+        options,
+        &mut reply_progress,
+        &mut count_warnings,
+    )?;
+    reply_progress.finish();
+    for ((index, _), more) in pending.iter().zip(remaining) {
+        let nodes = comments[*index].replies.nodes.get_or_insert_with(Vec::new);
+        nodes.extend(more);
+    }
 
-    // Step 4: For each comment, fetch all replies if needed (task 4.5)
-    // Optimization: COMMENTS_QUERY now fetches the first page of reply nodes.
-    // We only call fetch_all_replies if there are actual replies to fetch.
     for comment in &mut comments {
-        let has_replies = comment
+        if options.verify_counts {
+            count_warnings.extend(count_mismatch(
+                &format!("Replies of comment {}", comment.id),
+                comment.replies.total_count,
+                comment.replies.nodes.as_ref().map_or(0, Vec::len),
+            ));
+        }
+        // Drop null, too short, and excluded reply nodes
+        let replies: Vec<Reply> = comment
             .replies
             .nodes
-            .as_ref()
-            .is_some_and(|nodes| nodes.iter().any(|r| r.is_some()))
-            || comment.replies.page_info.has_next_page;
-
-        if has_replies {
-            let comment_id = comment.id.clone();
-            let replies = fetch_all_replies(client, &comment_id)?;
-
-            // Update the comment's replies with the fetched ones
-            comment.replies.nodes = if replies.is_empty() {
-                None
-            } else {
-                Some(replies.into_iter().map(Some).collect())
-            };
-            // Reset page_info to indicate no more pages since we've fetched all replies
-            comment.replies.page_info = crate::models::PageInfo {
-                has_next_page: false,
-                end_cursor: None,
-            };
-        }
-        // If no replies, the initial fetch already set nodes to None and page_info correctly
+            .take()
+            .unwrap_or_default()
+            .into_iter()
+            .flatten()
+            .filter(|r| {
+                meets_min_length(&r.body, options.min_comment_length)
+                    && keeps_author(r.author.as_ref(), options)
+            })
+            .collect();
+        comment.replies.nodes = if replies.is_empty() {
+            None
+        } else {
+            Some(replies.into_iter().map(Some).collect())
+        };
+        // Reset page_info to indicate no more pages since we've fetched all replies
+        comment.replies.page_info = crate::models::PageInfo {
+            has_next_page: false,
+            end_cursor: None,
+        };
+    }
+    // Comments by authors outside `options.only_authors` only stay as context
+    // for their included replies
+    comments.retain(|c| {
+        is_included_author(c.author.as_ref(), &options.only_authors) || c.replies.nodes.is_some()
+    });
+
+    for warning in &count_warnings {
+        eprintln!("Warning: {}", warning);
     }
 
     // Step 5: Replace null authors with `<deleted>` placeholder (task 4.6)
     replace_deleted_authors(&mut discussion, &mut comments)?;
 
     // Step 6: Sort comments by createdAt ascending (task 4.7)
-    comments.sort_by(|a, b| a.created_at.cmp(&b.created_at));
+    comments.sort_by_key(|a| a.created_at);
 
     // Step 7: Sort replies for each comment by createdAt ascending (task 4.8)
     for comment in &mut comments {
@@ -119,20 +422,101 @@ pub fn fetch_discussion(
         end_cursor: None,
     };
 
+    if let Some(Error::PaginationFailed {
+        comment_id,
+        after,
+        source,
+        ..
+    }) = comments_error
+    {
+        return Err(Error::PaginationFailed {
+            comment_id,
+            after,
+            source,
+            partial: Some(Box::new(discussion)),
+        });
+    }
     Ok(discussion)
 }
 
+/// Fetch the replies after the first page for each pending comment
+///
+/// `pending` pairs an index into `comments` with the cursor to continue
+/// after. Up to `options.parallel()` comments are fetched at a time, each on
+/// its own thread. Returns the reply nodes (including `null` ones) in the
+/// order of `pending`. With `options.lenient`, a comment whose replies fail
+/// keeps the replies fetched before the failure, and a warning is added to
+/// `warnings` instead of failing the whole fetch.
+fn fetch_remaining_replies(
+    client: &GitHubClient,
+    comments: &[Comment],
+    pending: &[(usize, String)],
+    options: &FetchOptions,
+    progress: &mut ProgressReporter,
+    warnings: &mut Vec<String>,
+) -> Result<Vec<Vec<Option<Reply>>>> {
+    let fetch = |(index, after): &(usize, String)| {
+        let mut replies = Vec::new();
+        let result = fetch_replies_after(
+            client,
+            &comments[*index].id,
+            Some(after.clone()),
+            options,
+            &mut replies,
+        );
+        (replies, result)
+    };
+
+    let mut all_replies = Vec::with_capacity(pending.len());
+    for batch in pending.chunks(options.parallel()) {
+        let results: Vec<(Vec<Option<Reply>>, Result<()>)> = if batch.len() == 1 {
+            vec![fetch(&batch[0])]
+        } else {
+            std::thread::scope(|scope| {
+                let handles: Vec<_> = batch
+                    .iter()
+                    .map(|entry| scope.spawn(move || fetch(entry)))
+                    .collect();
+                handles
+                    .into_iter()
+                    .map(|handle| {
+                        handle
+                            .join()
+                            .unwrap_or_else(|e| std::panic::resume_unwind(e))
+                    })
+                    .collect()
+            })
+        };
+        for ((index, _), (replies, result)) in batch.iter().zip(results) {
+            match result {
+                Ok(()) => {}
+                Err(e) if options.lenient => warnings.push(format!(
+                    "Replies of comment {} are incomplete, keeping the {} fetched after the first page: {}",
+                    comments[*index].id,
+                    replies.len(),
+                    e
+                )),
+                Err(e) => return Err(e),
+            }
+            all_replies.push(replies);
+            progress.inc(1);
+        }
+    }
+    Ok(all_replies)
+}
+
 /// Replace null authors with `<deleted>` placeholder
 ///
 /// This helper function handles task 4.6 by replacing null author fields
 /// with Author structs containing login: Some("<deleted>")
 fn replace_deleted_authors(discussion: &mut Discussion, comments: &mut [Comment]) -> Result<()> {
-    use crate::models::Author;
+    use crate::models::{Author, DELETED_AUTHOR};
 
     // Handle discussion author
     if discussion.author.is_none() {
         discussion.author = Some(Author {
-            login: Some("<deleted>".to_string()),
+            login: Some(DELETED_AUTHOR.to_string()),
+            typename: None,
         });
     }
 
@@ -140,7 +524,8 @@ fn replace_deleted_authors(discussion: &mut Discussion, comments: &mut [Comment]
     for comment in comments {
         if comment.author.is_none() {
             comment.author = Some(Author {
-                login: Some("<deleted>".to_string()),
+                login: Some(DELETED_AUTHOR.to_string()),
+                typename: None,
             });
         }
 
@@ -151,7 +536,8 @@ fn replace_deleted_authors(discussion: &mut Discussion, comments: &mut [Comment]
                     && r.author.is_none()
                 {
                     r.author = Some(Author {
-                        login: Some("<deleted>".to_string()),
+                        login: Some(DELETED_AUTHOR.to_string()),
+                        typename: None,
                     });
                 }
             }
@@ -161,42 +547,90 @@ fn replace_deleted_authors(discussion: &mut Discussion, comments: &mut [Comment]
     Ok(())
 }
 
-/// Fetch all comments for a discussion using cursor-based pagination
+/// Cursor to continue after a page, or `None` if it was the last page
+///
+/// Returns Error::ApiInvariant if `hasNextPage` is true but `endCursor` is
+/// null, which would otherwise refetch the first page forever.
+fn next_page_cursor(page_info: &crate::models::PageInfo) -> Result<Option<String>> {
+    if !page_info.has_next_page {
+        return Ok(None);
+    }
+    match &page_info.end_cursor {
+        Some(cursor) => Ok(Some(cursor.clone())),
+        None => Err(Error::ApiInvariant(
+            "hasNextPage was true but endCursor was null".to_string(),
+        )),
+    }
+}
+
+/// Fetch the comments for a discussion using cursor-based pagination
 ///
 /// # Arguments
 /// * `client` - The GitHubClient to use for queries
 /// * `discussion_id` - The node ID of the discussion
+/// * `after` - Cursor of an already-fetched page (`None` to start from the first comment)
+/// * `options` - Page size (comments and inline replies per page) and whether to fetch `bodyHTML`
+/// * `progress` - Reporter advanced by the number of comments in each page
+/// * `all_comments` - Receives the comment nodes of each page as it is fetched
 ///
 /// # Returns
-/// A vector of all comments for the discussion
+/// Ok once all comment nodes for the discussion after `after` have been
+/// appended to `all_comments`, with `null` nodes (deleted comments) kept as `None`
 ///
 /// # Behavior
-/// - Starts with `after: null` to fetch the first page
+/// - Starts with the given `after` cursor
 /// - Continues fetching while `pageInfo.hasNextPage` is true
 /// - Uses `pageInfo.endCursor` as the `after` parameter for subsequent requests
 /// - Accumulates comments across all pages
-/// - Fails immediately on any error (no partial results)
-pub(crate) fn fetch_all_comments(
+/// - Sets the progress total from the first page's `totalCount` when starting from the beginning
+/// - Stops at the first error, with `Error::PaginationFailed` carrying the
+///   cursor of the last page fetched; the comments of the pages fetched
+///   before it stay in `all_comments`
+pub(crate) fn fetch_comments_after(
     client: &GitHubClient,
     discussion_id: &str,
-) -> Result<Vec<Comment>> {
-    let mut all_comments = Vec::new();
-    let mut after: Option<String> = None;
+    mut after: Option<String>,
+    options: &FetchOptions,
+    progress: &mut ProgressReporter,
+    all_comments: &mut Vec<Option<Comment>>,
+) -> Result<()> {
+    let mut page = 0;
 
     loop {
+        page += 1;
         let variables = serde_json::json!({
             "id": discussion_id,
-            "after": after
+            "after": after,
+            "first": options.page_size(),
+            "bodyHtml": options.body_html
         });
 
-        let response = execute_query_raw(client, COMMENTS_QUERY, variables)?;
-        let comments_response = parse_comments_response(response)?;
+        debug!("Fetching comments page {} (after: {:?})", page, after);
+        let comments_response = execute_query_raw(client, COMMENTS_QUERY, variables)
+            .and_then(parse_comments_response)
+            .map_err(|e| Error::PaginationFailed {
+                comment_id: None,
+                after: after.clone(),
+                source: Box::new(e),
+                partial: None,
+            })?;
+        trace!(
+            "Comments page {}: {} nodes, hasNextPage: {}",
+            page,
+            comments_response.nodes.as_ref().map_or(0, Vec::len),
+            comments_response.page_info.has_next_page
+        );
+
+        if after.is_none()
+            && let Some(total) = comments_response.total_count
+        {
+            progress.set_total(total);
+        }
 
-        // Accumulate comments (filter out nulls from nodes array)
+        // Accumulate comment nodes
         if let Some(nodes) = comments_response.nodes {
-            for c in nodes.into_iter().flatten() {
-                all_comments.push(c);
-            }
+            progress.inc(nodes.len() as u64);
+            all_comments.extend(nodes);
         }
 
         // Check if there are more pages
@@ -214,42 +648,72 @@ pub(crate) fn fetch_all_comments(
         }
     }
 
-    Ok(all_comments)
+    Ok(())
 }
 
-/// Fetch all replies for a comment using cursor-based pagination
+/// Fetch the replies for a comment using cursor-based pagination
 ///
 /// # Arguments
 /// * `client` - The GitHubClient to use for queries
 /// * `comment_id` - The node ID of the comment
+/// * `after` - Cursor of an already-fetched page (`None` to start from the first reply)
+/// * `options` - Page size (replies per page) and whether to fetch `bodyHTML`
+///
+/// * `all_replies` - Receives the reply nodes of each page as it is fetched
 ///
 /// # Returns
-/// A vector of all replies for the comment
+/// Ok once all reply nodes for the comment after `after` have been appended
+/// to `all_replies`, with `null` nodes (deleted replies) kept as `None`
 ///
 /// # Behavior
-/// - Starts with `after: null` to fetch the first page
+/// - Starts with the given `after` cursor
 /// - Continues fetching while `pageInfo.hasNextPage` is true
 /// - Uses `pageInfo.endCursor` as the `after` parameter for subsequent requests
 /// - Accumulates replies across all pages
-/// - Fails immediately on any error (no partial results)
-pub(crate) fn fetch_all_replies(client: &GitHubClient, comment_id: &str) -> Result<Vec<Reply>> {
-    let mut all_replies = Vec::new();
-    let mut after: Option<String> = None;
+/// - Stops at the first error, with `Error::PaginationFailed` carrying the
+///   cursor of the last page fetched; the replies of the pages fetched before
+///   it stay in `all_replies`
+pub(crate) fn fetch_replies_after(
+    client: &GitHubClient,
+    comment_id: &str,
+    mut after: Option<String>,
+    options: &FetchOptions,
+    all_replies: &mut Vec<Option<Reply>>,
+) -> Result<()> {
+    let mut page = 0;
 
     loop {
+        page += 1;
         let variables = serde_json::json!({
             "id": comment_id,
-            "after": after
+            "after": after,
+            "first": options.page_size(),
+            "bodyHtml": options.body_html
         });
 
-        let response = execute_query_raw(client, REPLIES_QUERY, variables)?;
-        let replies_response = parse_replies_response(response)?;
+        debug!(
+            "Fetching replies of {} page {} (after: {:?})",
+            comment_id, page, after
+        );
+        let replies_response = execute_query_raw(client, REPLIES_QUERY, variables)
+            .and_then(parse_replies_response)
+            .map_err(|e| Error::PaginationFailed {
+                comment_id: Some(comment_id.to_string()),
+                after: after.clone(),
+                source: Box::new(e),
+                partial: None,
+            })?;
+        trace!(
+            "Replies page {} of {}: {} nodes, hasNextPage: {}",
+            page,
+            comment_id,
+            replies_response.nodes.as_ref().map_or(0, Vec::len),
+            replies_response.page_info.has_next_page
+        );
 
-        // Accumulate replies (filter out nulls from nodes array)
+        // Accumulate reply nodes
         if let Some(nodes) = replies_response.nodes {
-            for r in nodes.into_iter().flatten() {
-                all_replies.push(r);
-            }
+            all_replies.extend(nodes);
         }
 
         // Check if there are more pages
@@ -267,7 +731,7 @@ pub(crate) fn fetch_all_replies(client: &GitHubClient, comment_id: &str) -> Resu
         }
     }
 
-    Ok(all_replies)
+    Ok(())
 }
 
 /// Execute a GraphQL query and return the raw JSON response
@@ -283,15 +747,8 @@ fn execute_query_raw(
     let response = client.execute_query_raw(query, variables)?;
 
     // Check for GraphQL errors
-    if let Some(errors) = response.get("errors").and_then(|e| e.as_array())
-        && !errors.is_empty()
-    {
-        let error_messages: Vec<String> = errors
-            .iter()
-            .filter_map(|e| e.get("message").and_then(|m| m.as_str()))
-            .map(|s| s.to_string())
-            .collect();
-        return Err(Error::GraphQL(error_messages.join("; ")));
+    if let Some(err) = graphql_error(&response) {
+        return Err(err);
     }
 
     Ok(response)
@@ -338,7 +795,13 @@ fn parse_comments_response(response: Value) -> Result<CommentsResponse> {
     let page_info: crate::models::PageInfo = serde_json::from_value(page_info_value.clone())
         .map_err(|e| Error::JsonParse(format!("Failed to parse PageInfo: {}", e)))?;
 
-    Ok(CommentsResponse { nodes, page_info })
+    let total_count = comments.get("totalCount").and_then(|v| v.as_u64());
+
+    Ok(CommentsResponse {
+        nodes,
+        page_info,
+        total_count,
+    })
 }
 
 /// Parse a raw JSON response into a RepliesResponse
@@ -739,6 +1202,1078 @@ mod tests {
         assert_eq!(page2.nodes.unwrap().len(), 1);
     }
 
+    /// Build a COMMENTS_QUERY response page with one comment per id
+    fn comments_page(ids: &[&str], total_count: u64, end_cursor: Option<&str>) -> Value {
+        let nodes: Vec<Value> = ids
+            .iter()
+            .map(|id| {
+                json!({
+                    "id": id,
+                    "databaseId": 1,
+                    "author": {"login": "user"},
+                    "createdAt": "2024-01-01T00:00:00Z",
+                    "body": format!("Body of {}", id),
+                    "replies": {
+                        "nodes": [],
+                        "pageInfo": {"hasNextPage": false, "endCursor": null}
+                    }
+                })
+            })
+            .collect();
+        json!({
+            "data": {
+                "node": {
+                    "comments": {
+                        "totalCount": total_count,
+                        "nodes": nodes,
+                        "pageInfo": {
+                            "hasNextPage": end_cursor.is_some(),
+                            "endCursor": end_cursor
+                        }
+                    }
+                }
+            }
+        })
+    }
+
+    fn utc(s: &str) -> DateTime<Utc> {
+        DateTime::parse_from_rfc3339(s).unwrap().with_timezone(&Utc)
+    }
+
+    #[test]
+    fn test_page_size_clamped() {
+        let page_size = |page_size| {
+            FetchOptions {
+                page_size,
+                ..Default::default()
+            }
+            .page_size()
+        };
+        assert_eq!(page_size(None), 100);
+        assert_eq!(page_size(Some(25)), 25);
+        assert_eq!(page_size(Some(0)), 1);
+        assert_eq!(page_size(Some(500)), 100);
+    }
+
+    #[test]
+    fn test_fetch_discussion_sends_page_size() {
+        use crate::client::MockHttpClient;
+
+        let mut mock_http = MockHttpClient::new();
+        mock_http
+            .expect_post()
+            .withf(|_url, body| body.contains("repository(owner"))
+            .times(1)
+            .returning(|_url, body| {
+                let request: Value = serde_json::from_str(body).unwrap();
+                assert_eq!(request["variables"]["first"], 2);
+                let comments =
+                    comments_page(&["C_1", "C_2"], 3, Some("c2"))["data"]["node"]["comments"]
+                        .clone();
+                Ok(discussion_response(comments).to_string())
+            });
+        mock_http
+            .expect_post()
+            .withf(|_url, body| body.contains("... on Discussion "))
+            .times(1)
+            .returning(|_url, body| {
+                let request: Value = serde_json::from_str(body).unwrap();
+                assert_eq!(request["variables"]["first"], 2);
+                assert_eq!(request["variables"]["after"], "c2");
+                Ok(comments_page(&["C_3"], 3, None).to_string())
+            });
+
+        let client = GitHubClient::new(Box::new(mock_http));
+        let options = FetchOptions {
+            page_size: Some(2),
+            ..Default::default()
+        };
+        let discussion =
+            fetch_discussion_with_options(&client, "owner", "repo", 1, &options).unwrap();
+        assert_eq!(comment_ids(&discussion), ["C_1", "C_2", "C_3"]);
+    }
+
+    #[test]
+    fn test_fetch_discussion_sends_body_html_flag() {
+        use crate::client::MockHttpClient;
+
+        let mut mock_http = MockHttpClient::new();
+        mock_http
+            .expect_post()
+            .withf(|_url, body| body.contains("repository(owner"))
+            .times(1)
+            .returning(|_url, body| {
+                let request: Value = serde_json::from_str(body).unwrap();
+                assert_eq!(request["variables"]["bodyHtml"], true);
+                let comments = comment_with_replies(
+                    vec![reply_node("R_1", "2024-01-01T02:00:00Z")],
+                    Some("r1"),
+                );
+                Ok(discussion_response(comments).to_string())
+            });
+        mock_http
+            .expect_post()
+            .withf(|_url, body| body.contains("on DiscussionComment"))
+            .times(1)
+            .returning(|_url, body| {
+                let request: Value = serde_json::from_str(body).unwrap();
+                assert_eq!(request["variables"]["bodyHtml"], true);
+                let mut reply = reply_node("R_2", "2024-01-01T03:00:00Z");
+                reply["bodyHTML"] = json!("<p>Body of R_2</p>");
+                Ok(json!({
+                    "data": {
+                        "node": {
+                            "replies": {
+                                "nodes": [reply],
+                                "pageInfo": {"hasNextPage": false, "endCursor": null}
+                            }
+                        }
+                    }
+                })
+                .to_string())
+            });
+
+        let client = GitHubClient::new(Box::new(mock_http));
+        let options = FetchOptions {
+            body_html: true,
+            ..Default::default()
+        };
+        let discussion =
+            fetch_discussion_with_options(&client, "owner", "repo", 1, &options).unwrap();
+        let comment = discussion.comments.nodes.unwrap()[0].clone().unwrap();
+        let replies: Vec<_> = comment
+            .replies
+            .nodes
+            .unwrap()
+            .into_iter()
+            .flatten()
+            .collect();
+        assert_eq!(replies[1].body_html.as_deref(), Some("<p>Body of R_2</p>"));
+    }
+
+    #[test]
+    fn test_in_date_range_unbounded() {
+        assert!(in_date_range(&utc("2024-01-15T00:00:00Z"), None, None));
+    }
+
+    #[test]
+    fn test_in_date_range_inclusive_endpoints() {
+        let since = utc("2024-01-10T00:00:00Z");
+        let until = utc("2024-01-20T23:59:59Z");
+
+        assert!(in_date_range(&since, Some(&since), Some(&until)));
+        assert!(in_date_range(&until, Some(&since), Some(&until)));
+        assert!(in_date_range(
+            &utc("2024-01-15T12:00:00Z"),
+            Some(&since),
+            Some(&until)
+        ));
+        assert!(!in_date_range(
+            &utc("2024-01-09T23:59:59Z"),
+            Some(&since),
+            Some(&until)
+        ));
+        assert!(!in_date_range(
+            &utc("2024-01-21T00:00:00Z"),
+            Some(&since),
+            Some(&until)
+        ));
+    }
+
+    #[test]
+    fn test_in_date_range_single_bound() {
+        let bound = utc("2024-01-10T00:00:00Z");
+
+        assert!(in_date_range(
+            &utc("2030-01-01T00:00:00Z"),
+            Some(&bound),
+            None
+        ));
+        assert!(!in_date_range(
+            &utc("2020-01-01T00:00:00Z"),
+            Some(&bound),
+            None
+        ));
+        assert!(in_date_range(
+            &utc("2020-01-01T00:00:00Z"),
+            None,
+            Some(&bound)
+        ));
+        assert!(!in_date_range(
+            &utc("2030-01-01T00:00:00Z"),
+            None,
+            Some(&bound)
+        ));
+    }
+
+    #[test]
+    fn test_meets_min_length_boundary() {
+        assert!(meets_min_length("+1", None));
+        assert!(meets_min_length("thanks", Some(6)));
+        assert!(!meets_min_length("thanks", Some(7)));
+        // Surrounding whitespace does not count, characters are not bytes
+        assert!(!meets_min_length("  +1\n\n", Some(3)));
+        assert!(meets_min_length(" ありがとう ", Some(5)));
+        assert!(!meets_min_length("", Some(1)));
+    }
+
+    #[test]
+    fn test_fetch_discussion_min_comment_length() {
+        use crate::client::MockHttpClient;
+
+        let mut mock_http = MockHttpClient::new();
+        mock_http.expect_post().times(1).returning(|_url, _body| {
+            let comment = |id: &str, body: &str, replies: Vec<Value>| {
+                json!({
+                    "id": id,
+                    "databaseId": 1,
+                    "author": {"login": "user"},
+                    "createdAt": "2024-01-01T01:00:00Z",
+                    "body": body,
+                    "replies": {
+                        "nodes": replies,
+                        "pageInfo": {"hasNextPage": false, "endCursor": null}
+                    }
+                })
+            };
+            let reply = |id: &str, body: &str| {
+                let mut node = reply_node(id, "2024-01-01T02:00:00Z");
+                node["body"] = json!(body);
+                node
+            };
+            let comments = json!({
+                "totalCount": 2,
+                "nodes": [
+                    comment("C_1", "+1", vec![reply("R_1", "Why do you agree?")]),
+                    comment(
+                        "C_2",
+                        "Detailed answer",
+                        vec![reply("R_2", "thanks"), reply("R_3", "Follow-up question")]
+                    )
+                ],
+                "pageInfo": {"hasNextPage": false, "endCursor": null}
+            });
+            Ok(discussion_response(comments).to_string())
+        });
+
+        let client = GitHubClient::new(Box::new(mock_http));
+        let options = FetchOptions {
+            min_comment_length: Some(7),
+            ..Default::default()
+        };
+        let discussion =
+            fetch_discussion_with_options(&client, "owner", "repo", 1, &options).unwrap();
+
+        // The original post is shorter than the minimum but never filtered
+        assert_eq!(discussion.body, "Body");
+        // C_1 is dropped together with its reply
+        assert_eq!(comment_ids(&discussion), ["C_2"]);
+        assert_eq!(reply_ids(&discussion), ["R_3"]);
+    }
+
+    #[test]
+    fn test_is_excluded_author() {
+        let author = |login: &str, typename: &str| Author {
+            login: Some(login.to_string()),
+            typename: Some(typename.to_string()),
+        };
+        let excluded = ["Renovate".to_string(), "github-actions[bot]".to_string()];
+
+        assert!(is_excluded_author(
+            Some(&author("renovate", "User")),
+            &excluded,
+            false
+        ));
+        // `[bot]` is ignored on either side
+        assert!(is_excluded_author(
+            Some(&author("github-actions", "Bot")),
+            &excluded,
+            false
+        ));
+        assert!(is_excluded_author(
+            Some(&author("GitHub-Actions[BOT]", "Bot")),
+            &excluded,
+            false
+        ));
+        assert!(!is_excluded_author(
+            Some(&author("octocat", "User")),
+            &excluded,
+            false
+        ));
+        // Deleted accounts are never excluded
+        assert!(!is_excluded_author(None, &excluded, true));
+    }
+
+    #[test]
+    fn test_is_excluded_author_bots() {
+        let author = |login: &str, typename: Option<&str>| Author {
+            login: Some(login.to_string()),
+            typename: typename.map(str::to_string),
+        };
+
+        assert!(is_excluded_author(
+            Some(&author("dependabot", Some("Bot"))),
+            &[],
+            true
+        ));
+        assert!(is_excluded_author(
+            Some(&author("my-app[bot]", None)),
+            &[],
+            true
+        ));
+        assert!(!is_excluded_author(
+            Some(&author("robot", Some("User"))),
+            &[],
+            true
+        ));
+        assert!(!is_excluded_author(
+            Some(&author("dependabot", Some("Bot"))),
+            &[],
+            false
+        ));
+        assert_eq!(strip_bot_suffix("[bot]"), "");
+        assert_eq!(strip_bot_suffix("bot"), "bot");
+        assert_eq!(strip_bot_suffix("ボット"), "ボット");
+    }
+
+    #[test]
+    fn test_is_included_author() {
+        let author = |login: &str| Author {
+            login: Some(login.to_string()),
+            typename: None,
+        };
+        let included = ["Alice".to_string(), "my-app[bot]".to_string()];
+
+        assert!(is_included_author(Some(&author("alice")), &included));
+        assert!(is_included_author(Some(&author("my-app")), &included));
+        assert!(!is_included_author(Some(&author("bob")), &included));
+        assert!(!is_included_author(None, &included));
+        // An empty list includes everyone
+        assert!(is_included_author(Some(&author("bob")), &[]));
+        assert!(is_included_author(None, &[]));
+    }
+
+    #[test]
+    fn test_keeps_author_exclusion_takes_precedence() {
+        let author = |login: &str, typename: &str| Author {
+            login: Some(login.to_string()),
+            typename: Some(typename.to_string()),
+        };
+        let options = FetchOptions {
+            only_authors: vec!["alice".to_string(), "dependabot".to_string()],
+            exclude_authors: vec!["ALICE".to_string()],
+            exclude_bots: true,
+            ..Default::default()
+        };
+
+        assert!(!keeps_author(Some(&author("alice", "User")), &options));
+        assert!(!keeps_author(Some(&author("dependabot", "Bot")), &options));
+        assert!(!keeps_author(Some(&author("bob", "User")), &options));
+
+        let options = FetchOptions {
+            only_authors: vec!["alice".to_string()],
+            ..Default::default()
+        };
+        assert!(keeps_author(Some(&author("alice", "User")), &options));
+        assert!(keeps_author(
+            Some(&author("bob", "User")),
+            &FetchOptions::default()
+        ));
+    }
+
+    #[test]
+    fn test_fetch_discussion_exclude_authors() {
+        use crate::client::MockHttpClient;
+
+        let mut mock_http = MockHttpClient::new();
+        mock_http.expect_post().times(1).returning(|_url, _body| {
+            let author =
+                |login: &str, typename: &str| json!({"login": login, "__typename": typename});
+            let mut bot_reply = reply_node("R_2", "2024-01-01T02:00:00Z");
+            bot_reply["author"] = author("github-actions", "Bot");
+            let comments = json!({
+                "totalCount": 3,
+                "nodes": [
+                    {
+                        "id": "C_1",
+                        "databaseId": 1,
+                        "author": author("dependabot", "Bot"),
+                        "createdAt": "2024-01-01T01:00:00Z",
+                        "body": "Bumps a dependency",
+                        "replies": {
+                            "nodes": [reply_node("R_1", "2024-01-01T02:00:00Z")],
+                            "pageInfo": {"hasNextPage": false, "endCursor": null}
+                        }
+                    },
+                    {
+                        "id": "C_2",
+                        "databaseId": 2,
+                        "author": author("Spammer", "User"),
+                        "createdAt": "2024-01-01T01:00:00Z",
+                        "body": "Spam",
+                        "replies": {
+                            "nodes": [],
+                            "pageInfo": {"hasNextPage": false, "endCursor": null}
+                        }
+                    },
+                    {
+                        "id": "C_3",
+                        "databaseId": 3,
+                        "author": author("octocat", "User"),
+                        "createdAt": "2024-01-01T01:00:00Z",
+                        "body": "Question",
+                        "replies": {
+                            "nodes": [bot_reply, reply_node("R_3", "2024-01-01T03:00:00Z")],
+                            "pageInfo": {"hasNextPage": false, "endCursor": null}
+                        }
+                    }
+                ],
+                "pageInfo": {"hasNextPage": false, "endCursor": null}
+            });
+            Ok(discussion_response(comments).to_string())
+        });
+
+        let client = GitHubClient::new(Box::new(mock_http));
+        let options = FetchOptions {
+            exclude_authors: vec!["spammer".to_string()],
+            exclude_bots: true,
+            ..Default::default()
+        };
+        let discussion =
+            fetch_discussion_with_options(&client, "owner", "repo", 1, &options).unwrap();
+
+        assert_eq!(comment_ids(&discussion), ["C_3"]);
+        assert_eq!(reply_ids(&discussion), ["R_3"]);
+    }
+
+    #[test]
+    fn test_fetch_discussion_only_authors_keeps_parent_of_included_reply() {
+        use crate::client::MockHttpClient;
+
+        let mut mock_http = MockHttpClient::new();
+        mock_http.expect_post().times(1).returning(|_url, _body| {
+            let author = |login: &str| json!({"login": login, "__typename": "User"});
+            let comment = |id: &str, login: &str, replies: Vec<Value>| {
+                json!({
+                    "id": id,
+                    "databaseId": 1,
+                    "author": author(login),
+                    "createdAt": "2024-01-01T01:00:00Z",
+                    "body": "Comment",
+                    "replies": {
+                        "nodes": replies,
+                        "pageInfo": {"hasNextPage": false, "endCursor": null}
+                    }
+                })
+            };
+            let mut alice_reply = reply_node("R_1", "2024-01-01T02:00:00Z");
+            alice_reply["author"] = author("alice");
+            let mut bob_reply = reply_node("R_2", "2024-01-01T03:00:00Z");
+            bob_reply["author"] = author("bob");
+            let comments = json!({
+                "totalCount": 3,
+                "nodes": [
+                    comment("C_1", "bob", vec![alice_reply, bob_reply.clone()]),
+                    comment("C_2", "bob", vec![bob_reply]),
+                    comment("C_3", "alice", vec![]),
+                ],
+                "pageInfo": {"hasNextPage": false, "endCursor": null}
+            });
+            Ok(discussion_response(comments).to_string())
+        });
+
+        let client = GitHubClient::new(Box::new(mock_http));
+        let options = FetchOptions {
+            only_authors: vec!["alice".to_string()],
+            ..Default::default()
+        };
+        let discussion =
+            fetch_discussion_with_options(&client, "owner", "repo", 1, &options).unwrap();
+
+        // C_1 is kept as context for alice's reply, without bob's reply
+        assert_eq!(comment_ids(&discussion), ["C_1", "C_3"]);
+        assert_eq!(reply_ids(&discussion), ["R_1"]);
+    }
+
+    #[test]
+    fn test_fetch_discussion_resumes_after_comment_cursor() {
+        use crate::client::MockHttpClient;
+
+        let mut mock_http = MockHttpClient::new();
+        mock_http
+            .expect_post()
+            .withf(|_url, body| body.contains("repository(owner"))
+            .times(1)
+            .returning(|_url, _body| {
+                let comments =
+                    comments_page(&["C_1"], 3, Some("c1"))["data"]["node"]["comments"].clone();
+                Ok(discussion_response(comments).to_string())
+            });
+        mock_http
+            .expect_post()
+            .withf(|_url, body| {
+                body.contains("... on Discussion ") && body.contains("\"after\":\"resume_cursor\"")
+            })
+            .times(1)
+            .returning(|_url, _body| Ok(comments_page(&["C_3"], 3, None).to_string()));
+
+        let client = GitHubClient::new(Box::new(mock_http));
+        let options = FetchOptions {
+            after_comment_cursor: Some("resume_cursor".to_string()),
+            ..Default::default()
+        };
+        let discussion =
+            fetch_discussion_with_options(&client, "owner", "repo", 1, &options).unwrap();
+        // The inline first page is not kept when resuming
+        assert_eq!(comment_ids(&discussion), ["C_3"]);
+    }
+
+    #[test]
+    fn test_count_mismatch() {
+        assert_eq!(count_mismatch("Comments", Some(3), 3), None);
+        assert_eq!(count_mismatch("Comments", None, 3), None);
+        assert_eq!(
+            count_mismatch("Comments", Some(3), 2).as_deref(),
+            Some("Comments: totalCount is 3, fetched 2")
+        );
+        assert_eq!(
+            count_mismatch("Replies of comment C_1", Some(0), 1).as_deref(),
+            Some("Replies of comment C_1: totalCount is 0, fetched 1")
+        );
+    }
+
+    #[test]
+    fn test_fetch_all_comments_reports_progress() {
+        use crate::client::MockHttpClient;
+
+        let mut mock_http = MockHttpClient::new();
+        let mut seq = mockall::Sequence::new();
+        mock_http
+            .expect_post()
+            .times(1)
+            .in_sequence(&mut seq)
+            .withf(|_url, body| body.contains("\"after\":null"))
+            .returning(|_url, _body| {
+                Ok(comments_page(&["c1", "c2"], 3, Some("cursor_page2")).to_string())
+            });
+        mock_http
+            .expect_post()
+            .times(1)
+            .in_sequence(&mut seq)
+            .withf(|_url, body| body.contains("\"after\":\"cursor_page2\""))
+            .returning(|_url, _body| Ok(comments_page(&["c3"], 3, None).to_string()));
+
+        let client = GitHubClient::new(Box::new(mock_http));
+        let mut progress = ProgressReporter::hidden("Fetching comments");
+        let mut comments = Vec::new();
+        fetch_comments_after(
+            &client,
+            "discussion_id",
+            None,
+            &FetchOptions::default(),
+            &mut progress,
+            &mut comments,
+        )
+        .unwrap();
+
+        assert_eq!(comments.len(), 3);
+        assert_eq!(progress.total(), Some(3));
+        assert_eq!(progress.current(), 3);
+    }
+
+    #[test]
+    fn test_fetch_discussion_not_found() {
+        use crate::client::MockHttpClient;
+
+        let mut mock_http = MockHttpClient::new();
+        mock_http.expect_post().times(1).returning(|_url, _body| {
+            Ok(json!({"data": {"repository": {"discussion": null}}}).to_string())
+        });
+
+        let client = GitHubClient::new(Box::new(mock_http));
+        match fetch_discussion(&client, "owner", "repo", 999) {
+            Err(Error::DiscussionNotFound {
+                owner,
+                repo,
+                number,
+            }) => assert_eq!(
+                (owner.as_str(), repo.as_str(), number),
+                ("owner", "repo", 999)
+            ),
+            other => panic!("Expected DiscussionNotFound, got {:?}", other.map(|d| d.id)),
+        }
+    }
+
+    /// DISCUSSION_QUERY response for discussion `D_1` with the given first comments page
+    fn discussion_response(comments: Value) -> Value {
+        json!({
+            "data": {
+                "repository": {
+                    "discussion": {
+                        "id": "D_1",
+                        "title": "Discussion",
+                        "number": 1,
+                        "url": "https://github.com/owner/repo/discussions/1",
+                        "createdAt": "2024-01-01T00:00:00Z",
+                        "body": "Body",
+                        "author": {"login": "user"},
+                        "comments": comments
+                    }
+                }
+            }
+        })
+    }
+
+    fn reply_node(id: &str, created_at: &str) -> Value {
+        json!({
+            "id": id,
+            "databaseId": 10,
+            "author": {"login": "user"},
+            "createdAt": created_at,
+            "body": format!("Body of {}", id)
+        })
+    }
+
+    /// Comments page with one comment `C_1` and the given first reply page
+    fn comment_with_replies(replies: Vec<Value>, end_cursor: Option<&str>) -> Value {
+        json!({
+            "totalCount": 1,
+            "nodes": [{
+                "id": "C_1",
+                "databaseId": 1,
+                "author": {"login": "user"},
+                "createdAt": "2024-01-01T01:00:00Z",
+                "body": "Comment",
+                "replies": {
+                    "nodes": replies,
+                    "pageInfo": {
+                        "hasNextPage": end_cursor.is_some(),
+                        "endCursor": end_cursor
+                    }
+                }
+            }],
+            "pageInfo": {"hasNextPage": false, "endCursor": null}
+        })
+    }
+
+    fn comment_ids(discussion: &Discussion) -> Vec<String> {
+        let comments = discussion.comments.nodes.iter().flatten().flatten();
+        comments.map(|c| c.id.clone()).collect()
+    }
+
+    fn reply_ids(discussion: &Discussion) -> Vec<String> {
+        let comments = discussion.comments.nodes.as_ref().unwrap();
+        let comment = comments[0].as_ref().unwrap();
+        comment
+            .replies
+            .nodes
+            .iter()
+            .flatten()
+            .flatten()
+            .map(|r| r.id.clone())
+            .collect()
+    }
+
+    #[test]
+    fn test_fetch_discussion_reuses_complete_comments_page() {
+        use crate::client::MockHttpClient;
+
+        let mut mock_http = MockHttpClient::new();
+        mock_http
+            .expect_post()
+            .withf(|_url, body| body.contains("repository(owner"))
+            .times(1)
+            .returning(|_url, _body| {
+                let comments =
+                    comments_page(&["C_1", "C_2"], 2, None)["data"]["node"]["comments"].clone();
+                Ok(discussion_response(comments).to_string())
+            });
+        mock_http
+            .expect_post()
+            .withf(|_url, body| body.contains("... on Discussion "))
+            .times(0);
+
+        let client = GitHubClient::new(Box::new(mock_http));
+        let discussion = fetch_discussion(&client, "owner", "repo", 1).unwrap();
+        assert_eq!(comment_ids(&discussion), ["C_1", "C_2"]);
+    }
+
+    #[test]
+    fn test_fetch_discussion_continues_comments_after_first_page() {
+        use crate::client::MockHttpClient;
+
+        let mut mock_http = MockHttpClient::new();
+        mock_http
+            .expect_post()
+            .withf(|_url, body| body.contains("repository(owner"))
+            .times(1)
+            .returning(|_url, _body| {
+                let comments =
+                    comments_page(&["C_1"], 2, Some("c1"))["data"]["node"]["comments"].clone();
+                Ok(discussion_response(comments).to_string())
+            });
+        mock_http
+            .expect_post()
+            .withf(|_url, body| {
+                body.contains("... on Discussion ") && body.contains("\"after\":\"c1\"")
+            })
+            .times(1)
+            .returning(|_url, _body| Ok(comments_page(&["C_2"], 2, None).to_string()));
+
+        let client = GitHubClient::new(Box::new(mock_http));
+        let discussion = fetch_discussion(&client, "owner", "repo", 1).unwrap();
+        assert_eq!(comment_ids(&discussion), ["C_1", "C_2"]);
+    }
+
+    #[test]
+    fn test_fetch_discussion_reuses_complete_reply_page() {
+        use crate::client::MockHttpClient;
+
+        let mut mock_http = MockHttpClient::new();
+        mock_http
+            .expect_post()
+            .withf(|_url, body| body.contains("repository(owner"))
+            .times(1)
+            .returning(|_url, _body| {
+                let replies = vec![
+                    reply_node("R_2", "2024-01-01T03:00:00Z"),
+                    reply_node("R_1", "2024-01-01T02:00:00Z"),
+                ];
+                Ok(discussion_response(comment_with_replies(replies, None)).to_string())
+            });
+        mock_http
+            .expect_post()
+            .withf(|_url, body| body.contains("on DiscussionComment"))
+            .times(0);
+
+        let client = GitHubClient::new(Box::new(mock_http));
+        let discussion = fetch_discussion(&client, "owner", "repo", 1).unwrap();
+        assert_eq!(reply_ids(&discussion), ["R_1", "R_2"]);
+    }
+
+    #[test]
+    fn test_fetch_discussion_continues_replies_after_first_page() {
+        use crate::client::MockHttpClient;
+
+        let mut mock_http = MockHttpClient::new();
+        mock_http
+            .expect_post()
+            .withf(|_url, body| body.contains("repository(owner"))
+            .times(1)
+            .returning(|_url, _body| {
+                let replies = vec![reply_node("R_1", "2024-01-01T02:00:00Z")];
+                Ok(discussion_response(comment_with_replies(replies, Some("r1"))).to_string())
+            });
+        mock_http
+            .expect_post()
+            .withf(|_url, body| {
+                body.contains("on DiscussionComment") && body.contains("\"after\":\"r1\"")
+            })
+            .times(1)
+            .returning(|_url, _body| {
+                Ok(json!({
+                    "data": {
+                        "node": {
+                            "replies": {
+                                "nodes": [reply_node("R_2", "2024-01-01T03:00:00Z")],
+                                "pageInfo": {"hasNextPage": false, "endCursor": null}
+                            }
+                        }
+                    }
+                })
+                .to_string())
+            });
+
+        let client = GitHubClient::new(Box::new(mock_http));
+        let discussion = fetch_discussion(&client, "owner", "repo", 1).unwrap();
+        assert_eq!(reply_ids(&discussion), ["R_1", "R_2"]);
+    }
+
+    #[test]
+    fn test_fetch_discussion_comments_error_surfaces_cursor() {
+        use crate::client::MockHttpClient;
+
+        let mut mock_http = MockHttpClient::new();
+        let mut seq = mockall::Sequence::new();
+        mock_http
+            .expect_post()
+            .times(1)
+            .in_sequence(&mut seq)
+            .returning(|_url, _body| {
+                let comments =
+                    comments_page(&["C_1"], 3, Some("c1"))["data"]["node"]["comments"].clone();
+                Ok(discussion_response(comments).to_string())
+            });
+        mock_http
+            .expect_post()
+            .times(1)
+            .in_sequence(&mut seq)
+            .withf(|_url, body| body.contains("\"after\":\"c1\""))
+            .returning(|_url, _body| Ok(comments_page(&["C_2"], 3, Some("c2")).to_string()));
+        mock_http
+            .expect_post()
+            .times(1)
+            .in_sequence(&mut seq)
+            .withf(|_url, body| body.contains("\"after\":\"c2\""))
+            .returning(|_url, _body| Err(Error::Http("connection reset".to_string())));
+
+        let client = GitHubClient::new(Box::new(mock_http));
+        match fetch_discussion(&client, "owner", "repo", 1) {
+            Err(Error::PaginationFailed {
+                comment_id: None,
+                after,
+                source,
+                partial: Some(partial),
+            }) => {
+                assert_eq!(after.as_deref(), Some("c2"));
+                assert!(matches!(*source, Error::Http(_)));
+                // The comments fetched before the failure are kept
+                assert_eq!(comment_ids(&partial), ["C_1", "C_2"]);
+            }
+            other => panic!("Expected PaginationFailed, got {:?}", other.map(|d| d.id)),
+        }
+    }
+
+    #[test]
+    fn test_fetch_discussion_replies_error_surfaces_cursor() {
+        use crate::client::MockHttpClient;
+
+        let mut mock_http = MockHttpClient::new();
+        mock_http
+            .expect_post()
+            .withf(|_url, body| body.contains("repository(owner"))
+            .times(1)
+            .returning(|_url, _body| {
+                let replies = vec![reply_node("R_1", "2024-01-01T02:00:00Z")];
+                Ok(discussion_response(comment_with_replies(replies, Some("r1"))).to_string())
+            });
+        mock_http
+            .expect_post()
+            .withf(|_url, body| body.contains("on DiscussionComment"))
+            .times(1)
+            .returning(|_url, _body| Err(Error::RateLimit { retry_after: None }));
+
+        let client = GitHubClient::new(Box::new(mock_http));
+        let err = fetch_discussion(&client, "owner", "repo", 1).unwrap_err();
+        assert_eq!(err.exit_code(), 3);
+        match err {
+            Error::PaginationFailed {
+                comment_id, after, ..
+            } => {
+                assert_eq!(comment_id.as_deref(), Some("C_1"));
+                assert_eq!(after.as_deref(), Some("r1"));
+            }
+            other => panic!("Expected PaginationFailed, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_parallel_clamped() {
+        let parallel = |parallel| {
+            FetchOptions {
+                parallel,
+                ..Default::default()
+            }
+            .parallel()
+        };
+        assert_eq!(parallel(None), 1);
+        assert_eq!(parallel(Some(0)), 1);
+        assert_eq!(parallel(Some(4)), 4);
+        assert_eq!(parallel(Some(100)), MAX_FETCH_PARALLEL);
+    }
+
+    #[test]
+    fn test_fetch_discussion_parallel_replies() {
+        use crate::client::MockHttpClient;
+
+        let ids = ["C_1", "C_2", "C_3", "C_4", "C_5"];
+        let mut mock_http = MockHttpClient::new();
+        mock_http
+            .expect_post()
+            .withf(|_url, body| body.contains("repository(owner"))
+            .times(1)
+            .returning(move |_url, _body| {
+                let nodes: Vec<Value> = ids
+                    .iter()
+                    .enumerate()
+                    .map(|(i, id)| {
+                        json!({
+                            "id": id,
+                            "databaseId": i,
+                            "author": {"login": "user"},
+                            "createdAt": format!("2024-01-01T0{}:00:00Z", i),
+                            "body": "Comment",
+                            "replies": {
+                                "nodes": [reply_node(&format!("{}_R1", id), "2024-01-02T00:00:00Z")],
+                                "pageInfo": {"hasNextPage": true, "endCursor": "r1"}
+                            }
+                        })
+                    })
+                    .collect();
+                let comments = json!({
+                    "totalCount": ids.len(),
+                    "nodes": nodes,
+                    "pageInfo": {"hasNextPage": false, "endCursor": null}
+                });
+                Ok(discussion_response(comments).to_string())
+            });
+        mock_http
+            .expect_post()
+            .withf(|_url, body| body.contains("on DiscussionComment"))
+            .times(ids.len())
+            .returning(|_url, body| {
+                let request: Value = serde_json::from_str(body).unwrap();
+                let id = request["variables"]["id"].as_str().unwrap();
+                Ok(json!({
+                    "data": {
+                        "node": {
+                            "replies": {
+                                "nodes": [reply_node(&format!("{}_R2", id), "2024-01-03T00:00:00Z")],
+                                "pageInfo": {"hasNextPage": false, "endCursor": null}
+                            }
+                        }
+                    }
+                })
+                .to_string())
+            });
+
+        let client = GitHubClient::new(Box::new(mock_http));
+        let options = FetchOptions {
+            parallel: Some(3),
+            ..Default::default()
+        };
+        let discussion =
+            fetch_discussion_with_options(&client, "owner", "repo", 1, &options).unwrap();
+
+        assert_eq!(comment_ids(&discussion), ids);
+        for comment in discussion.comments.nodes.iter().flatten().flatten() {
+            let replies: Vec<&str> = comment
+                .replies
+                .nodes
+                .iter()
+                .flatten()
+                .flatten()
+                .map(|r| r.id.as_str())
+                .collect();
+            assert_eq!(
+                replies,
+                [format!("{}_R1", comment.id), format!("{}_R2", comment.id)]
+            );
+        }
+    }
+
+    #[test]
+    fn test_fetch_discussion_lenient_replies() {
+        use crate::client::MockHttpClient;
+
+        let mock_http = || {
+            let mut mock_http = MockHttpClient::new();
+            mock_http
+                .expect_post()
+                .withf(|_url, body| body.contains("repository(owner"))
+                .returning(|_url, _body| {
+                    let comment = |id: &str| {
+                        json!({
+                            "id": id,
+                            "databaseId": 1,
+                            "author": {"login": "user"},
+                            "createdAt": "2024-01-01T01:00:00Z",
+                            "body": "Comment",
+                            "replies": {
+                                "nodes": [reply_node(&format!("{}_R1", id), "2024-01-02T00:00:00Z")],
+                                "pageInfo": {"hasNextPage": true, "endCursor": "r1"}
+                            }
+                        })
+                    };
+                    let comments = json!({
+                        "totalCount": 2,
+                        "nodes": [comment("C_1"), comment("C_2")],
+                        "pageInfo": {"hasNextPage": false, "endCursor": null}
+                    });
+                    Ok(discussion_response(comments).to_string())
+                });
+            mock_http
+                .expect_post()
+                .withf(|_url, body| body.contains("on DiscussionComment"))
+                .returning(|_url, body| {
+                    // The second page of C_1's replies arrives, its third fails
+                    let request: Value = serde_json::from_str(body).unwrap();
+                    let id = request["variables"]["id"].as_str().unwrap();
+                    if request["variables"]["after"] == "r2" {
+                        return Err(Error::Http("connection reset".to_string()));
+                    }
+                    let end_cursor = if id == "C_1" { Some("r2") } else { None };
+                    Ok(json!({
+                        "data": {
+                            "node": {
+                                "replies": {
+                                    "nodes": [reply_node(&format!("{}_R2", id), "2024-01-03T00:00:00Z")],
+                                    "pageInfo": {"hasNextPage": end_cursor.is_some(), "endCursor": end_cursor}
+                                }
+                            }
+                        }
+                    })
+                    .to_string())
+                });
+            GitHubClient::new(Box::new(mock_http))
+        };
+
+        // Strict by default
+        assert!(fetch_discussion(&mock_http(), "owner", "repo", 1).is_err());
+
+        let options = FetchOptions {
+            lenient: true,
+            ..Default::default()
+        };
+        let discussion =
+            fetch_discussion_with_options(&mock_http(), "owner", "repo", 1, &options).unwrap();
+        let replies: Vec<Vec<&str>> = discussion
+            .comments
+            .nodes
+            .iter()
+            .flatten()
+            .flatten()
+            .map(|c| {
+                let replies = c.replies.nodes.iter().flatten().flatten();
+                replies.map(|r| r.id.as_str()).collect()
+            })
+            .collect();
+        assert_eq!(
+            replies,
+            [vec!["C_1_R1", "C_1_R2"], vec!["C_2_R1", "C_2_R2"]]
+        );
+
+        // The incomplete replies are reported
+        let client = mock_http();
+        let discussion = client.execute_query(DISCUSSION_QUERY, json!({})).unwrap();
+        let comments: Vec<Comment> = discussion
+            .comments
+            .nodes
+            .unwrap()
+            .into_iter()
+            .flatten()
+            .collect();
+        let pending = [(0, "r1".to_string()), (1, "r1".to_string())];
+        let mut warnings = Vec::new();
+        let remaining = fetch_remaining_replies(
+            &client,
+            &comments,
+            &pending,
+            &options,
+            &mut ProgressReporter::hidden("Fetching replies"),
+            &mut warnings,
+        )
+        .unwrap();
+        assert_eq!(remaining.iter().map(Vec::len).collect::<Vec<_>>(), [1, 1]);
+        assert_eq!(warnings.len(), 1);
+        assert!(
+            warnings[0].starts_with("Replies of comment C_1 are incomplete, keeping the 1 fetched"),
+            "{}",
+            warnings[0]
+        );
+        assert!(warnings[0].contains("connection reset"));
+    }
+
     // Task 5.4: Add test for multiple pages of replies
     #[test]
     fn test_fetch_all_replies_multiple_pages() {
@@ -820,7 +2355,9 @@ mod tests {
                     has_next_page: false,
                     end_cursor: None,
                 },
+                ..Default::default()
             },
+            ..Default::default()
         };
 
         // Create a comment with null author
@@ -841,12 +2378,15 @@ mod tests {
                         .unwrap()
                         .with_timezone(&Utc),
                     body: "Reply 1".to_string(),
+                    ..Default::default()
                 })]),
                 page_info: crate::models::PageInfo {
                     has_next_page: false,
                     end_cursor: None,
                 },
+                ..Default::default()
             },
+            ..Default::default()
         }];
 
         // Apply the replace_deleted_authors function
@@ -893,6 +2433,7 @@ mod tests {
                 database_id: 2,
                 author: Some(Author {
                     login: Some("user2".to_string()),
+                    typename: None,
                 }),
                 created_at: DateTime::parse_from_rfc3339("2024-01-01T02:00:00Z")
                     .unwrap()
@@ -904,13 +2445,16 @@ mod tests {
                         has_next_page: false,
                         end_cursor: None,
                     },
+                    ..Default::default()
                 },
+                ..Default::default()
             },
             Comment {
                 id: "comment_1".to_string(),
                 database_id: 1,
                 author: Some(Author {
                     login: Some("user1".to_string()),
+                    typename: None,
                 }),
                 created_at: DateTime::parse_from_rfc3339("2024-01-01T01:00:00Z")
                     .unwrap()
@@ -922,13 +2466,16 @@ mod tests {
                         has_next_page: false,
                         end_cursor: None,
                     },
+                    ..Default::default()
                 },
+                ..Default::default()
             },
             Comment {
                 id: "comment_3".to_string(),
                 database_id: 3,
                 author: Some(Author {
                     login: Some("user3".to_string()),
+                    typename: None,
                 }),
                 created_at: DateTime::parse_from_rfc3339("2024-01-01T03:00:00Z")
                     .unwrap()
@@ -940,12 +2487,14 @@ mod tests {
                         has_next_page: false,
                         end_cursor: None,
                     },
+                    ..Default::default()
                 },
+                ..Default::default()
             },
         ];
 
         // Sort comments
-        comments.sort_by(|a, b| a.created_at.cmp(&b.created_at));
+        comments.sort_by_key(|a| a.created_at);
 
         // Verify they're in chronological order
         assert_eq!(comments[0].id, "comment_1");
@@ -958,6 +2507,7 @@ mod tests {
             database_id: 1,
             author: Some(Author {
                 login: Some("user1".to_string()),
+                typename: None,
             }),
             created_at: DateTime::parse_from_rfc3339("2024-01-01T01:00:00Z")
                 .unwrap()
@@ -970,40 +2520,48 @@ mod tests {
                         database_id: 3,
                         author: Some(Author {
                             login: Some("user3".to_string()),
+                            typename: None,
                         }),
                         created_at: DateTime::parse_from_rfc3339("2024-01-01T03:00:00Z")
                             .unwrap()
                             .with_timezone(&Utc),
                         body: "Reply 3".to_string(),
+                        ..Default::default()
                     }),
                     Some(Reply {
                         id: "reply_1".to_string(),
                         database_id: 1,
                         author: Some(Author {
                             login: Some("user1".to_string()),
+                            typename: None,
                         }),
                         created_at: DateTime::parse_from_rfc3339("2024-01-01T01:00:00Z")
                             .unwrap()
                             .with_timezone(&Utc),
                         body: "Reply 1".to_string(),
+                        ..Default::default()
                     }),
                     Some(Reply {
                         id: "reply_2".to_string(),
                         database_id: 2,
                         author: Some(Author {
                             login: Some("user2".to_string()),
+                            typename: None,
                         }),
                         created_at: DateTime::parse_from_rfc3339("2024-01-01T02:00:00Z")
                             .unwrap()
                             .with_timezone(&Utc),
                         body: "Reply 2".to_string(),
+                        ..Default::default()
                     }),
                 ]),
                 page_info: crate::models::PageInfo {
                     has_next_page: false,
                     end_cursor: None,
                 },
+                ..Default::default()
             },
+            ..Default::default()
         };
 
         // Sort replies
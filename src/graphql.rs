@@ -1,62 +1,57 @@
-/// GraphQL query to fetch discussion metadata
-///
-/// This query fetches only discussion metadata:
-/// - Discussion ID (node ID for pagination queries)
-/// - Discussion metadata (title, number, URL, created at, body, author)
-///
-/// Note: Comments and replies are fetched separately using pagination queries
-/// (COMMENTS_QUERY and REPLIES_QUERY) to ensure complete data retrieval.
-pub const DISCUSSION_QUERY: &str = r#"
-query ($owner: String!, $repo: String!, $number: Int!) {
-    repository(owner: $owner, name: $repo) {
-        discussion(number: $number) {
-            id
-            title
-            number
-            url
-            createdAt
-            body
-            author {
-                login
-            }
-        }
-    }
-}
-"#;
+/// Largest page size GitHub accepts for `first` on a connection
+pub const MAX_PAGE_SIZE: u32 = 100;
 
-/// GraphQL query to fetch comments for a discussion with pagination
+/// Selection set for a page of discussion comments
 ///
-/// This query fetches:
-/// - Comment nodes with id, databaseId, author, createdAt, body
+/// Shared by DISCUSSION_QUERY (first page, inline) and COMMENTS_QUERY
+/// (subsequent pages). Selects:
+/// - Comment nodes with id, databaseId, author, createdAt, body (and bodyHTML if requested), upvoteCount, lastEditedAt, isAnswer, reactionGroups
 /// - First page of reply nodes (to avoid unnecessary API calls for comments without replies)
-/// - Replies pageInfo (for determining if additional pagination is needed)
-/// - PageInfo for comment pagination
-///
-/// Variables:
-/// - $id: ID! - The discussion node ID
-/// - $after: String - Cursor for pagination (null for first page)
-pub const COMMENTS_QUERY: &str = r#"
-query ($id: ID!, $after: String) {
-    node(id: $id) {
-        ... on Discussion {
-            comments(first: 100, after: $after) {
+/// - Replies pageInfo and totalCount (for determining if additional pagination is needed)
+/// - Comments totalCount (for progress reporting) and PageInfo for comment pagination
+macro_rules! comments_page_fields {
+    () => {
+        r#"
+                totalCount
                 nodes {
                     id
                     databaseId
                     author {
                         login
+                        __typename
                     }
                     createdAt
                     body
-                    replies(first: 100) {
+                    bodyHTML @include(if: $bodyHtml)
+                    upvoteCount
+                    lastEditedAt
+                    isAnswer
+                    reactionGroups {
+                        content
+                        reactors {
+                            totalCount
+                        }
+                    }
+                    replies(first: $first) {
+                        totalCount
                         nodes {
                             id
                             databaseId
                             author {
                                 login
+                                __typename
                             }
                             createdAt
                             body
+                            bodyHTML @include(if: $bodyHtml)
+                            upvoteCount
+                            lastEditedAt
+                            reactionGroups {
+                                content
+                                reactors {
+                                    totalCount
+                                }
+                            }
                         }
                         pageInfo {
                             hasNextPage
@@ -68,34 +63,160 @@ query ($id: ID!, $after: String) {
                     hasNextPage
                     endCursor
                 }
+"#
+    };
+}
+
+/// GraphQL query to fetch a discussion with its first page of comments
+///
+/// This query fetches:
+/// - Discussion ID (node ID for pagination queries)
+/// - Discussion metadata (title, number, URL, created at, body, author)
+/// - Accepted answer reference and when it was chosen (Q&A categories)
+/// - Reaction groups with reactor counts
+/// - Category name and emoji
+/// - Closed/locked state and when it was closed
+/// - Poll question and options with their vote counts (GitHub polls have at
+///   most 8 options, so one page covers them)
+/// - Rate limit budget (cost, limit, remaining, resetAt)
+/// - Upvote count and last edit time
+/// - First page of comments, with the same fields as COMMENTS_QUERY
+///
+/// Variables:
+/// - $owner: String!, $repo: String!, $number: Int! - The discussion to fetch
+/// - $first: Int! - Page size for comments and their inline replies (at most MAX_PAGE_SIZE)
+/// - $bodyHtml: Boolean! - Whether to also fetch the rendered `bodyHTML` of each body
+///
+/// Note: Further pages of comments and replies are fetched separately using
+/// pagination queries (COMMENTS_QUERY and REPLIES_QUERY) to ensure complete
+/// data retrieval.
+pub const DISCUSSION_QUERY: &str = concat!(
+    r#"
+query ($owner: String!, $repo: String!, $number: Int!, $first: Int!, $bodyHtml: Boolean!) {
+    repository(owner: $owner, name: $repo) {
+        discussion(number: $number) {
+            id
+            title
+            number
+            url
+            createdAt
+            body
+            bodyHTML @include(if: $bodyHtml)
+            upvoteCount
+            lastEditedAt
+            author {
+                login
+                __typename
+            }
+            answer {
+                id
+            }
+            answerChosenAt
+            reactionGroups {
+                content
+                reactors {
+                    totalCount
+                }
             }
+            category {
+                name
+                emoji
+            }
+            closed
+            closedAt
+            locked
+            poll {
+                question
+                totalVoteCount
+                options(first: 8) {
+                    nodes {
+                        option
+                        totalVoteCount
+                    }
+                }
+            }
+            comments(first: $first) {"#,
+    comments_page_fields!(),
+    r#"            }
+        }
+    }
+    rateLimit {
+        cost
+        limit
+        remaining
+        resetAt
+    }
+}
+"#
+);
+
+/// GraphQL query to fetch comments for a discussion with pagination
+///
+/// This query fetches:
+/// - A page of comments (see `comments_page_fields!`)
+/// - Rate limit budget (cost, limit, remaining, resetAt)
+///
+/// Variables:
+/// - $id: ID! - The discussion node ID
+/// - $after: String - Cursor for pagination (null for first page)
+/// - $first: Int! - Page size for comments and their inline replies (at most MAX_PAGE_SIZE)
+/// - $bodyHtml: Boolean! - Whether to also fetch the rendered `bodyHTML` of each body
+pub const COMMENTS_QUERY: &str = concat!(
+    r#"
+query ($id: ID!, $after: String, $first: Int!, $bodyHtml: Boolean!) {
+    node(id: $id) {
+        ... on Discussion {
+            comments(first: $first, after: $after) {"#,
+    comments_page_fields!(),
+    r#"            }
         }
     }
+    rateLimit {
+        cost
+        limit
+        remaining
+        resetAt
+    }
 }
-"#;
+"#
+);
 
 /// GraphQL query to fetch replies for a comment with pagination
 ///
 /// This query fetches:
-/// - Reply nodes with id, databaseId, author, createdAt, body
-/// - PageInfo for reply pagination
+/// - Reply nodes with id, databaseId, author, createdAt, body, upvoteCount, lastEditedAt, reactionGroups
+/// - Replies totalCount and PageInfo for reply pagination
+/// - Rate limit budget (cost, limit, remaining, resetAt)
 ///
 /// Variables:
 /// - $id: ID! - The comment node ID
 /// - $after: String - Cursor for pagination (null for first page)
+/// - $first: Int! - Page size (at most MAX_PAGE_SIZE)
+/// - $bodyHtml: Boolean! - Whether to also fetch the rendered `bodyHTML` of each reply
 pub const REPLIES_QUERY: &str = r#"
-query ($id: ID!, $after: String) {
+query ($id: ID!, $after: String, $first: Int!, $bodyHtml: Boolean!) {
     node(id: $id) {
         ... on DiscussionComment {
-            replies(first: 100, after: $after) {
+            replies(first: $first, after: $after) {
+                totalCount
                 nodes {
                     id
                     databaseId
                     author {
                         login
+                        __typename
                     }
                     createdAt
                     body
+                    bodyHTML @include(if: $bodyHtml)
+                    upvoteCount
+                    lastEditedAt
+                    reactionGroups {
+                        content
+                        reactors {
+                            totalCount
+                        }
+                    }
                 }
                 pageInfo {
                     hasNextPage
@@ -104,6 +225,12 @@ query ($id: ID!, $after: String) {
             }
         }
     }
+    rateLimit {
+        cost
+        limit
+        remaining
+        resetAt
+    }
 }
 "#;
 
@@ -129,6 +256,76 @@ mod tests {
         assert!(COMMENTS_QUERY.contains("author"));
         assert!(COMMENTS_QUERY.contains("login"));
         assert!(COMMENTS_QUERY.contains("replies"));
+        assert!(COMMENTS_QUERY.contains("isAnswer"));
+    }
+
+    #[test]
+    fn test_queries_contain_reaction_groups() {
+        for query in [DISCUSSION_QUERY, COMMENTS_QUERY, REPLIES_QUERY] {
+            assert!(query.contains("reactionGroups"));
+            assert!(query.contains("totalCount"));
+        }
+        // Both comments and their inline replies request reactions
+        assert_eq!(COMMENTS_QUERY.matches("reactionGroups").count(), 2);
+    }
+
+    #[test]
+    fn test_discussion_query_contains_first_comments_page() {
+        assert!(DISCUSSION_QUERY.contains("comments(first: $first) {"));
+        assert!(DISCUSSION_QUERY.contains("isAnswer"));
+        assert!(DISCUSSION_QUERY.contains("replies(first: $first)"));
+        assert!(DISCUSSION_QUERY.contains("hasNextPage"));
+    }
+
+    #[test]
+    fn test_query_contains_answer_fields() {
+        assert!(DISCUSSION_QUERY.contains("answer {"));
+        assert!(DISCUSSION_QUERY.contains("answerChosenAt"));
+    }
+
+    #[test]
+    fn test_query_contains_category_fields() {
+        assert!(DISCUSSION_QUERY.contains("category {"));
+        assert!(DISCUSSION_QUERY.contains("emoji"));
+    }
+
+    #[test]
+    fn test_query_contains_poll_fields() {
+        assert!(DISCUSSION_QUERY.contains("poll {"));
+        assert!(DISCUSSION_QUERY.contains("question"));
+        assert!(DISCUSSION_QUERY.contains("options(first: 8)"));
+        assert!(DISCUSSION_QUERY.contains("totalVoteCount"));
+    }
+
+    #[test]
+    fn test_queries_contain_upvote_count() {
+        for query in [DISCUSSION_QUERY, COMMENTS_QUERY, REPLIES_QUERY] {
+            assert!(query.contains("upvoteCount"));
+        }
+    }
+
+    #[test]
+    fn test_queries_contain_last_edited_at() {
+        for query in [DISCUSSION_QUERY, COMMENTS_QUERY, REPLIES_QUERY] {
+            assert!(query.contains("lastEditedAt"));
+        }
+    }
+
+    #[test]
+    fn test_query_contains_state_fields() {
+        assert!(DISCUSSION_QUERY.contains("closed\n"));
+        assert!(DISCUSSION_QUERY.contains("closedAt"));
+        assert!(DISCUSSION_QUERY.contains("locked"));
+    }
+
+    #[test]
+    fn test_queries_contain_rate_limit() {
+        for query in [DISCUSSION_QUERY, COMMENTS_QUERY, REPLIES_QUERY] {
+            assert!(query.contains("rateLimit {"));
+            assert!(query.contains("cost"));
+            assert!(query.contains("remaining"));
+            assert!(query.contains("resetAt"));
+        }
     }
 
     #[test]
@@ -138,6 +335,8 @@ mod tests {
         assert!(COMMENTS_QUERY.contains("hasNextPage"));
         assert!(COMMENTS_QUERY.contains("endCursor"));
         assert!(REPLIES_QUERY.contains("pageInfo"));
+        assert!(COMMENTS_QUERY.contains("totalCount"));
+        assert!(REPLIES_QUERY.contains("totalCount"));
     }
 
     #[test]
@@ -147,6 +346,26 @@ mod tests {
         assert!(DISCUSSION_QUERY.contains("$number: Int!"));
     }
 
+    #[test]
+    fn test_queries_take_page_size_variable() {
+        for query in [DISCUSSION_QUERY, COMMENTS_QUERY, REPLIES_QUERY] {
+            assert!(query.contains("$first: Int!"));
+            assert!(!query.contains("first: 100"));
+        }
+    }
+
+    #[test]
+    fn test_queries_fetch_body_html_on_request() {
+        for query in [DISCUSSION_QUERY, COMMENTS_QUERY, REPLIES_QUERY] {
+            assert!(query.contains("$bodyHtml: Boolean!"));
+            // Every body is paired with its optional rendered HTML
+            assert_eq!(
+                query.matches("bodyHTML @include(if: $bodyHtml)").count(),
+                query.lines().filter(|line| line.trim() == "body").count()
+            );
+        }
+    }
+
     #[test]
     fn test_query_syntax_basic() {
         // Basic GraphQL syntax checks
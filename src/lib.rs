@@ -1,8 +1,14 @@
+pub mod api;
 pub mod auth;
 pub mod cli;
 pub(crate) mod command_runner;
+pub mod config;
 pub mod error;
 pub mod output;
+pub mod progress;
+pub mod stats;
+pub mod template;
+pub mod transform;
 
 // GraphQL client modules
 pub mod client;
@@ -1,66 +1,330 @@
-use clap::Parser;
-use gh_discussion_export::cli::CliArgs;
-use gh_discussion_export::client::ReqwestClient;
-use gh_discussion_export::fetch::fetch_discussion;
-use gh_discussion_export::output::{format_discussion, write_output};
+use clap::{CommandFactory, FromArgMatches};
+use gh_discussion_export::api::render_discussion;
+use gh_discussion_export::cli::{CliArgs, write_completion, write_man_page};
+use gh_discussion_export::client::{ReqwestClient, graphql_endpoint};
+use gh_discussion_export::config::Config;
+use gh_discussion_export::error::{EXIT_USAGE, Error};
+use gh_discussion_export::fetch::fetch_discussion_with_options;
+use gh_discussion_export::output::{
+    append_output, check_output_path, format_appended_comments, format_discussion_split,
+    format_dry_run_summary, format_porcelain_counts, format_porcelain_summary, write_output,
+    write_split_output, write_stdout,
+};
+use gh_discussion_export::template::load_template;
 
 fn main() {
-    // Parse command-line arguments
-    let args = CliArgs::parse();
+    // Parse command-line arguments (usage errors exit with EXIT_USAGE, not clap's default 2)
+    let exit_on_clap_error = |e: clap::Error| -> ! {
+        let code = if e.use_stderr() { EXIT_USAGE } else { 0 };
+        let _ = e.print();
+        std::process::exit(code);
+    };
+    let matches = CliArgs::command()
+        .try_get_matches()
+        .unwrap_or_else(|e| exit_on_clap_error(e));
+    let mut args = CliArgs::from_arg_matches(&matches).unwrap_or_else(|e| exit_on_clap_error(e));
+
+    if let Some(shell) = args.generate_completion {
+        if let Err(e) = write_completion(shell, &mut std::io::stdout()) {
+            eprintln!("Error: {}", e);
+            std::process::exit(e.exit_code());
+        }
+        return;
+    }
+    if args.generate_man {
+        if let Err(e) = write_man_page(&mut std::io::stdout()) {
+            eprintln!("Error: {}", e);
+            std::process::exit(e.exit_code());
+        }
+        return;
+    }
+
+    // Fill in flags not given on the command line from the config file
+    match Config::load() {
+        Ok(Some(config)) => config.apply(&mut args, &matches),
+        Ok(None) => {}
+        Err(e) => {
+            eprintln!("Error: {}", e);
+            std::process::exit(e.exit_code());
+        }
+    }
+
+    // Send this crate's log records to stderr; RUST_LOG, if set, refines the -v level
+    env_logger::Builder::new()
+        .filter_module("gh_discussion_export", args.log_level())
+        .parse_default_env()
+        .init();
+
+    if let Err(e) = args.validate() {
+        eprintln!("Error: {}", e);
+        std::process::exit(e.exit_code());
+    }
 
     // Extract owner, repo, number from arguments
     let (owner, repo) = match args.repo_components() {
         Ok(components) => components,
         Err(e) => {
             eprintln!("Error: {}", e);
-            std::process::exit(1);
+            std::process::exit(e.exit_code());
         }
     };
-    let number = args.number;
+    let number = args.number();
 
-    // Determine output path (use arg value or default to `<number>-discussion.md`)
+    // Determine output path (use arg value or default to `<number>-discussion.<ext>`)
     let output_path = args.output_path();
 
-    // Get GitHub token
-    let token = match gh_discussion_export::auth::get_github_token() {
+    // Refuse to clobber an existing export before making any API calls
+    if !args.stdout
+        && !args.dry_run
+        && !args.append
+        && let Err(e) = check_output_path(&output_path, args.force)
+    {
+        eprintln!("Error: {}", e);
+        std::process::exit(e.exit_code());
+    }
+
+    // Read the template up front so a broken one wastes no API calls
+    let mut output_options = args.output_options();
+    if let Some(path) = &args.template {
+        match load_template(path) {
+            Ok(template) => output_options.template = Some(template),
+            Err(e) => {
+                eprintln!("Error: {}", e);
+                std::process::exit(e.exit_code());
+            }
+        }
+    }
+
+    // Get GitHub token (from stdin if requested, otherwise env vars or `gh`)
+    let token_result = if args.token_stdin {
+        gh_discussion_export::auth::read_token_from_stdin()
+    } else {
+        gh_discussion_export::auth::get_github_token_for_host(args.api_host())
+    };
+    let token = match token_result {
         Ok(token) => token,
         Err(e) => {
             eprintln!("Error: {}", e);
-            std::process::exit(1);
+            std::process::exit(e.exit_code());
         }
     };
 
     // Create GitHub client
-    let http_client = match ReqwestClient::new(token) {
+    let http_client = match ReqwestClient::with_options(token, &args.http_options()) {
         Ok(client) => Box::new(client),
         Err(e) => {
             eprintln!("Error: {}", e);
-            std::process::exit(1);
+            std::process::exit(e.exit_code());
         }
     };
-    let client = gh_discussion_export::client::GitHubClient::new(http_client);
+    let endpoint = graphql_endpoint(args.api_host());
+    let client = gh_discussion_export::client::GitHubClient::with_endpoint(http_client, endpoint)
+        .with_retry_policy(args.retry_policy());
 
-    // Fetch discussion
-    let discussion = match fetch_discussion(&client, &owner, &repo, number) {
+    // Fetch discussion. When exporting to a Markdown file, the comments
+    // fetched before a failed comments page are still written, and the rest
+    // can be appended later with --append.
+    let mut resume = None;
+    let discussion = match fetch_discussion_with_options(
+        &client,
+        &owner,
+        &repo,
+        number,
+        &args.fetch_options(),
+    ) {
         Ok(discussion) => discussion,
+        Err(Error::PaginationFailed {
+            comment_id: None,
+            after: Some(cursor),
+            source,
+            partial: Some(partial),
+        }) if args.resumable() => {
+            eprintln!("Error: {}", source);
+            // The rerun with --append finds the exported comments by their markers
+            output_options.db_id_markers = true;
+            resume = Some((cursor, source.exit_code()));
+            *partial
+        }
         Err(e) => {
             eprintln!("Error: {}", e);
-            std::process::exit(1);
+            match &e {
+                Error::PaginationFailed {
+                    comment_id: Some(comment_id),
+                    after: Some(cursor),
+                    ..
+                } => eprintln!(
+                    "Replies of comment {} were fetched up to cursor {}",
+                    comment_id, cursor
+                ),
+                Error::PaginationFailed {
+                    comment_id: None,
+                    after: Some(cursor),
+                    ..
+                } => {
+                    eprintln!("Comments were fetched up to cursor {}", cursor);
+                    eprintln!(
+                        "To fetch the remaining comments, rerun with --append --after-comment-cursor {}",
+                        cursor
+                    );
+                }
+                _ => {}
+            }
+            std::process::exit(e.exit_code());
         }
     };
 
-    // Generate Markdown output
-    let markdown = format_discussion(&discussion, &owner, &repo);
+    // All API calls are done; report the rate limit budget if requested
+    if args.show_rate_limit {
+        match client.rate_limit_usage() {
+            Some(usage) => eprintln!("{}", usage.summary()),
+            None => eprintln!("Rate limit: not reported by the API"),
+        }
+    }
+
+    // In dry-run mode, report what would be exported and stop before writing
+    if args.dry_run {
+        let target = if args.stdout {
+            "<stdout>"
+        } else {
+            &output_path
+        };
+        print!("{}", format_dry_run_summary(&discussion, target));
+        return;
+    }
 
-    // Write output file
-    match write_output(&markdown, &output_path) {
+    // In append mode, add only the comments newer than the existing export
+    if args.append && std::path::Path::new(&output_path).exists() {
+        let existing = match std::fs::read_to_string(&output_path) {
+            Ok(existing) => existing,
+            Err(e) => {
+                let e = Error::from(e);
+                eprintln!("Error: {}", e);
+                std::process::exit(e.exit_code());
+            }
+        };
+        let appended =
+            match format_appended_comments(&discussion, &existing, &args.output_options()) {
+                Ok(appended) => appended,
+                Err(e) => {
+                    eprintln!("Error: {}", e);
+                    std::process::exit(e.exit_code());
+                }
+            };
+        if let Err(e) = append_output(&appended.markdown, &output_path) {
+            eprintln!("Error: {}", e);
+            std::process::exit(e.exit_code());
+        }
+        if let Some((cursor, code)) = resume {
+            exit_with_resume_hint(&output_path, &cursor, code);
+        }
+        if args.porcelain {
+            print!(
+                "{}",
+                format_porcelain_counts(appended.comments, appended.replies, &output_path)
+            );
+        } else if !args.quiet {
+            println!(
+                "Appended {} new comment(s) to: {}",
+                appended.comments, output_path
+            );
+        }
+        return;
+    }
+
+    // In split mode, write the index and one file per comment
+    if args.split_files {
+        if let Some(dir) = &args.output_dir
+            && let Err(e) = std::fs::create_dir_all(dir)
+        {
+            let e = Error::from(e);
+            eprintln!("Error: {}", e);
+            std::process::exit(e.exit_code());
+        }
+        let index_name = std::path::Path::new(&output_path)
+            .file_name()
+            .map(|name| name.to_string_lossy().into_owned())
+            .unwrap_or_else(|| output_path.clone());
+        let files = format_discussion_split(
+            &discussion,
+            &owner,
+            &repo,
+            &index_name,
+            &args.output_options(),
+        );
+        if let Err(e) = write_split_output(&files, &output_path, args.force) {
+            eprintln!("Error: {}", e);
+            std::process::exit(e.exit_code());
+        }
+        if args.porcelain {
+            print!("{}", format_porcelain_summary(&discussion, &output_path));
+        } else if !args.quiet {
+            println!(
+                "Discussion exported to: {} (and {} comment file(s))",
+                output_path,
+                files.len() - 1
+            );
+        }
+        return;
+    }
+
+    // Generate output in the requested format
+    let content = match render_discussion(&discussion, &owner, &repo, args.format, &output_options)
+    {
+        Ok(content) => content,
+        Err(e) => {
+            eprintln!("Error: {}", e);
+            std::process::exit(e.exit_code());
+        }
+    };
+
+    // Write to stdout, or to the output file and report where it went
+    if args.stdout {
+        if let Err(e) = write_stdout(&content) {
+            eprintln!("Error: {}", e);
+            std::process::exit(e.exit_code());
+        }
+        return;
+    }
+
+    if let Some(dir) = &args.output_dir
+        && let Err(e) = std::fs::create_dir_all(dir)
+    {
+        let e = Error::from(e);
+        eprintln!("Error: {}", e);
+        std::process::exit(e.exit_code());
+    }
+
+    match write_output(&content, &output_path) {
         Ok(()) => {}
         Err(e) => {
             eprintln!("Error: {}", e);
-            std::process::exit(1);
+            std::process::exit(e.exit_code());
         }
     };
+    if let Some((cursor, code)) = resume {
+        exit_with_resume_hint(&output_path, &cursor, code);
+    }
+
+    // Print success message (or the machine-readable summary line)
+    if args.porcelain {
+        print!("{}", format_porcelain_summary(&discussion, &output_path));
+    } else if !args.quiet {
+        println!("Discussion exported to: {}", output_path);
+    }
+}
 
-    // Print success message
-    println!("Discussion exported to: {}", output_path);
+/// Explain how to resume a partial export, then exit with `code`
+///
+/// Called after the comments fetched before a failed comments page were
+/// written to `output_path`.
+fn exit_with_resume_hint(output_path: &str, cursor: &str, code: i32) -> ! {
+    eprintln!(
+        "The comments fetched before the failure were written to: {}",
+        output_path
+    );
+    eprintln!(
+        "To append the remaining comments, rerun with --append --after-comment-cursor {}",
+        cursor
+    );
+    std::process::exit(code);
 }
@@ -1,22 +1,47 @@
 use chrono::{DateTime, Utc};
-use serde::Deserialize;
+use serde::{Deserialize, Serialize};
+
+/// Login substituted for the author of content whose account was deleted
+pub const DELETED_AUTHOR: &str = "<deleted>";
 
 /// Represents a GitHub user (author of comments/replies)
-#[derive(Debug, Deserialize, Clone, PartialEq)]
+#[derive(Debug, Deserialize, Serialize, Clone, PartialEq)]
 pub struct Author {
     pub login: Option<String>,
+    /// GraphQL type of the actor, such as `User` or `Bot`
+    #[serde(
+        default,
+        rename = "__typename",
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub typename: Option<String>,
 }
 
 /// Pagination information for GraphQL connections
-#[derive(Debug, Default, Deserialize, Clone, PartialEq)]
+#[derive(Debug, Default, Deserialize, Serialize, Clone, PartialEq)]
 #[serde(rename_all = "camelCase")]
 pub struct PageInfo {
     pub has_next_page: bool,
     pub end_cursor: Option<String>,
 }
 
+/// Total count of a GraphQL connection (e.g. reactors of a reaction group)
+#[derive(Debug, Default, Deserialize, Serialize, Clone, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct TotalCount {
+    pub total_count: u64,
+}
+
+/// Reactions of a single kind (e.g. THUMBS_UP) on a discussion, comment, or reply
+#[derive(Debug, Deserialize, Serialize, Clone, PartialEq)]
+pub struct ReactionGroup {
+    /// Reaction content as returned by GitHub (e.g. `THUMBS_UP`, `HEART`)
+    pub content: String,
+    pub reactors: TotalCount,
+}
+
 /// A reply to a comment
-#[derive(Debug, Deserialize, Clone, PartialEq)]
+#[derive(Debug, Default, Deserialize, Serialize, Clone, PartialEq)]
 #[serde(rename_all = "camelCase")]
 pub struct Reply {
     pub id: String,
@@ -24,10 +49,20 @@ pub struct Reply {
     pub author: Option<Author>,
     pub created_at: DateTime<Utc>,
     pub body: String,
+    /// Rendered HTML of the body (only fetched for `--body-format html`)
+    #[serde(default, rename = "bodyHTML", skip_serializing_if = "Option::is_none")]
+    pub body_html: Option<String>,
+    #[serde(default)]
+    pub upvote_count: u64,
+    /// When the body was last edited (`None` if never edited)
+    #[serde(default)]
+    pub last_edited_at: Option<DateTime<Utc>>,
+    #[serde(default)]
+    pub reaction_groups: Option<Vec<ReactionGroup>>,
 }
 
 /// A comment on a discussion
-#[derive(Debug, Deserialize, Clone, PartialEq)]
+#[derive(Debug, Default, Deserialize, Serialize, Clone, PartialEq)]
 #[serde(rename_all = "camelCase")]
 pub struct Comment {
     pub id: String,
@@ -35,21 +70,76 @@ pub struct Comment {
     pub author: Option<Author>,
     pub created_at: DateTime<Utc>,
     pub body: String,
+    /// Rendered HTML of the body (only fetched for `--body-format html`)
+    #[serde(default, rename = "bodyHTML", skip_serializing_if = "Option::is_none")]
+    pub body_html: Option<String>,
+    #[serde(default)]
+    pub upvote_count: u64,
+    /// When the body was last edited (`None` if never edited)
+    #[serde(default)]
+    pub last_edited_at: Option<DateTime<Utc>>,
     pub replies: CommentReplies,
+    /// Whether this comment is the accepted answer (Q&A categories only)
+    #[serde(default)]
+    pub is_answer: bool,
+    #[serde(default)]
+    pub reaction_groups: Option<Vec<ReactionGroup>>,
 }
 
 /// Replies connection with pagination info
-#[derive(Debug, Deserialize, Clone, PartialEq)]
+#[derive(Debug, Default, Deserialize, Serialize, Clone, PartialEq)]
 #[serde(rename_all = "camelCase")]
 pub struct CommentReplies {
     /// nodes may be missing in COMMENTS_QUERY responses (only pageInfo is returned)
     #[serde(default)]
     pub nodes: Option<Vec<Option<Reply>>>,
     pub page_info: PageInfo,
+    /// Total number of replies reported by the API (may include deleted replies)
+    #[serde(default)]
+    pub total_count: Option<u64>,
+}
+
+/// Reference to the accepted answer of a discussion
+#[derive(Debug, Deserialize, Serialize, Clone, PartialEq)]
+pub struct DiscussionAnswer {
+    pub id: String,
+}
+
+/// Category a discussion belongs to (e.g. General, Q&A, Announcements)
+#[derive(Debug, Default, Deserialize, Serialize, Clone, PartialEq)]
+pub struct DiscussionCategory {
+    pub name: String,
+    /// Emoji shortcode as returned by GitHub (e.g. `:speech_balloon:`)
+    #[serde(default)]
+    pub emoji: Option<String>,
+}
+
+/// A poll attached to a discussion
+#[derive(Debug, Default, Deserialize, Serialize, Clone, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct Poll {
+    pub question: String,
+    /// Total number of votes across all options
+    pub total_vote_count: u64,
+    pub options: PollOptions,
+}
+
+/// Options connection of a poll
+#[derive(Debug, Default, Deserialize, Serialize, Clone, PartialEq)]
+pub struct PollOptions {
+    pub nodes: Option<Vec<Option<PollOption>>>,
+}
+
+/// A single poll option with its vote count
+#[derive(Debug, Default, Deserialize, Serialize, Clone, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct PollOption {
+    pub option: String,
+    pub total_vote_count: u64,
 }
 
 /// A GitHub discussion
-#[derive(Debug, Deserialize, Clone, PartialEq)]
+#[derive(Debug, Default, Deserialize, Serialize, Clone, PartialEq)]
 #[serde(rename_all = "camelCase")]
 pub struct Discussion {
     pub id: String,
@@ -58,18 +148,51 @@ pub struct Discussion {
     pub url: String,
     pub created_at: DateTime<Utc>,
     pub body: String,
+    /// Rendered HTML of the body (only fetched for `--body-format html`)
+    #[serde(default, rename = "bodyHTML", skip_serializing_if = "Option::is_none")]
+    pub body_html: Option<String>,
     pub author: Option<Author>,
-    /// comments is populated after initial query via fetch_all_comments
+    /// Accepted answer, if the discussion is answerable and one was chosen
+    #[serde(default)]
+    pub answer: Option<DiscussionAnswer>,
+    /// When the accepted answer was chosen
+    #[serde(default)]
+    pub answer_chosen_at: Option<DateTime<Utc>>,
+    #[serde(default)]
+    pub upvote_count: u64,
+    /// When the body was last edited (`None` if never edited)
+    #[serde(default)]
+    pub last_edited_at: Option<DateTime<Utc>>,
+    #[serde(default)]
+    pub reaction_groups: Option<Vec<ReactionGroup>>,
+    #[serde(default)]
+    pub category: Option<DiscussionCategory>,
+    #[serde(default)]
+    pub closed: bool,
+    /// When the discussion was closed (`None` if open)
+    #[serde(default)]
+    pub closed_at: Option<DateTime<Utc>>,
+    /// Whether the discussion is locked to further comments
+    #[serde(default)]
+    pub locked: bool,
+    /// Poll attached to the discussion, if any
+    #[serde(default)]
+    pub poll: Option<Poll>,
+    /// comments holds the first page from the initial query; fetch_discussion
+    /// replaces it with all comments
     #[serde(default)]
     pub comments: DiscussionComments,
 }
 
 /// Comments connection with pagination info
-#[derive(Debug, Deserialize, Clone, PartialEq)]
+#[derive(Debug, Deserialize, Serialize, Clone, PartialEq)]
 #[serde(rename_all = "camelCase")]
 pub struct DiscussionComments {
     pub nodes: Option<Vec<Option<Comment>>>,
     pub page_info: PageInfo,
+    /// Total number of comments reported by the API (may include deleted comments)
+    #[serde(default)]
+    pub total_count: Option<u64>,
 }
 
 impl Default for DiscussionComments {
@@ -80,18 +203,96 @@ impl Default for DiscussionComments {
                 has_next_page: false,
                 end_cursor: None,
             },
+            total_count: None,
         }
     }
 }
 
+/// Rate limit budget reported in the `rateLimit` field of a GraphQL response
+#[derive(Debug, Deserialize, Serialize, Clone, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct RateLimitInfo {
+    /// Points charged for this query
+    pub cost: u64,
+    /// Maximum points available in the current window
+    pub limit: u64,
+    /// Points remaining in the current window
+    pub remaining: u64,
+    /// When the current window resets
+    pub reset_at: DateTime<Utc>,
+}
+
 /// GraphQL error response structure
 #[derive(Debug, Deserialize, Clone, PartialEq)]
 pub struct GraphQLError {
     pub message: String,
+    /// GitHub-specific error type, e.g. `NOT_FOUND`
+    #[serde(rename = "type", default)]
+    pub error_type: Option<String>,
     pub path: Option<Vec<serde_json::Value>>,
     pub extensions: Option<serde_json::Value>,
 }
 
+impl GraphQLError {
+    /// Error code from GitHub's `type` field, or `extensions.code` / `extensions.type`
+    pub fn code(&self) -> Option<&str> {
+        self.error_type.as_deref().or_else(|| {
+            let extensions = self.extensions.as_ref()?;
+            extensions
+                .get("code")
+                .or_else(|| extensions.get("type"))
+                .and_then(|c| c.as_str())
+        })
+    }
+
+    /// Whether the error reports a missing repository, discussion, or node
+    pub fn is_not_found(&self) -> bool {
+        self.code() == Some("NOT_FOUND")
+    }
+
+    /// Render `path` as e.g. `repository.discussion.comments.nodes[0]`
+    pub fn path_string(&self) -> Option<String> {
+        let path = self.path.as_ref().filter(|p| !p.is_empty())?;
+        let mut rendered = String::new();
+        for segment in path {
+            match segment {
+                serde_json::Value::Number(index) => rendered.push_str(&format!("[{}]", index)),
+                serde_json::Value::String(field) => {
+                    if !rendered.is_empty() {
+                        rendered.push('.');
+                    }
+                    rendered.push_str(field);
+                }
+                other => {
+                    if !rendered.is_empty() {
+                        rendered.push('.');
+                    }
+                    rendered.push_str(&other.to_string());
+                }
+            }
+        }
+        Some(rendered)
+    }
+}
+
+impl std::fmt::Display for GraphQLError {
+    /// Formats as `<message> (code: <code>, path: <path>)`, omitting absent parts
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.message)?;
+        let details: Vec<String> = [
+            self.code().map(|c| format!("code: {}", c)),
+            self.path_string().map(|p| format!("path: {}", p)),
+        ]
+        .into_iter()
+        .flatten()
+        .collect();
+        if !details.is_empty() {
+            write!(f, " ({})", details.join(", "))?;
+        }
+        Ok(())
+    }
+}
+
 /// Wrapper for GraphQL error responses
 #[derive(Debug, Deserialize, Clone, PartialEq)]
 pub struct ErrorResponse {
@@ -190,6 +391,63 @@ mod tests {
         let replies = comment.replies.nodes.unwrap();
         assert_eq!(replies.len(), 1);
         assert!(replies[0].is_some());
+        assert!(comment.replies.total_count.is_none());
+    }
+
+    #[test]
+    fn test_body_html_deserialization() {
+        let json_data = json!({
+            "id": "reply_1",
+            "databaseId": 789,
+            "author": {"login": "replier"},
+            "createdAt": "2024-01-15T12:00:00Z",
+            "body": "**Bold** @octocat",
+            "bodyHTML": "<p><strong>Bold</strong> <a href=\"https://github.com/octocat\">@octocat</a></p>"
+        });
+
+        let reply: Reply = serde_json::from_value(json_data).unwrap();
+        assert_eq!(reply.body, "**Bold** @octocat");
+        assert_eq!(
+            reply.body_html.as_deref(),
+            Some(
+                "<p><strong>Bold</strong> <a href=\"https://github.com/octocat\">@octocat</a></p>"
+            )
+        );
+        // Serialized back under the GraphQL field name
+        let value = serde_json::to_value(&reply).unwrap();
+        assert_eq!(value["bodyHTML"], reply.body_html.unwrap());
+
+        // bodyHTML is only fetched on request, and omitted from JSON output otherwise
+        let reply: Reply = serde_json::from_value(json!({
+            "id": "reply_2",
+            "databaseId": 790,
+            "author": null,
+            "createdAt": "2024-01-15T12:00:00Z",
+            "body": "Plain"
+        }))
+        .unwrap();
+        assert_eq!(reply.body_html, None);
+        assert!(
+            serde_json::to_value(&reply)
+                .unwrap()
+                .get("bodyHTML")
+                .is_none()
+        );
+    }
+
+    #[test]
+    fn test_connection_total_count_deserialization() {
+        let json_data = json!({
+            "nodes": [],
+            "pageInfo": {"hasNextPage": true, "endCursor": "cursor123"},
+            "totalCount": 250
+        });
+
+        let comments: DiscussionComments = serde_json::from_value(json_data.clone()).unwrap();
+        assert_eq!(comments.total_count, Some(250));
+
+        let replies: CommentReplies = serde_json::from_value(json_data).unwrap();
+        assert_eq!(replies.total_count, Some(250));
     }
 
     #[test]
@@ -210,6 +468,323 @@ mod tests {
         assert!(comment.author.is_none());
     }
 
+    #[test]
+    fn test_discussion_deserialization_with_answer() {
+        let json_data = json!({
+            "id": "discussion_123",
+            "title": "How do I do X?",
+            "number": 123,
+            "url": "https://github.com/test/repo/discussions/123",
+            "createdAt": "2024-01-15T10:30:00Z",
+            "body": "Question body",
+            "author": {"login": "asker"},
+            "answer": {"id": "comment_1"},
+            "answerChosenAt": "2024-01-16T09:00:00Z"
+        });
+
+        let discussion: Discussion = serde_json::from_value(json_data).unwrap();
+        assert_eq!(discussion.answer.unwrap().id, "comment_1");
+        assert_eq!(
+            discussion.answer_chosen_at.unwrap().to_rfc3339(),
+            "2024-01-16T09:00:00+00:00"
+        );
+    }
+
+    #[test]
+    fn test_discussion_deserialization_without_answer() {
+        let json_data = json!({
+            "id": "discussion_123",
+            "title": "General topic",
+            "number": 123,
+            "url": "https://github.com/test/repo/discussions/123",
+            "createdAt": "2024-01-15T10:30:00Z",
+            "body": "Body",
+            "author": {"login": "poster"},
+            "answer": null,
+            "answerChosenAt": null
+        });
+
+        let discussion: Discussion = serde_json::from_value(json_data).unwrap();
+        assert!(discussion.answer.is_none());
+        assert!(discussion.answer_chosen_at.is_none());
+    }
+
+    #[test]
+    fn test_discussion_deserialization_with_category() {
+        let json_data = json!({
+            "id": "discussion_123",
+            "title": "Hello",
+            "number": 123,
+            "url": "https://github.com/test/repo/discussions/123",
+            "createdAt": "2024-01-15T10:30:00Z",
+            "body": "Body",
+            "author": {"login": "poster"},
+            "category": {"name": "General", "emoji": ":speech_balloon:"}
+        });
+
+        let discussion: Discussion = serde_json::from_value(json_data).unwrap();
+        let category = discussion.category.unwrap();
+        assert_eq!(category.name, "General");
+        assert_eq!(category.emoji.as_deref(), Some(":speech_balloon:"));
+    }
+
+    #[test]
+    fn test_discussion_deserialization_with_poll() {
+        let json_data = json!({
+            "id": "discussion_123",
+            "title": "Which name?",
+            "number": 123,
+            "url": "https://github.com/test/repo/discussions/123",
+            "createdAt": "2024-01-15T10:30:00Z",
+            "body": "Vote below",
+            "author": {"login": "poster"},
+            "poll": {
+                "question": "Which name do you prefer?",
+                "totalVoteCount": 5,
+                "options": {
+                    "nodes": [
+                        {"option": "foo", "totalVoteCount": 3},
+                        {"option": "bar", "totalVoteCount": 2}
+                    ]
+                }
+            }
+        });
+
+        let discussion: Discussion = serde_json::from_value(json_data).unwrap();
+        let poll = discussion.poll.unwrap();
+        assert_eq!(poll.question, "Which name do you prefer?");
+        assert_eq!(poll.total_vote_count, 5);
+        let options: Vec<_> = poll.options.nodes.unwrap().into_iter().flatten().collect();
+        assert_eq!(
+            options,
+            vec![
+                PollOption {
+                    option: "foo".to_string(),
+                    total_vote_count: 3
+                },
+                PollOption {
+                    option: "bar".to_string(),
+                    total_vote_count: 2
+                }
+            ]
+        );
+    }
+
+    #[test]
+    fn test_discussion_deserialization_without_poll() {
+        let json_data = json!({
+            "id": "discussion_123",
+            "title": "Hello",
+            "number": 123,
+            "url": "https://github.com/test/repo/discussions/123",
+            "createdAt": "2024-01-15T10:30:00Z",
+            "body": "Body",
+            "author": {"login": "poster"},
+            "poll": null
+        });
+
+        let discussion: Discussion = serde_json::from_value(json_data).unwrap();
+        assert_eq!(discussion.poll, None);
+    }
+
+    #[test]
+    fn test_discussion_deserialization_with_state() {
+        let json_data = json!({
+            "id": "discussion_123",
+            "title": "Old topic",
+            "number": 123,
+            "url": "https://github.com/test/repo/discussions/123",
+            "createdAt": "2024-01-15T10:30:00Z",
+            "body": "Body",
+            "author": {"login": "poster"},
+            "closed": true,
+            "closedAt": "2024-02-01T00:00:00Z",
+            "locked": true
+        });
+
+        let discussion: Discussion = serde_json::from_value(json_data).unwrap();
+        assert!(discussion.closed);
+        assert!(discussion.locked);
+        assert_eq!(
+            discussion.closed_at.unwrap().to_rfc3339(),
+            "2024-02-01T00:00:00+00:00"
+        );
+    }
+
+    #[test]
+    fn test_discussion_deserialization_state_absent() {
+        let json_data = json!({
+            "id": "discussion_123",
+            "title": "Topic",
+            "number": 123,
+            "url": "https://github.com/test/repo/discussions/123",
+            "createdAt": "2024-01-15T10:30:00Z",
+            "body": "Body",
+            "author": {"login": "poster"}
+        });
+
+        let discussion: Discussion = serde_json::from_value(json_data).unwrap();
+        assert!(!discussion.closed);
+        assert!(!discussion.locked);
+        assert!(discussion.closed_at.is_none());
+    }
+
+    #[test]
+    fn test_category_deserialization_without_emoji() {
+        let category: DiscussionCategory =
+            serde_json::from_value(json!({"name": "Ideas"})).unwrap();
+        assert_eq!(category.name, "Ideas");
+        assert!(category.emoji.is_none());
+    }
+
+    #[test]
+    fn test_comment_deserialization_is_answer() {
+        let json_data = json!({
+            "id": "comment_1",
+            "databaseId": 456,
+            "author": {"login": "answerer"},
+            "createdAt": "2024-01-15T11:00:00Z",
+            "body": "The answer",
+            "isAnswer": true,
+            "replies": {
+                "nodes": [],
+                "pageInfo": {"hasNextPage": false, "endCursor": null}
+            }
+        });
+
+        let comment: Comment = serde_json::from_value(json_data).unwrap();
+        assert!(comment.is_answer);
+    }
+
+    #[test]
+    fn test_comment_deserialization_is_answer_absent() {
+        let json_data = json!({
+            "id": "comment_1",
+            "databaseId": 456,
+            "author": {"login": "commenter"},
+            "createdAt": "2024-01-15T11:00:00Z",
+            "body": "Not an answer",
+            "replies": {
+                "nodes": [],
+                "pageInfo": {"hasNextPage": false, "endCursor": null}
+            }
+        });
+
+        let comment: Comment = serde_json::from_value(json_data).unwrap();
+        assert!(!comment.is_answer);
+    }
+
+    #[test]
+    fn test_reply_deserialization_with_reaction_groups() {
+        let json_data = json!({
+            "id": "reply_1",
+            "databaseId": 789,
+            "author": {"login": "replier"},
+            "createdAt": "2024-01-15T12:00:00Z",
+            "body": "Test reply",
+            "reactionGroups": [
+                {"content": "THUMBS_UP", "reactors": {"totalCount": 5}},
+                {"content": "HEART", "reactors": {"totalCount": 0}}
+            ]
+        });
+
+        let reply: Reply = serde_json::from_value(json_data).unwrap();
+        let groups = reply.reaction_groups.unwrap();
+        assert_eq!(groups.len(), 2);
+        assert_eq!(groups[0].content, "THUMBS_UP");
+        assert_eq!(groups[0].reactors.total_count, 5);
+        assert_eq!(groups[1].reactors.total_count, 0);
+    }
+
+    #[test]
+    fn test_upvote_count_deserialization() {
+        let json_data = json!({
+            "id": "comment_1",
+            "databaseId": 456,
+            "author": {"login": "commenter"},
+            "createdAt": "2024-01-15T11:00:00Z",
+            "body": "Popular comment",
+            "upvoteCount": 5,
+            "replies": {
+                "nodes": [
+                    {
+                        "id": "reply_1",
+                        "databaseId": 789,
+                        "author": {"login": "replier"},
+                        "createdAt": "2024-01-15T12:00:00Z",
+                        "body": "Reply without upvoteCount"
+                    }
+                ],
+                "pageInfo": {"hasNextPage": false, "endCursor": null}
+            }
+        });
+
+        let comment: Comment = serde_json::from_value(json_data).unwrap();
+        assert_eq!(comment.upvote_count, 5);
+        let replies = comment.replies.nodes.unwrap();
+        assert_eq!(replies[0].as_ref().unwrap().upvote_count, 0);
+    }
+
+    #[test]
+    fn test_comment_deserialization_edited() {
+        let json_data = json!({
+            "id": "comment_1",
+            "databaseId": 456,
+            "author": {"login": "commenter"},
+            "createdAt": "2024-01-15T11:00:00Z",
+            "body": "Edited comment",
+            "lastEditedAt": "2024-01-16T08:00:00Z",
+            "replies": {
+                "nodes": [],
+                "pageInfo": {"hasNextPage": false, "endCursor": null}
+            }
+        });
+
+        let comment: Comment = serde_json::from_value(json_data).unwrap();
+        assert_eq!(
+            comment.last_edited_at.unwrap().to_rfc3339(),
+            "2024-01-16T08:00:00+00:00"
+        );
+    }
+
+    #[test]
+    fn test_comment_deserialization_not_edited() {
+        let json_data = json!({
+            "id": "comment_1",
+            "databaseId": 456,
+            "author": {"login": "commenter"},
+            "createdAt": "2024-01-15T11:00:00Z",
+            "body": "Original comment",
+            "lastEditedAt": null,
+            "replies": {
+                "nodes": [],
+                "pageInfo": {"hasNextPage": false, "endCursor": null}
+            }
+        });
+
+        let comment: Comment = serde_json::from_value(json_data).unwrap();
+        assert!(comment.last_edited_at.is_none());
+    }
+
+    #[test]
+    fn test_rate_limit_info_deserialization() {
+        let json_data = json!({
+            "cost": 1,
+            "limit": 5000,
+            "remaining": 4850,
+            "resetAt": "2024-01-15T11:00:00Z"
+        });
+
+        let rate_limit: RateLimitInfo = serde_json::from_value(json_data).unwrap();
+        assert_eq!(rate_limit.cost, 1);
+        assert_eq!(rate_limit.limit, 5000);
+        assert_eq!(rate_limit.remaining, 4850);
+        assert_eq!(
+            rate_limit.reset_at.to_rfc3339(),
+            "2024-01-15T11:00:00+00:00"
+        );
+    }
+
     #[test]
     fn test_page_info_deserialization() {
         let json_data = json!({
@@ -246,6 +821,52 @@ mod tests {
         assert!(error.message.contains("invalid"));
         assert!(error.path.is_some());
         assert!(error.extensions.is_some());
+        assert_eq!(error.code(), Some("GRAPHQL_VALIDATION_FAILED"));
+        assert!(!error.is_not_found());
+        assert_eq!(
+            error.to_string(),
+            "Field 'invalid' doesn't exist on type 'Query' \
+             (code: GRAPHQL_VALIDATION_FAILED, path: repository.discussion.invalid)"
+        );
+    }
+
+    #[test]
+    fn test_graphql_error_not_found_type() {
+        let json_data = json!({
+            "type": "NOT_FOUND",
+            "path": ["repository", "discussion"],
+            "message": "Could not resolve to a Discussion with the number of 999."
+        });
+
+        let error: GraphQLError = serde_json::from_value(json_data).unwrap();
+        assert_eq!(error.error_type.as_deref(), Some("NOT_FOUND"));
+        assert!(error.is_not_found());
+        assert_eq!(
+            error.to_string(),
+            "Could not resolve to a Discussion with the number of 999. \
+             (code: NOT_FOUND, path: repository.discussion)"
+        );
+    }
+
+    #[test]
+    fn test_graphql_error_path_with_index_and_no_details() {
+        let error: GraphQLError = serde_json::from_value(json!({
+            "message": "Something went wrong",
+            "path": ["node", "comments", "nodes", 3, "body"],
+            "extensions": {"type": "NOT_FOUND"}
+        }))
+        .unwrap();
+        assert_eq!(
+            error.path_string().as_deref(),
+            Some("node.comments.nodes[3].body")
+        );
+        assert!(error.is_not_found());
+
+        let error: GraphQLError =
+            serde_json::from_value(json!({"message": "Something went wrong"})).unwrap();
+        assert_eq!(error.code(), None);
+        assert_eq!(error.path_string(), None);
+        assert_eq!(error.to_string(), "Something went wrong");
     }
 
     #[test]
@@ -4,37 +4,425 @@
 // preserving all content verbatim except for heading escape (to preserve
 // document structure).
 
+use crate::cli::BodyFormat;
 use crate::error::{Error, Result};
-use crate::models::Discussion;
-use chrono::SecondsFormat;
+use crate::models::{Comment, DELETED_AUTHOR, Discussion, DiscussionCategory, Poll, ReactionGroup};
+use crate::stats::compute_stats;
+use crate::transform::{link_mentions, rewrite_comment_links, strip_html_comments};
+use chrono::{DateTime, SecondsFormat, Utc};
+use chrono_tz::Tz;
+use serde::Serialize;
+use std::collections::HashMap;
 use std::fs;
+use std::io::Write;
+use unicode_normalization::UnicodeNormalization;
 
 #[cfg(test)]
-use crate::models::{Comment, Reply};
+use crate::models::Reply;
+
+/// Options controlling how a discussion is rendered
+#[derive(Debug, Clone, Default)]
+pub struct OutputOptions {
+    /// Emit YAML front matter in place of the plain-text header
+    pub front_matter: bool,
+    /// Emit a `Replies: <N>` line under each comment's author line
+    pub reply_counts: bool,
+    /// Render authors in `_author:` lines as links to their GitHub profile
+    pub author_links: bool,
+    /// Insert a table of contents and emit an HTML anchor before each comment
+    pub toc: bool,
+    /// Timezone for rendered timestamps (UTC when `None`)
+    pub timezone: Option<Tz>,
+    /// strftime-style pattern for rendered timestamps (RFC 3339 when `None`)
+    pub date_format: Option<String>,
+    /// Rewrite links to comments and replies in this export to local anchors
+    pub local_links: bool,
+    /// Render each post, comment, and reply body as a Markdown blockquote
+    pub blockquote_bodies: bool,
+    /// Rewrite `@username` mentions in bodies into profile links
+    pub link_mentions: bool,
+    /// Normalize bodies to Unicode NFC (composed) form
+    pub normalize_unicode: bool,
+    /// Remove `<!-- ... -->` HTML comments from bodies
+    pub strip_html_comments: bool,
+    /// Which Markdown headings in bodies are escaped
+    pub heading_escape: HeadingEscape,
+    /// Emit a `[permalink](<url>)` line under each comment and reply
+    pub permalinks: bool,
+    /// Emit an HTML anchor before each comment and reply heading
+    pub anchor_ids: bool,
+    /// Omit the `## Original Post` section
+    pub comments_only: bool,
+    /// Omit the table of contents and the `## Comments` section
+    pub post_only: bool,
+    /// Append a `## Statistics` footer summarizing the whole discussion
+    pub stats: bool,
+    /// Render consecutive comments by the same author as one block
+    pub collapse_author_runs: bool,
+    /// Indent each reply's author line, body, and trailing lines under its
+    /// heading by rendering them as a blockquote
+    pub indent_replies: bool,
+    /// Emit `<!-- comment-db-id: <N> -->` / `<!-- reply-db-id: <N> -->`
+    /// markers before each comment and reply body
+    pub db_id_markers: bool,
+    /// Handlebars template rendered in place of the built-in layout
+    /// (see `crate::template`); the layout flags above then have no effect
+    pub template: Option<String>,
+    /// Render GitHub's HTML (`bodyHTML`) instead of the Markdown source
+    /// where it was fetched
+    pub body_format: BodyFormat,
+}
+
+/// Which Markdown headings at the start of body lines are backslash-escaped
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum HeadingEscape {
+    /// Keep headings verbatim
+    Never,
+    /// Escape every heading, so bodies cannot disturb the archive's structure
+    #[default]
+    All,
+    /// Escape headings of this level and deeper (e.g. 3 escapes `###` but not `#`)
+    MinLevel(usize),
+}
+
+impl HeadingEscape {
+    /// Whether a body line is escaped
+    fn escapes(self, line: &str) -> bool {
+        let level = line.chars().take_while(|&c| c == '#').count();
+        match self {
+            HeadingEscape::Never => false,
+            HeadingEscape::All => level > 0,
+            HeadingEscape::MinLevel(min) => level > 0 && level >= min,
+        }
+    }
+}
+
+impl OutputOptions {
+    /// Whether `<a id="comment-<N>"></a>` anchors precede comment headings
+    fn comment_anchors(&self) -> bool {
+        self.anchor_ids || self.toc || self.local_links
+    }
+
+    /// Whether `<a id="reply-<N>-<M>"></a>` anchors precede reply headings
+    fn reply_anchors(&self) -> bool {
+        self.anchor_ids || self.local_links
+    }
+}
+
+/// The deleted-author placeholder as rendered in Markdown
+///
+/// Escaped so renderers do not treat `<deleted>` as an unknown HTML tag
+/// and drop it.
+const DELETED_AUTHOR_MARKDOWN: &str = "\\<deleted\\>";
 
 /// Helper function to extract author login, returning "<deleted>" if null
 fn get_author_login(author: Option<&crate::models::Author>) -> &str {
     author
         .and_then(|a| a.login.as_deref())
-        .unwrap_or("<deleted>")
+        .unwrap_or(DELETED_AUTHOR)
+}
+
+/// Author login for Markdown output, with the deleted placeholder escaped
+pub(crate) fn markdown_author_login(author: Option<&crate::models::Author>) -> &str {
+    match get_author_login(author) {
+        DELETED_AUTHOR => DELETED_AUTHOR_MARKDOWN,
+        login => login,
+    }
+}
+
+/// Format a timestamp as RFC 3339, converted to `options.timezone` if set
+///
+/// UTC timestamps keep the `Z` suffix; other zones render their offset,
+/// e.g. `2024-01-15T19:30:00+09:00` for `Asia/Tokyo`.
+fn format_rfc3339(timestamp: &DateTime<Utc>, options: &OutputOptions) -> String {
+    match options.timezone {
+        Some(tz) => timestamp
+            .with_timezone(&tz)
+            .to_rfc3339_opts(SecondsFormat::Secs, true),
+        None => timestamp.to_rfc3339_opts(SecondsFormat::Secs, true),
+    }
+}
+
+/// Format a timestamp for display in the Markdown body
+///
+/// Applies `options.date_format` (a strftime-style pattern, validated by
+/// the CLI) in `options.timezone`, or falls back to RFC 3339.
+pub(crate) fn format_timestamp(timestamp: &DateTime<Utc>, options: &OutputOptions) -> String {
+    match (options.date_format.as_deref(), options.timezone) {
+        (Some(fmt), Some(tz)) => timestamp.with_timezone(&tz).format(fmt).to_string(),
+        (Some(fmt), None) => timestamp.format(fmt).to_string(),
+        (None, _) => format_rfc3339(timestamp, options),
+    }
+}
+
+/// Format the ` (edited <timestamp>)` suffix for an `_author:` metadata line
+///
+/// Returns an empty String if the content was never edited, or if the edit
+/// time equals the creation time.
+fn format_edited(
+    created_at: &DateTime<Utc>,
+    last_edited_at: Option<&DateTime<Utc>>,
+    options: &OutputOptions,
+) -> String {
+    match edited_timestamp(created_at, last_edited_at, options) {
+        Some(edited) => format!(" (edited {})", edited),
+        None => String::new(),
+    }
+}
+
+/// The formatted edit time, or `None` if never edited after creation
+pub(crate) fn edited_timestamp(
+    created_at: &DateTime<Utc>,
+    last_edited_at: Option<&DateTime<Utc>>,
+    options: &OutputOptions,
+) -> Option<String> {
+    last_edited_at
+        .filter(|edited| *edited != created_at)
+        .map(|edited| format_timestamp(edited, options))
+}
+
+/// Format the author for an `_author:` metadata line
+///
+/// With `options.author_links`, renders `[login](https://github.com/login)`.
+/// The `<deleted>` placeholder is never linked and is escaped as `\<deleted\>`.
+pub(crate) fn format_author(
+    author: Option<&crate::models::Author>,
+    options: &OutputOptions,
+) -> String {
+    match get_author_login(author) {
+        DELETED_AUTHOR => DELETED_AUTHOR_MARKDOWN.to_string(),
+        login if options.author_links => format!("[{}](https://github.com/{})", login, login),
+        login => login.to_string(),
+    }
+}
+
+/// Map a GitHub reaction content value to its emoji
+///
+/// Unknown values are returned as-is so new reaction kinds are not lost.
+fn reaction_emoji(content: &str) -> &str {
+    match content {
+        "THUMBS_UP" => "👍",
+        "THUMBS_DOWN" => "👎",
+        "LAUGH" => "😄",
+        "HOORAY" => "🎉",
+        "CONFUSED" => "😕",
+        "HEART" => "❤️",
+        "ROCKET" => "🚀",
+        "EYES" => "👀",
+        other => other,
+    }
+}
+
+/// Map the emoji shortcodes of GitHub's default discussion categories to Unicode
+///
+/// Unknown shortcodes are returned as-is; GitHub renders them in Markdown.
+fn category_emoji(shortcode: &str) -> &str {
+    match shortcode {
+        ":speech_balloon:" => "💬",
+        ":mega:" => "📣",
+        ":pray:" => "🙏",
+        ":bulb:" => "💡",
+        ":raised_hands:" => "🙌",
+        ":ballot_box:" => "🗳️",
+        other => other,
+    }
+}
+
+/// Generate the `Category:` header line, e.g. `Category: 💬 General\n`
+fn format_category(category: &DiscussionCategory) -> String {
+    format!("Category: {}\n", category_label(category))
+}
+
+/// The category name, prefixed with its emoji if it has one
+pub(crate) fn category_label(category: &DiscussionCategory) -> String {
+    match category.emoji.as_deref().filter(|e| !e.is_empty()) {
+        Some(emoji) => format!("{} {}", category_emoji(emoji), category.name),
+        None => category.name.clone(),
+    }
+}
+
+/// Generate the `Status:` header line for closed or locked discussions
+///
+/// Returns e.g. `Status: closed at 2024-02-01T00:00:00Z (locked)\n`, or an
+/// empty String for open, unlocked discussions.
+fn format_status(discussion: &Discussion, options: &OutputOptions) -> String {
+    match status_label(discussion, options) {
+        Some(status) => format!("Status: {}\n", status),
+        None => String::new(),
+    }
+}
+
+/// The status of a closed or locked discussion, e.g.
+/// `closed at 2024-02-01T00:00:00Z (locked)`
+///
+/// The closing time is left out when it is unknown. Returns `None` for open,
+/// unlocked discussions.
+pub(crate) fn status_label(discussion: &Discussion, options: &OutputOptions) -> Option<String> {
+    if !discussion.closed && !discussion.locked {
+        return None;
+    }
+    let state = match (discussion.closed, discussion.closed_at.as_ref()) {
+        (true, Some(closed_at)) => format!("closed at {}", format_timestamp(closed_at, options)),
+        (true, None) => "closed".to_string(),
+        (false, _) => "open".to_string(),
+    };
+    let locked = if discussion.locked { " (locked)" } else { "" };
+    Some(format!("{}{}", state, locked))
+}
+
+/// Generate a compact reaction summary line
+///
+/// Returns e.g. `Reactions: 👍 5  ❤️ 2\n\n`, or an empty String when there
+/// are no reactions (groups with zero reactors are skipped).
+fn format_reactions(groups: Option<&Vec<ReactionGroup>>) -> String {
+    match reaction_summary(groups) {
+        Some(summary) => format!("Reactions: {}\n\n", summary),
+        None => String::new(),
+    }
+}
+
+/// Compact reaction summary, e.g. `👍 5  ❤️ 2`
+///
+/// Returns `None` when there are no reactions.
+pub(crate) fn reaction_summary(groups: Option<&Vec<ReactionGroup>>) -> Option<String> {
+    let entries: Vec<String> = groups
+        .into_iter()
+        .flatten()
+        .filter(|g| g.reactors.total_count > 0)
+        .map(|g| format!("{} {}", reaction_emoji(&g.content), g.reactors.total_count))
+        .collect();
+
+    if entries.is_empty() {
+        None
+    } else {
+        Some(entries.join("  "))
+    }
+}
+
+/// Generate an upvote count line
+///
+/// Returns e.g. `Upvotes: 5\n\n`, or an empty String when there are none.
+fn format_upvotes(upvote_count: u64) -> String {
+    if upvote_count == 0 {
+        String::new()
+    } else {
+        format!("Upvotes: {}\n\n", upvote_count)
+    }
+}
+
+/// Generate the poll section of the original post
+///
+/// Returns e.g.
+/// `Poll: Which name?\n\n- foo: 3 votes (60%)\n- bar: 2 votes (40%)\n\nTotal votes: 5\n\n`,
+/// or an empty String when the discussion has no poll. Percentages are of
+/// the poll's total vote count, rounded to whole numbers.
+pub(crate) fn format_poll(poll: Option<&Poll>) -> String {
+    let Some(poll) = poll else {
+        return String::new();
+    };
+    let votes = |n: u64| if n == 1 { "vote" } else { "votes" };
+
+    let mut output = format!("Poll: {}\n\n", escape_markdown_text(&poll.question));
+    for option in poll.options.nodes.iter().flatten().flatten() {
+        let percent = if poll.total_vote_count == 0 {
+            0.0
+        } else {
+            option.total_vote_count as f64 * 100.0 / poll.total_vote_count as f64
+        };
+        output.push_str(&format!(
+            "- {}: {} {} ({:.0}%)\n",
+            escape_markdown_text(&option.option),
+            option.total_vote_count,
+            votes(option.total_vote_count),
+            percent
+        ));
+    }
+    output.push_str(&format!("\nTotal votes: {}\n\n", poll.total_vote_count));
+    output
+}
+
+/// Build the GitHub permalink to a comment or reply
+///
+/// Appends `#discussioncomment-<databaseId>` to the discussion URL, so the
+/// link also points at the right host on GitHub Enterprise Server.
+pub(crate) fn comment_permalink(discussion_url: &str, database_id: i64) -> String {
+    format!("{}#discussioncomment-{}", discussion_url, database_id)
+}
+
+/// Generate a permalink line for a comment or reply
+///
+/// Returns e.g. `[permalink](https://github.com/o/r/discussions/1#discussioncomment-456)\n\n`,
+/// or an empty String unless `options.permalinks` is set.
+fn format_permalink(discussion_url: &str, database_id: i64, options: &OutputOptions) -> String {
+    if options.permalinks {
+        format!(
+            "[permalink]({})\n\n",
+            comment_permalink(discussion_url, database_id)
+        )
+    } else {
+        String::new()
+    }
+}
+
+/// Escape Markdown inline syntax in plain text such as the discussion title
+///
+/// Backslash-escapes characters that would otherwise start code spans,
+/// emphasis, links, HTML tags, or an ATX closing sequence, so the text
+/// renders literally inside a heading.
+pub(crate) fn escape_markdown_text(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    for c in text.chars() {
+        if matches!(
+            c,
+            '\\' | '`' | '*' | '_' | '~' | '[' | ']' | '<' | '>' | '#'
+        ) {
+            escaped.push('\\');
+        }
+        escaped.push(c);
+    }
+    escaped
 }
 
 /// Escape Markdown heading syntax at the start of lines
 ///
-/// Prefixes '#' at the start of any line with a backslash to prevent
-/// it from being interpreted as a Markdown heading. This preserves
-/// document structure while keeping content readable.
+/// Prefixes '#' at the start of each line selected by `mode` with a
+/// backslash to prevent it from being interpreted as a Markdown heading.
+/// This preserves document structure while keeping content readable.
+///
+/// Lines are split with their terminators, so blank lines, trailing
+/// whitespace, and the exact number of trailing newlines survive unchanged.
+fn escape_headings(body: &str, mode: HeadingEscape) -> String {
+    let mut result = String::with_capacity(body.len());
+    for line in body.split_inclusive('\n') {
+        if mode.escapes(line) {
+            result.push('\\');
+        }
+        result.push_str(line);
+    }
+    result
+}
+
+/// Normalize CRLF line endings to LF
+///
+/// Replaces \r\n with \n, then replaces any remaining lone \r with \n
+/// to ensure pure LF line endings in the output file.
+fn normalize_crlf(body: &str) -> String {
+    body.replace("\r\n", "\n").replace('\r', "\n")
+}
+
+/// Prefix every line with `> ` to render it as a Markdown blockquote
 ///
-/// Preserves trailing newlines to maintain lossless fidelity.
-fn escape_headings(body: &str) -> String {
+/// Empty lines get a bare `>` so the quote is not interrupted. Existing
+/// quotes nest (`> quoted` becomes `> > quoted`). Preserves trailing newlines.
+fn blockquote(body: &str) -> String {
     let ends_with_newline = body.ends_with('\n');
     let mut result = body
         .lines()
         .map(|line| {
-            if line.starts_with('#') {
-                format!("\\{}", line)
+            if line.is_empty() {
+                ">".to_string()
             } else {
-                line.to_string()
+                format!("> {}", line)
             }
         })
         .collect::<Vec<_>>()
@@ -46,45 +434,167 @@ fn escape_headings(body: &str) -> String {
     result
 }
 
-/// Normalize CRLF line endings to LF
+/// Indent a rendered reply by quoting it
 ///
-/// Replaces \r\n with \n, then replaces any remaining lone \r with \n
-/// to ensure pure LF line endings in the output file.
-fn normalize_crlf(body: &str) -> String {
-    body.replace("\r\n", "\n").replace('\r', "\n")
+/// Leading spaces would not show once rendered (CommonMark ignores up to
+/// three) and could turn indented lines into code blocks, while a blockquote
+/// renders indented and keeps the reply's own structure, including code
+/// blocks and indentation, intact. The trailing blank lines separating the
+/// reply from what follows stay outside the quote.
+fn indent_reply(content: &str) -> String {
+    let quoted = content.trim_end_matches('\n');
+    let trailing = content.len() - quoted.len();
+    format!("{}{}", blockquote(quoted), "\n".repeat(trailing))
 }
 
 /// Process body content for output
 ///
 /// Applies heading escape and CRLF normalization while preserving
-/// all other content verbatim.
-fn process_body(body: &str) -> String {
+/// all other content verbatim, including blank lines, trailing whitespace,
+/// and trailing newlines.
+fn process_body(body: &str, heading_escape: HeadingEscape) -> String {
     let normalized = normalize_crlf(body);
-    escape_headings(&normalized)
+    escape_headings(&normalized, heading_escape)
+}
+
+/// Process body content for output, escaping headings per
+/// `options.heading_escape`, then apply `options.normalize_unicode` and
+/// `options.link_mentions`
+///
+/// With `options.strip_html_comments`, comments are removed before anything
+/// else, so text revealed by the removal is escaped like any other.
+fn process_body_with_options(body: &str, options: &OutputOptions) -> String {
+    let mut processed = if options.strip_html_comments {
+        process_body(&strip_html_comments(body), options.heading_escape)
+    } else {
+        process_body(body, options.heading_escape)
+    };
+    if options.normalize_unicode {
+        processed = processed.nfc().collect();
+    }
+    if options.link_mentions {
+        processed = link_mentions(&processed);
+    }
+    processed
+}
+
+/// The body to render: `body_html` with `BodyFormat::Html` when it was
+/// fetched, otherwise the Markdown `body`
+pub(crate) fn source_body<'a>(
+    body: &'a str,
+    body_html: Option<&'a str>,
+    options: &OutputOptions,
+) -> &'a str {
+    match (options.body_format, body_html) {
+        (BodyFormat::Html, Some(html)) => html,
+        _ => body,
+    }
+}
+
+/// Process body content and, if `anchors` is non-empty, rewrite links to
+/// comments of this export into local anchors, then apply
+/// `options.blockquote_bodies`
+///
+/// Links are rewritten before blockquoting, so code blocks are still
+/// recognized and left alone.
+pub(crate) fn render_body(
+    body: &str,
+    anchors: &HashMap<i64, String>,
+    options: &OutputOptions,
+) -> String {
+    let mut processed = process_body_with_options(body, options);
+    if !anchors.is_empty() {
+        processed = rewrite_comment_links(&processed, anchors);
+    }
+    if options.blockquote_bodies {
+        blockquote(&processed)
+    } else {
+        processed
+    }
 }
 
 /// Generate header section with discussion metadata
 ///
 /// Returns a String containing:
-/// - # <title>
+/// - # <title> (with Markdown syntax escaped)
 /// - Discussion: <owner>/<repo>#<number>
 /// - URL: https://github.com/<owner>/<repo>/discussions/<number>
 /// - Created at: <ISO8601>
 /// - Author: <login>
+/// - Category: <emoji> <name> (only when the category is known)
+/// - Status: <closed at <ISO8601>|open> (locked) (only when closed or locked)
 /// - ---
-pub(crate) fn generate_header(discussion: &Discussion, owner: &str, repo: &str) -> String {
-    let author = get_author_login(discussion.author.as_ref());
+pub(crate) fn generate_header(
+    discussion: &Discussion,
+    owner: &str,
+    repo: &str,
+    options: &OutputOptions,
+) -> String {
+    let author = markdown_author_login(discussion.author.as_ref());
+    let category = discussion
+        .category
+        .as_ref()
+        .map(format_category)
+        .unwrap_or_default();
     format!(
-        "# {}\n\nDiscussion: {}/{}#{}\nURL: {}\n\nCreated at: {}\nAuthor: {}\n\n---\n",
-        discussion.title,
+        "# {}\n\nDiscussion: {}/{}#{}\nURL: {}\n\nCreated at: {}\nAuthor: {}\n{}{}\n---\n",
+        escape_markdown_text(&discussion.title),
         owner,
         repo,
         discussion.number,
         discussion.url,
-        discussion
-            .created_at
-            .to_rfc3339_opts(SecondsFormat::Secs, true),
-        author
+        format_timestamp(&discussion.created_at, options),
+        author,
+        category,
+        format_status(discussion, options)
+    )
+}
+
+/// Quote a string as a YAML double-quoted scalar
+///
+/// Escapes backslashes, double quotes, and control characters so that
+/// values containing YAML-special characters (e.g. `:` or `#`) stay valid.
+fn yaml_quote(value: &str) -> String {
+    let mut quoted = String::with_capacity(value.len() + 2);
+    quoted.push('"');
+    for c in value.chars() {
+        match c {
+            '\\' => quoted.push_str("\\\\"),
+            '"' => quoted.push_str("\\\""),
+            '\n' => quoted.push_str("\\n"),
+            '\r' => quoted.push_str("\\r"),
+            '\t' => quoted.push_str("\\t"),
+            c if c.is_control() => quoted.push_str(&format!("\\u{:04x}", c as u32)),
+            c => quoted.push(c),
+        }
+    }
+    quoted.push('"');
+    quoted
+}
+
+/// Generate YAML front matter with discussion metadata
+///
+/// Returns a String containing a `---`-delimited block with `title`,
+/// `discussion_number`, `url`, `created_at`, `author`, and `repository`,
+/// suitable for static site generators such as Hugo or Jekyll.
+///
+/// `created_at` is always RFC 3339 so it stays machine-readable, even when
+/// a custom `date_format` is set.
+pub(crate) fn generate_front_matter(
+    discussion: &Discussion,
+    owner: &str,
+    repo: &str,
+    options: &OutputOptions,
+) -> String {
+    let author = get_author_login(discussion.author.as_ref());
+    format!(
+        "---\ntitle: {}\ndiscussion_number: {}\nurl: {}\ncreated_at: {}\nauthor: {}\nrepository: {}\n---\n\n",
+        yaml_quote(&discussion.title),
+        discussion.number,
+        yaml_quote(&discussion.url),
+        format_rfc3339(&discussion.created_at, options),
+        yaml_quote(author),
+        yaml_quote(&format!("{}/{}", owner, repo))
     )
 }
 
@@ -92,71 +602,328 @@ pub(crate) fn generate_header(discussion: &Discussion, owner: &str, repo: &str)
 ///
 /// Returns a String containing:
 /// - ## Original Post
-/// - _author: <login> (<ISO8601>)_
-/// - <body content verbatim except heading escape>
+/// - _author: <login> (<ISO8601>)_ (with ` (edited <ISO8601>)` if edited)
+/// - <body content verbatim except heading escape>, followed by a blank line
+/// - Poll: <question> with each option's votes (only when there is a poll)
+/// - Upvotes: <N> (only when there are upvotes)
+/// - Reactions: <summary> (only when there are reactions)
 /// - ---
-pub(crate) fn generate_original_post(discussion: &Discussion) -> String {
-    let author = get_author_login(discussion.author.as_ref());
-    let body = process_body(&discussion.body);
+pub(crate) fn generate_original_post(discussion: &Discussion, options: &OutputOptions) -> String {
+    render_original_post(discussion, &link_anchors(discussion, options), options)
+}
+
+/// Like `generate_original_post`, rewriting comment links to `anchors`
+fn render_original_post(
+    discussion: &Discussion,
+    anchors: &HashMap<i64, String>,
+    options: &OutputOptions,
+) -> String {
+    let author = format_author(discussion.author.as_ref(), options);
+    let body = render_body(
+        source_body(&discussion.body, discussion.body_html.as_deref(), options),
+        anchors,
+        options,
+    );
     format!(
-        "## Original Post\n\n_author: {} ({})_\n\n{}\n\n---\n",
+        "## Original Post\n\n_author: {} ({}){}_\n\n{}\n\n{}{}{}---\n",
         author,
-        discussion
-            .created_at
-            .to_rfc3339_opts(SecondsFormat::Secs, true),
-        body
+        format_timestamp(&discussion.created_at, options),
+        format_edited(
+            &discussion.created_at,
+            discussion.last_edited_at.as_ref(),
+            options
+        ),
+        body,
+        format_poll(discussion.poll.as_ref()),
+        format_upvotes(discussion.upvote_count),
+        format_reactions(discussion.reaction_groups.as_ref())
     )
 }
 
+/// Number of replies to report for a comment
+///
+/// Prefers the number of fetched reply nodes, since that matches what is
+/// rendered after pagination, and falls back to `totalCount` when the nodes
+/// are absent.
+pub(crate) fn reply_count(replies: &crate::models::CommentReplies) -> u64 {
+    match replies.nodes {
+        Some(ref nodes) => nodes.iter().flatten().count() as u64,
+        None => replies.total_count.unwrap_or(0),
+    }
+}
+
+/// Anchor id for the Nth comment, used by the table of contents
+fn comment_anchor(comment_num: usize) -> String {
+    format!("comment-{}", comment_num)
+}
+
+/// Anchor id for reply M of the Nth comment
+fn reply_anchor(comment_num: usize, reply_num: usize) -> String {
+    format!("reply-{}-{}", comment_num, reply_num)
+}
+
+/// Map comment and reply database IDs to links to their anchors in this
+/// export, e.g. `#comment-1`
+///
+/// Returns an empty map unless `options.local_links` is set. Numbering
+/// matches `generate_comments`.
+pub(crate) fn link_anchors(
+    discussion: &Discussion,
+    options: &OutputOptions,
+) -> HashMap<i64, String> {
+    comment_link_anchors(&discussion_comments(discussion), 1, false, options)
+}
+
+/// Like `link_anchors`, linking into the comment files of a split export
+fn split_link_anchors(discussion: &Discussion, options: &OutputOptions) -> HashMap<i64, String> {
+    comment_link_anchors(&discussion_comments(discussion), 1, true, options)
+}
+
+/// Like `link_anchors`, for `comments` numbered from `first_num`
+///
+/// With `split`, the links point into the files of a split export, e.g.
+/// `comment-1.md#comment-1`.
+fn comment_link_anchors(
+    comments: &[&Comment],
+    first_num: usize,
+    split: bool,
+    options: &OutputOptions,
+) -> HashMap<i64, String> {
+    let mut anchors = HashMap::new();
+    if !options.local_links {
+        return anchors;
+    }
+    for (i, comment) in comments.iter().enumerate() {
+        let comment_num = first_num + i;
+        let file = if split {
+            comment_file_name(comment_num)
+        } else {
+            String::new()
+        };
+        anchors.insert(
+            comment.database_id,
+            format!("{}#{}", file, comment_anchor(comment_num)),
+        );
+        for (j, reply) in comment.replies.nodes.iter().flatten().flatten().enumerate() {
+            anchors.insert(
+                reply.database_id,
+                format!("{}#{}", file, reply_anchor(comment_num, j + 1)),
+            );
+        }
+    }
+    anchors
+}
+
+/// The fetched comments of a discussion, skipping deleted (`null`) entries
+pub(crate) fn discussion_comments(discussion: &Discussion) -> Vec<&Comment> {
+    discussion
+        .comments
+        .nodes
+        .iter()
+        .flatten()
+        .flatten()
+        .collect()
+}
+
+/// Generate table of contents section
+///
+/// Returns a String containing:
+/// - ## Table of Contents
+/// - For each comment: - [Comment <N> by <login>](#comment-<N>)
+///
+/// Returns an empty String if there are no comments.
+pub(crate) fn generate_toc(discussion: &Discussion) -> String {
+    let comments = discussion_comments(discussion);
+    if comments.is_empty() {
+        return String::new();
+    }
+
+    let mut output = String::from("## Table of Contents\n\n");
+    for (i, comment) in comments.iter().enumerate() {
+        let comment_num = i + 1;
+        output.push_str(&format!(
+            "- [Comment {} by {}](#{})\n",
+            comment_num,
+            markdown_author_login(comment.author.as_ref()),
+            comment_anchor(comment_num)
+        ));
+    }
+    output.push('\n');
+    output
+}
+
 /// Generate comments section with all comments and replies
 ///
 /// Returns a String containing:
 /// - ## Comments
 /// - For each comment: ### Comment <N>
-///   - _author: <login> (<ISO8601>)_
+///   (preceded by `<a id="comment-<N>"></a>` with `options.anchor_ids`, `options.toc`,
+///   or `options.local_links`)
+///   - _author: <login> (<ISO8601>)_ (with ` (edited <ISO8601>)` if edited)
+///   - Replies: <N> (only with `options.reply_counts` and at least one reply)
+///   - <!-- comment-db-id: <ID> --> (only with `options.db_id_markers`)
 ///   - <body content verbatim except heading escape>
+///   - Upvotes: <N> (only when there are upvotes)
+///   - Reactions: <summary> (only when there are reactions)
+///   - [permalink](<url>) (only with `options.permalinks`)
 ///   - For each reply: #### Reply <N.M>
-///     - _author: <login> (<ISO8601>)_
+///     (preceded by `<a id="reply-<N>-<M>"></a>` with `options.anchor_ids` or
+///     `options.local_links`)
+///     - _author: <login> (<ISO8601>)_ (with ` (edited <ISO8601>)` if edited)
+///     - <!-- reply-db-id: <ID> --> (only with `options.db_id_markers`)
 ///     - <body content verbatim except heading escape>
+///     - Upvotes: <N> (only when there are upvotes)
+///     - Reactions: <summary> (only when there are reactions)
+///     - [permalink](<url>) (only with `options.permalinks`)
+///
+/// The accepted answer (if any) is marked as `### Comment <N> (✓ Accepted Answer)`.
+///
+/// With `options.collapse_author_runs`, a comment continuing its author's run
+/// (see `continues_author_run`) gets `* * *` and `_Comment <N> (<ISO8601>)_`
+/// instead of its heading and author line, merging it into the block above.
+///
+/// With `options.indent_replies`, everything under a `#### Reply` heading
+/// (author line, body, upvotes, reactions, and permalink) is indented as a
+/// blockquote (see `indent_reply`). Bodies are indented after heading escape
+/// and `options.blockquote_bodies`, so quoted reply bodies nest one level
+/// deeper.
 ///
 /// If there are no comments, still emits the ## Comments heading.
-pub(crate) fn generate_comments(discussion: &Discussion) -> String {
+pub(crate) fn generate_comments(discussion: &Discussion, options: &OutputOptions) -> String {
     let mut output = String::from("## Comments\n\n");
+    output.push_str(&render_comments(
+        discussion,
+        &discussion_comments(discussion),
+        1,
+        &link_anchors(discussion, options),
+        options,
+    ));
+    output
+}
 
-    if let Some(ref comments) = discussion.comments.nodes {
-        let mut comment_num = 0;
-        for comment in comments.iter().flatten() {
-            comment_num += 1;
-            let author = get_author_login(comment.author.as_ref());
-            let body = process_body(&comment.body);
+/// Whether `comment` continues the run of comments started by `prev`
+///
+/// A run joins consecutive comments by the same author as long as no replies
+/// come between them. Deleted authors never form runs, since they may be
+/// different people, and the accepted answer always keeps its own heading.
+fn continues_author_run(prev: &Comment, comment: &Comment, answer_id: Option<&str>) -> bool {
+    let is_answer = |c: &Comment| c.is_answer || answer_id == Some(c.id.as_str());
+    let login = get_author_login(comment.author.as_ref());
+    login != DELETED_AUTHOR
+        && login == get_author_login(prev.author.as_ref())
+        && reply_count(&prev.replies) == 0
+        && !is_answer(prev)
+        && !is_answer(comment)
+}
 
+/// Render `comments` and their replies, numbering them from `first_num` and
+/// rewriting comment links to `anchors` (see `link_anchors`)
+fn render_comments(
+    discussion: &Discussion,
+    comments: &[&Comment],
+    first_num: usize,
+    anchors: &HashMap<i64, String>,
+    options: &OutputOptions,
+) -> String {
+    let mut output = String::new();
+    let answer_id = discussion.answer.as_ref().map(|a| a.id.as_str());
+
+    for (i, comment) in comments.iter().enumerate() {
+        let comment_num = first_num + i;
+        let author = format_author(comment.author.as_ref(), options);
+        let body = render_body(
+            source_body(&comment.body, comment.body_html.as_deref(), options),
+            anchors,
+            options,
+        );
+        let is_answer = comment.is_answer || answer_id == Some(comment.id.as_str());
+        let answer_marker = if is_answer {
+            " (✓ Accepted Answer)"
+        } else {
+            ""
+        };
+        let reply_count_line = match reply_count(&comment.replies) {
+            n if options.reply_counts && n > 0 => format!("Replies: {}\n\n", n),
+            _ => String::new(),
+        };
+        let created_at = format_timestamp(&comment.created_at, options);
+        let edited = format_edited(
+            &comment.created_at,
+            comment.last_edited_at.as_ref(),
+            options,
+        );
+        let heading = if options.collapse_author_runs
+            && i > 0
+            && continues_author_run(comments[i - 1], comment, answer_id)
+        {
+            format!(
+                "* * *\n\n_Comment {} ({}){}_",
+                comment_num, created_at, edited
+            )
+        } else {
+            format!(
+                "### Comment {}{}\n\n_author: {} ({}){}_",
+                comment_num, answer_marker, author, created_at, edited
+            )
+        };
+
+        if options.comment_anchors() {
             output.push_str(&format!(
-                "\n### Comment {}\n\n_author: {} ({})_\n\n{}\n\n",
-                comment_num,
-                author,
-                comment
-                    .created_at
-                    .to_rfc3339_opts(SecondsFormat::Secs, true),
-                body
+                "\n<a id=\"{}\"></a>\n",
+                comment_anchor(comment_num)
             ));
+        }
 
-            // Add replies if present
-            if let Some(ref replies) = comment.replies.nodes {
-                let mut reply_num = 0;
-                for reply in replies.iter().flatten() {
-                    reply_num += 1;
-                    let reply_author = get_author_login(reply.author.as_ref());
-                    let reply_body = process_body(&reply.body);
-
+        output.push_str(&format!(
+            "\n{}\n\n{}{}{}\n\n{}{}{}",
+            heading,
+            reply_count_line,
+            format_db_id_marker(COMMENT_DB_ID_MARKER, comment.database_id, options),
+            body,
+            format_upvotes(comment.upvote_count),
+            format_reactions(comment.reaction_groups.as_ref()),
+            format_permalink(&discussion.url, comment.database_id, options)
+        ));
+
+        // Add replies if present
+        if let Some(ref replies) = comment.replies.nodes {
+            let mut reply_num = 0;
+            for reply in replies.iter().flatten() {
+                reply_num += 1;
+                let reply_author = format_author(reply.author.as_ref(), options);
+                let reply_body = render_body(
+                    source_body(&reply.body, reply.body_html.as_deref(), options),
+                    anchors,
+                    options,
+                );
+
+                if options.reply_anchors() {
                     output.push_str(&format!(
-                        "\n#### Reply {}.{}\n\n_author: {} ({})_\n\n{}\n\n",
-                        comment_num,
-                        reply_num,
-                        reply_author,
-                        reply.created_at.to_rfc3339_opts(SecondsFormat::Secs, true),
-                        reply_body
+                        "\n<a id=\"{}\"></a>\n",
+                        reply_anchor(comment_num, reply_num)
                     ));
                 }
+
+                let content = format!(
+                    "_author: {} ({}){}_\n\n{}{}\n\n{}{}{}",
+                    reply_author,
+                    format_timestamp(&reply.created_at, options),
+                    format_edited(&reply.created_at, reply.last_edited_at.as_ref(), options),
+                    format_db_id_marker(REPLY_DB_ID_MARKER, reply.database_id, options),
+                    reply_body,
+                    format_upvotes(reply.upvote_count),
+                    format_reactions(reply.reaction_groups.as_ref()),
+                    format_permalink(&discussion.url, reply.database_id, options)
+                );
+                let content = if options.indent_replies {
+                    indent_reply(&content)
+                } else {
+                    content
+                };
+                output.push_str(&format!(
+                    "\n#### Reply {}.{}\n\n{}",
+                    comment_num, reply_num, content
+                ));
             }
         }
     }
@@ -164,6 +931,145 @@ pub(crate) fn generate_comments(discussion: &Discussion) -> String {
     output
 }
 
+/// Name of the marker recording a comment's database ID
+const COMMENT_DB_ID_MARKER: &str = "comment-db-id";
+
+/// Name of the marker recording a reply's database ID
+///
+/// Distinct from `COMMENT_DB_ID_MARKER` so `--append` only counts comments.
+const REPLY_DB_ID_MARKER: &str = "reply-db-id";
+
+/// Machine-readable `<!-- <name>: <N> -->` marker line for a database ID
+///
+/// An HTML comment, so it is invisible when the Markdown is rendered.
+/// Returns an empty String unless `options.db_id_markers` is set.
+fn format_db_id_marker(name: &str, database_id: i64, options: &OutputOptions) -> String {
+    if options.db_id_markers {
+        format!("<!-- {}: {} -->\n", name, database_id)
+    } else {
+        String::new()
+    }
+}
+
+/// Parse a `<!-- comment-db-id: <N> -->` marker line
+///
+/// Surrounding whitespace is ignored. Returns `None` if `line` is not a
+/// comment marker; `<!-- reply-db-id: <N> -->` markers are not matched.
+pub fn parse_db_id_marker(line: &str) -> Option<i64> {
+    line.trim()
+        .strip_prefix("<!--")?
+        .trim_start()
+        .strip_prefix(COMMENT_DB_ID_MARKER)?
+        .strip_prefix(':')?
+        .strip_suffix("-->")?
+        .trim()
+        .parse()
+        .ok()
+}
+
+/// Whether a line starts a comment in an export: a `### Comment` heading,
+/// or the `* * *` separator of a comment merged into its author's run
+fn is_comment_start(line: &str) -> bool {
+    line.starts_with("### Comment ") || line == "* * *"
+}
+
+/// Database IDs of the comments in an existing export, in file order
+///
+/// Only markers where the exporter writes them are counted: on the first line
+/// after a comment's `_author:` (or collapsed `_Comment N_`) line and its
+/// optional `Replies:` line. Markers quoted in comment bodies or code blocks
+/// are ignored.
+pub fn exported_comment_ids(markdown: &str) -> Vec<i64> {
+    enum State {
+        Body,
+        Heading,
+        Author,
+    }
+
+    let mut ids = Vec::new();
+    let mut state = State::Body;
+    for line in markdown.lines() {
+        if is_comment_start(line) {
+            state = State::Heading;
+            continue;
+        }
+        state = match state {
+            State::Heading if line.is_empty() => State::Heading,
+            State::Heading if line.starts_with("_author: ") || line.starts_with("_Comment ") => {
+                State::Author
+            }
+            State::Author if line.is_empty() || line.starts_with("Replies: ") => State::Author,
+            State::Author => {
+                ids.extend(parse_db_id_marker(line));
+                State::Body
+            }
+            _ => State::Body,
+        };
+    }
+    ids
+}
+
+/// Highest comment database ID in an existing export
+///
+/// Returns `None` if the export has no `<!-- comment-db-id: <N> -->` markers,
+/// e.g. because it was not written with `--append`.
+pub fn last_exported_comment_id(markdown: &str) -> Option<i64> {
+    exported_comment_ids(markdown).into_iter().max()
+}
+
+/// Comments rendered by `format_appended_comments`
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct AppendedComments {
+    /// Markdown to append to the existing export (empty if nothing is new)
+    pub markdown: String,
+    /// Number of comments rendered
+    pub comments: usize,
+    /// Number of replies rendered under those comments
+    pub replies: usize,
+}
+
+/// Format the comments of `discussion` that are newer than an existing export
+///
+/// `existing` is the Markdown of an export written with `options.db_id_markers`.
+/// Only comments whose database ID is above the highest marker in `existing`
+/// are rendered, numbered after the comments already exported. New replies to
+/// already-exported comments are not included.
+///
+/// Returns `Error::InvalidArgs` if `existing` has comments but no markers
+/// (e.g. it was exported without `--append`), since every comment would
+/// otherwise be appended a second time.
+pub fn format_appended_comments(
+    discussion: &Discussion,
+    existing: &str,
+    options: &OutputOptions,
+) -> Result<AppendedComments> {
+    let exported = exported_comment_ids(existing);
+    if exported.is_empty() && existing.lines().any(is_comment_start) {
+        return Err(Error::InvalidArgs(
+            "existing export has no db-id markers; re-export with --append".to_string(),
+        ));
+    }
+    let last_id = exported.iter().copied().max();
+    let new_comments: Vec<_> = discussion_comments(discussion)
+        .into_iter()
+        .filter(|comment| last_id.is_none_or(|last| comment.database_id > last))
+        .collect();
+    Ok(AppendedComments {
+        markdown: render_comments(
+            discussion,
+            &new_comments,
+            exported.len() + 1,
+            &comment_link_anchors(&new_comments, exported.len() + 1, false, options),
+            options,
+        ),
+        comments: new_comments.len(),
+        replies: new_comments
+            .iter()
+            .map(|comment| comment.replies.nodes.iter().flatten().flatten().count())
+            .sum(),
+    })
+}
+
 /// Format complete discussion as Markdown
 ///
 /// Concatenates header, original post, and comments sections.
@@ -171,11 +1077,318 @@ pub(crate) fn generate_comments(discussion: &Discussion) -> String {
 ///
 /// Returns complete Markdown String ready for file output.
 pub fn format_discussion(discussion: &Discussion, owner: &str, repo: &str) -> String {
-    let header = generate_header(discussion, owner, repo);
-    let original_post = generate_original_post(discussion);
-    let comments = generate_comments(discussion);
+    format_discussion_with_options(discussion, owner, repo, &OutputOptions::default())
+}
+
+/// Generate the statistics footer
+///
+/// Returns a String containing:
+/// - ## Statistics
+/// - Comments, Replies, Participants, and Words counts
+/// - Date span: <ISO8601> to <ISO8601>
+///
+/// The statistics cover the whole discussion, including sections the
+/// export omits.
+pub(crate) fn generate_stats(discussion: &Discussion, options: &OutputOptions) -> String {
+    let stats = compute_stats(discussion);
+    format!(
+        "\n## Statistics\n\n- Comments: {}\n- Replies: {}\n- Participants: {}\n- Words: {}\n- Date span: {} to {}\n",
+        stats.comments,
+        stats.replies,
+        stats.participants,
+        stats.words,
+        format_timestamp(&stats.first_activity, options),
+        format_timestamp(&stats.last_activity, options)
+    )
+}
+
+/// Format complete discussion as Markdown using the given output options
+///
+/// When `options.front_matter` is set, YAML front matter replaces the
+/// plain-text header so metadata is not duplicated. `options.comments_only`
+/// and `options.post_only` drop the original post or the comments; the
+/// header is always emitted. `options.stats` appends the statistics footer.
+pub fn format_discussion_with_options(
+    discussion: &Discussion,
+    owner: &str,
+    repo: &str,
+    options: &OutputOptions,
+) -> String {
+    let header = if options.front_matter {
+        generate_front_matter(discussion, owner, repo, options)
+    } else {
+        generate_header(discussion, owner, repo, options)
+    };
+    let toc = if options.toc && !options.post_only {
+        generate_toc(discussion)
+    } else {
+        String::new()
+    };
+    let original_post = if options.comments_only {
+        String::new()
+    } else {
+        generate_original_post(discussion, options)
+    };
+    let comments = if options.post_only {
+        String::new()
+    } else {
+        generate_comments(discussion, options)
+    };
+    let stats = if options.stats {
+        generate_stats(discussion, options)
+    } else {
+        String::new()
+    };
+
+    format!("{}{}{}{}{}", header, toc, original_post, comments, stats)
+}
+
+/// A Markdown file of a split export
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SplitFile {
+    /// File name, relative to the directory of the index file
+    pub name: String,
+    /// Markdown content of the file
+    pub content: String,
+}
+
+/// File name of the Nth comment in a split export
+pub fn comment_file_name(comment_num: usize) -> String {
+    format!("comment-{}.md", comment_num)
+}
+
+/// Generate the index file of a split export
+///
+/// Returns a String containing the header (or front matter), the original
+/// post unless `options.comments_only`, and a `## Comments` section with one
+/// link per comment: - [Comment <N> by <login>](comment-<N>.md)
+///
+/// With `options.local_links`, links in the original post point into the
+/// comment files (see `split_link_anchors`).
+pub(crate) fn generate_split_index(
+    discussion: &Discussion,
+    owner: &str,
+    repo: &str,
+    options: &OutputOptions,
+) -> String {
+    let mut output = if options.front_matter {
+        generate_front_matter(discussion, owner, repo, options)
+    } else {
+        generate_header(discussion, owner, repo, options)
+    };
+    if !options.comments_only {
+        output.push_str(&render_original_post(
+            discussion,
+            &split_link_anchors(discussion, options),
+            options,
+        ));
+    }
+
+    output.push_str("## Comments\n\n");
+    let answer_id = discussion.answer.as_ref().map(|a| a.id.as_str());
+    for (i, comment) in discussion_comments(discussion).iter().enumerate() {
+        let comment_num = i + 1;
+        let is_answer = comment.is_answer || answer_id == Some(comment.id.as_str());
+        output.push_str(&format!(
+            "- [Comment {} by {}]({}){}\n",
+            comment_num,
+            markdown_author_login(comment.author.as_ref()),
+            comment_file_name(comment_num),
+            if is_answer {
+                " (✓ Accepted Answer)"
+            } else {
+                ""
+            }
+        ));
+    }
+    if options.stats {
+        output.push_str(&generate_stats(discussion, options));
+    }
+    output
+}
+
+/// Generate the file for a single comment of a split export
+///
+/// Returns a String containing a `# <title>` heading, a link back to
+/// `index_name`, and the comment with its replies as rendered by
+/// `generate_comments`, rewriting comment links to `anchors` (see
+/// `split_link_anchors`).
+pub(crate) fn generate_comment_file(
+    discussion: &Discussion,
+    comment: &Comment,
+    comment_num: usize,
+    index_name: &str,
+    anchors: &HashMap<i64, String>,
+    options: &OutputOptions,
+) -> String {
+    format!(
+        "# {}\n\n[Back to index]({})\n{}",
+        escape_markdown_text(&discussion.title),
+        index_name,
+        render_comments(discussion, &[comment], comment_num, anchors, options)
+    )
+}
+
+/// Format a discussion as an index file plus one file per comment
+///
+/// The first entry is the index, named `index_name`; it is followed by one
+/// `comment-<N>.md` per comment, each containing the comment and its replies.
+/// `options.toc` and `options.post_only` are ignored, since the index already
+/// lists every comment; the CLI rejects them with `--split-files`.
+pub fn format_discussion_split(
+    discussion: &Discussion,
+    owner: &str,
+    repo: &str,
+    index_name: &str,
+    options: &OutputOptions,
+) -> Vec<SplitFile> {
+    let mut files = vec![SplitFile {
+        name: index_name.to_string(),
+        content: generate_split_index(discussion, owner, repo, options),
+    }];
+    let anchors = split_link_anchors(discussion, options);
+    for (i, comment) in discussion_comments(discussion).into_iter().enumerate() {
+        let comment_num = i + 1;
+        files.push(SplitFile {
+            name: comment_file_name(comment_num),
+            content: generate_comment_file(
+                discussion,
+                comment,
+                comment_num,
+                index_name,
+                &anchors,
+                options,
+            ),
+        });
+    }
+    files
+}
+
+/// Count the comments and replies of a discussion
+///
+/// Returns `(comments, replies)`. Deleted (`null`) entries are not counted.
+fn count_comments_and_replies(discussion: &Discussion) -> (usize, usize) {
+    discussion.comments.nodes.iter().flatten().flatten().fold(
+        (0, 0),
+        |(comments, replies), comment| {
+            let comment_replies = comment.replies.nodes.iter().flatten().flatten().count();
+            (comments + 1, replies + comment_replies)
+        },
+    )
+}
+
+/// Format a dry-run summary of what would be exported
+///
+/// Returns a plain-text block with the title, comment and reply counts, and
+/// the target the export would be written to. Deleted (`null`) comments and
+/// replies are not counted.
+pub fn format_dry_run_summary(discussion: &Discussion, target: &str) -> String {
+    let (comments, replies) = count_comments_and_replies(discussion);
+
+    format!(
+        "Dry run: nothing was written\n\nTitle: {}\nComments: {}\nReplies: {}\nOutput: {}\n",
+        discussion.title, comments, replies, target
+    )
+}
+
+/// Format the machine-readable `--porcelain` summary of a completed export
+///
+/// Returns a single line of space-separated `key=value` fields:
+/// `exported comments=<N> replies=<N> path=<PATH>`. Field names are stable.
+/// `path` is always last and runs to the end of the line, so paths
+/// containing spaces stay parseable. Deleted (`null`) comments and replies
+/// are not counted.
+pub fn format_porcelain_summary(discussion: &Discussion, path: &str) -> String {
+    let (comments, replies) = count_comments_and_replies(discussion);
+    format_porcelain_counts(comments, replies, path)
+}
+
+/// Format the `--porcelain` summary line for the given counts
+///
+/// Same format as `format_porcelain_summary`, e.g. for the comments added
+/// with `--append`.
+pub fn format_porcelain_counts(comments: usize, replies: usize, path: &str) -> String {
+    format!(
+        "exported comments={} replies={} path={}\n",
+        comments, replies, path
+    )
+}
+
+/// Format complete discussion as pretty-printed JSON
+///
+/// Serializes the fully-fetched Discussion (including comments and replies)
+/// using the same field names as the GitHub GraphQL API. The returned String
+/// ends with a trailing newline.
+pub fn format_discussion_json(discussion: &Discussion) -> Result<String> {
+    let mut json = serde_json::to_string_pretty(discussion)
+        .map_err(|e| Error::Serialize(format!("Failed to serialize Discussion: {}", e)))?;
+    json.push('\n');
+    Ok(json)
+}
 
-    format!("{}{}{}", header, original_post, comments)
+/// One line of NDJSON output: the original post, a comment, or a reply
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct NdjsonRecord<'a> {
+    #[serde(rename = "type")]
+    kind: &'static str,
+    id: &'a str,
+    database_id: Option<i64>,
+    author: &'a str,
+    created_at: &'a DateTime<Utc>,
+    body: &'a str,
+    #[serde(rename = "parent_id", skip_serializing_if = "Option::is_none")]
+    parent_id: Option<&'a str>,
+}
+
+/// Format a discussion as newline-delimited JSON (NDJSON)
+///
+/// Emits one object per line: the original post (`"type": "post"`), then
+/// each comment (`"comment"`) followed by its replies (`"reply"`, with the
+/// comment's ID as `parent_id`). Every object has `id`, `databaseId` (`null`
+/// for the post), `author`, `createdAt`, and the Markdown `body`. The
+/// returned String ends with a trailing newline.
+pub fn format_discussion_ndjson(discussion: &Discussion) -> Result<String> {
+    let mut records = vec![NdjsonRecord {
+        kind: "post",
+        id: &discussion.id,
+        database_id: None,
+        author: get_author_login(discussion.author.as_ref()),
+        created_at: &discussion.created_at,
+        body: &discussion.body,
+        parent_id: None,
+    }];
+    for comment in discussion_comments(discussion) {
+        records.push(NdjsonRecord {
+            kind: "comment",
+            id: &comment.id,
+            database_id: Some(comment.database_id),
+            author: get_author_login(comment.author.as_ref()),
+            created_at: &comment.created_at,
+            body: &comment.body,
+            parent_id: None,
+        });
+        for reply in comment.replies.nodes.iter().flatten().flatten() {
+            records.push(NdjsonRecord {
+                kind: "reply",
+                id: &reply.id,
+                database_id: Some(reply.database_id),
+                author: get_author_login(reply.author.as_ref()),
+                created_at: &reply.created_at,
+                body: &reply.body,
+                parent_id: Some(&comment.id),
+            });
+        }
+    }
+
+    let mut output = String::new();
+    for record in &records {
+        let line = serde_json::to_string(record)
+            .map_err(|e| Error::Serialize(format!("Failed to serialize record: {}", e)))?;
+        output.push_str(&line);
+        output.push('\n');
+    }
+    Ok(output)
 }
 
 /// Write Markdown content to file
@@ -186,6 +1399,74 @@ pub fn write_output(markdown: &str, path: &str) -> Result<()> {
     fs::write(path, markdown).map_err(Error::Io)
 }
 
+/// Write the files of a split export
+///
+/// The first file is written to `index_path`; the others are written next
+/// to it. Unless `force` is set, nothing is written if any of the files
+/// already exists (`Err(Error::OutputExists)`).
+pub fn write_split_output(files: &[SplitFile], index_path: &str, force: bool) -> Result<()> {
+    let dir = std::path::Path::new(index_path)
+        .parent()
+        .unwrap_or(std::path::Path::new(""));
+    let paths: Vec<String> = files
+        .iter()
+        .enumerate()
+        .map(|(i, file)| match i {
+            0 => index_path.to_string(),
+            _ => dir.join(&file.name).to_string_lossy().into_owned(),
+        })
+        .collect();
+    for path in &paths {
+        check_output_path(path, force)?;
+    }
+    for (file, path) in files.iter().zip(&paths) {
+        write_output(&file.content, path)?;
+    }
+    Ok(())
+}
+
+/// Append Markdown content to an existing file
+///
+/// Returns Error if the file cannot be opened or written.
+pub fn append_output(markdown: &str, path: &str) -> Result<()> {
+    let mut file = fs::OpenOptions::new().append(true).open(path)?;
+    write_to(&mut file, markdown)
+}
+
+/// Decide whether the output file may be written
+///
+/// An existing file is only overwritten when `force` is set.
+pub fn should_write(exists: bool, force: bool) -> bool {
+    !exists || force
+}
+
+/// Check that the output file can be written without clobbering an export
+///
+/// Returns `Err(Error::OutputExists)` if `path` exists and `force` is not set.
+/// Called before fetching so no API calls are wasted.
+pub fn check_output_path(path: &str, force: bool) -> Result<()> {
+    if should_write(std::path::Path::new(path).exists(), force) {
+        Ok(())
+    } else {
+        Err(Error::OutputExists(path.to_string()))
+    }
+}
+
+/// Write content to standard output
+///
+/// Used by `--stdout` to pipe the export into another program.
+/// Returns Error if I/O operation fails.
+pub fn write_stdout(content: &str) -> Result<()> {
+    write_to(&mut std::io::stdout().lock(), content)
+}
+
+/// Write content to the given writer and flush it
+fn write_to<W: Write>(writer: &mut W, content: &str) -> Result<()> {
+    writer.write_all(content.as_bytes())?;
+    writer.flush()?;
+    Ok(())
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -204,8 +1485,10 @@ mod tests {
             body: "This is the original post body.".to_string(),
             author: Some(Author {
                 login: Some("testuser".to_string()),
+                typename: None,
             }),
             comments: Default::default(),
+            ..Default::default()
         }
     }
 
@@ -215,6 +1498,7 @@ mod tests {
             database_id: 1,
             author: login.map(|l| Author {
                 login: Some(l.to_string()),
+                typename: None,
             }),
             created_at: DateTime::parse_from_rfc3339("2024-01-15T11:00:00Z")
                 .unwrap()
@@ -223,14 +1507,16 @@ mod tests {
             replies: crate::models::CommentReplies {
                 nodes: Some(vec![]),
                 page_info: Default::default(),
+                ..Default::default()
             },
+            ..Default::default()
         }
     }
 
     #[test]
     fn test_generate_header_with_all_fields() {
         let discussion = make_discussion();
-        let header = generate_header(&discussion, "owner", "repo");
+        let header = generate_header(&discussion, "owner", "repo", &OutputOptions::default());
 
         assert!(header.contains("# Test Discussion"));
         assert!(header.contains("Discussion: owner/repo#123"));
@@ -240,19 +1526,183 @@ mod tests {
         assert!(header.ends_with("\n\n---\n"));
     }
 
+    #[test]
+    fn test_escape_markdown_text() {
+        assert_eq!(escape_markdown_text("Plain title"), "Plain title");
+        assert_eq!(
+            escape_markdown_text("Use `snake_case` for *all* [names]"),
+            "Use \\`snake\\_case\\` for \\*all\\* \\[names\\]"
+        );
+        assert_eq!(
+            escape_markdown_text("<div> ~old~ C:\\path #"),
+            "\\<div\\> \\~old\\~ C:\\\\path \\#"
+        );
+        assert_eq!(escape_markdown_text("日本語のタイトル"), "日本語のタイトル");
+    }
+
+    #[test]
+    fn test_generate_header_escapes_title() {
+        let mut discussion = make_discussion();
+        discussion.title = "Why does `foo_bar` fail with __init__?".to_string();
+        let header = generate_header(&discussion, "owner", "repo", &OutputOptions::default());
+
+        assert!(header.starts_with("# Why does \\`foo\\_bar\\` fail with \\_\\_init\\_\\_?\n"));
+    }
+
+    #[test]
+    fn test_front_matter_title_not_markdown_escaped() {
+        let mut discussion = make_discussion();
+        discussion.title = "Why does `foo_bar` fail?".to_string();
+        let front_matter =
+            generate_front_matter(&discussion, "owner", "repo", &OutputOptions::default());
+
+        assert!(front_matter.contains("title: \"Why does `foo_bar` fail?\"\n"));
+    }
+
+    #[test]
+    fn test_author_line_emphasis_with_hyphenated_login() {
+        let mut discussion = make_discussion();
+        discussion.author = Some(crate::models::Author {
+            login: Some("octo-cat-42".to_string()),
+            typename: None,
+        });
+        let post = generate_original_post(&discussion, &OutputOptions::default());
+
+        assert!(post.contains("_author: octo-cat-42 (2024-01-15T10:30:00Z)_\n"));
+    }
+
     #[test]
     fn test_generate_header_with_deleted_author() {
         let mut discussion = make_discussion();
         discussion.author = None;
-        let header = generate_header(&discussion, "owner", "repo");
+        let header = generate_header(&discussion, "owner", "repo", &OutputOptions::default());
+
+        assert!(header.contains("Author: \\<deleted\\>\n"));
+    }
+
+    #[test]
+    fn test_generate_header_with_category() {
+        let mut discussion = make_discussion();
+        discussion.category = Some(DiscussionCategory {
+            name: "General".to_string(),
+            emoji: Some(":speech_balloon:".to_string()),
+        });
+        let header = generate_header(&discussion, "owner", "repo", &OutputOptions::default());
+
+        assert!(header.contains("Author: testuser\nCategory: 💬 General\n\n---\n"));
+    }
+
+    #[test]
+    fn test_generate_header_with_category_without_emoji() {
+        let mut discussion = make_discussion();
+        discussion.category = Some(DiscussionCategory {
+            name: "Ideas".to_string(),
+            emoji: None,
+        });
+        let header = generate_header(&discussion, "owner", "repo", &OutputOptions::default());
+
+        assert!(header.contains("Author: testuser\nCategory: Ideas\n\n---\n"));
+    }
+
+    #[test]
+    fn test_generate_header_with_unknown_category_emoji() {
+        let mut discussion = make_discussion();
+        discussion.category = Some(DiscussionCategory {
+            name: "Show and tell".to_string(),
+            emoji: Some(":tada:".to_string()),
+        });
+        let header = generate_header(&discussion, "owner", "repo", &OutputOptions::default());
+
+        assert!(header.contains("Category: :tada: Show and tell\n"));
+    }
 
-        assert!(header.contains("Author: <deleted>"));
+    #[test]
+    fn test_generate_header_closed_and_locked() {
+        let mut discussion = make_discussion();
+        discussion.closed = true;
+        discussion.locked = true;
+        let header = generate_header(&discussion, "owner", "repo", &OutputOptions::default());
+
+        assert!(header.contains("Author: testuser\nStatus: closed (locked)\n\n---\n"));
+    }
+
+    #[test]
+    fn test_format_status() {
+        let options = OutputOptions::default();
+        let mut discussion = make_discussion();
+        assert_eq!(format_status(&discussion, &options), "");
+        discussion.closed = true;
+        assert_eq!(format_status(&discussion, &options), "Status: closed\n");
+        discussion.closed_at = Some(
+            DateTime::parse_from_rfc3339("2024-02-01T00:00:00Z")
+                .unwrap()
+                .with_timezone(&Utc),
+        );
+        assert_eq!(
+            format_status(&discussion, &options),
+            "Status: closed at 2024-02-01T00:00:00Z\n"
+        );
+        assert_eq!(
+            format_status(&discussion, &options_with_date_format("%Y-%m-%d")),
+            "Status: closed at 2024-02-01\n"
+        );
+        discussion.closed = false;
+        discussion.locked = true;
+        assert_eq!(
+            format_status(&discussion, &options),
+            "Status: open (locked)\n"
+        );
+    }
+
+    #[test]
+    fn test_generate_front_matter() {
+        let discussion = make_discussion();
+        let front_matter =
+            generate_front_matter(&discussion, "owner", "repo", &OutputOptions::default());
+
+        assert_eq!(
+            front_matter,
+            "---\n\
+             title: \"Test Discussion\"\n\
+             discussion_number: 123\n\
+             url: \"https://github.com/owner/repo/discussions/123\"\n\
+             created_at: 2024-01-15T10:30:00Z\n\
+             author: \"testuser\"\n\
+             repository: \"owner/repo\"\n\
+             ---\n\n"
+        );
+    }
+
+    #[test]
+    fn test_generate_front_matter_quotes_title_with_colon() {
+        let mut discussion = make_discussion();
+        discussion.title = "RFC: \"quoted\" \\ title".to_string();
+        let front_matter =
+            generate_front_matter(&discussion, "owner", "repo", &OutputOptions::default());
+
+        assert!(front_matter.contains("title: \"RFC: \\\"quoted\\\" \\\\ title\"\n"));
+    }
+
+    #[test]
+    fn test_format_discussion_with_front_matter_skips_header() {
+        let discussion = make_discussion();
+        let options = OutputOptions {
+            front_matter: true,
+            ..Default::default()
+        };
+        let formatted = format_discussion_with_options(&discussion, "owner", "repo", &options);
+
+        assert!(formatted.starts_with("---\ntitle: \"Test Discussion\"\n"));
+        assert!(!formatted.contains("# Test Discussion\n"));
+        assert!(!formatted.contains("Discussion: owner/repo#123"));
+        assert!(formatted.contains("## Original Post"));
+        assert!(formatted.contains("## Comments"));
     }
 
     #[test]
     fn test_generate_original_post() {
         let discussion = make_discussion();
-        let post = generate_original_post(&discussion);
+        let post = generate_original_post(&discussion, &OutputOptions::default());
 
         assert!(post.contains("## Original Post"));
         assert!(post.contains("_author: testuser (2024-01-15T10:30:00Z)_"));
@@ -264,9 +1714,9 @@ mod tests {
     fn test_generate_original_post_with_deleted_author() {
         let mut discussion = make_discussion();
         discussion.author = None;
-        let post = generate_original_post(&discussion);
+        let post = generate_original_post(&discussion, &OutputOptions::default());
 
-        assert!(post.contains("_author: <deleted>"));
+        assert!(post.contains("_author: \\<deleted\\> ("));
         assert!(post.contains("This is the original post body."));
     }
 
@@ -277,7 +1727,7 @@ mod tests {
         let comment2 = make_comment(Some("user2"), "Second comment");
 
         discussion.comments.nodes = Some(vec![Some(comment1), Some(comment2)]);
-        let comments = generate_comments(&discussion);
+        let comments = generate_comments(&discussion, &OutputOptions::default());
 
         assert!(comments.contains("## Comments"));
         assert!(comments.contains("### Comment 1"));
@@ -290,7 +1740,7 @@ mod tests {
     fn test_generate_comments_with_no_comments() {
         let mut discussion = make_discussion();
         discussion.comments.nodes = Some(vec![]);
-        let comments = generate_comments(&discussion);
+        let comments = generate_comments(&discussion, &OutputOptions::default());
 
         assert!(comments.contains("## Comments"));
         // Should not contain any comment or reply headings
@@ -298,10 +1748,159 @@ mod tests {
         assert!(!comments.contains("#### Reply"));
     }
 
+    #[test]
+    fn test_generate_comments_marks_accepted_answer() {
+        let mut discussion = make_discussion();
+        let comment1 = make_comment(Some("user1"), "Not the answer");
+        let mut comment2 = make_comment(Some("user2"), "The answer");
+        comment2.is_answer = true;
+
+        discussion.comments.nodes = Some(vec![Some(comment1), Some(comment2)]);
+        let comments = generate_comments(&discussion, &OutputOptions::default());
+
+        assert!(comments.contains("### Comment 1\n"));
+        assert!(comments.contains("### Comment 2 (✓ Accepted Answer)\n"));
+        assert_eq!(comments.matches("Accepted Answer").count(), 1);
+    }
+
+    #[test]
+    fn test_generate_comments_marks_answer_by_discussion_reference() {
+        let mut discussion = make_discussion();
+        let mut comment = make_comment(Some("user1"), "The answer");
+        comment.id = "answer_comment".to_string();
+        discussion.answer = Some(crate::models::DiscussionAnswer {
+            id: "answer_comment".to_string(),
+        });
+
+        discussion.comments.nodes = Some(vec![Some(comment)]);
+        let comments = generate_comments(&discussion, &OutputOptions::default());
+
+        assert!(comments.contains("### Comment 1 (✓ Accepted Answer)"));
+    }
+
+    #[test]
+    fn test_generate_comments_without_answer() {
+        let mut discussion = make_discussion();
+        discussion.comments.nodes = Some(vec![Some(make_comment(Some("user1"), "Comment"))]);
+        let comments = generate_comments(&discussion, &OutputOptions::default());
+
+        assert!(!comments.contains("Accepted Answer"));
+    }
+
+    fn make_reaction_group(content: &str, count: u64) -> ReactionGroup {
+        ReactionGroup {
+            content: content.to_string(),
+            reactors: crate::models::TotalCount { total_count: count },
+        }
+    }
+
+    #[test]
+    fn test_generate_comments_with_reactions() {
+        let mut discussion = make_discussion();
+        let mut comment = make_comment(Some("user1"), "Comment body");
+        comment.reaction_groups = Some(vec![
+            make_reaction_group("THUMBS_UP", 5),
+            make_reaction_group("LAUGH", 0),
+            make_reaction_group("HEART", 2),
+        ]);
+
+        discussion.comments.nodes = Some(vec![Some(comment)]);
+        let comments = generate_comments(&discussion, &OutputOptions::default());
+
+        assert!(comments.contains("Comment body\n\nReactions: 👍 5  ❤️ 2\n\n"));
+        assert!(!comments.contains("😄"));
+    }
+
+    #[test]
+    fn test_generate_comments_with_upvotes() {
+        let mut discussion = make_discussion();
+        let mut comment = make_comment(Some("user1"), "Great idea");
+        comment.upvote_count = 5;
+        comment.reaction_groups = Some(vec![make_reaction_group("HEART", 1)]);
+        comment.replies.nodes = Some(vec![Some(make_reply("reply_1", "Reply 1"))]);
+        discussion.comments.nodes = Some(vec![Some(comment)]);
+
+        let comments = generate_comments(&discussion, &OutputOptions::default());
+
+        assert!(comments.contains("Great idea\n\nUpvotes: 5\n\nReactions: ❤️ 1\n\n"));
+        assert_eq!(comments.matches("Upvotes:").count(), 1);
+    }
+
+    #[test]
+    fn test_generate_original_post_with_upvotes() {
+        let mut discussion = make_discussion();
+        discussion.upvote_count = 12;
+        let post = generate_original_post(&discussion, &OutputOptions::default());
+
+        assert!(post.ends_with("\n\nUpvotes: 12\n\n---\n"));
+    }
+
+    #[test]
+    fn test_edited_suffix() {
+        let mut discussion = make_discussion();
+        discussion.last_edited_at = Some(
+            DateTime::parse_from_rfc3339("2024-01-15T10:45:00Z")
+                .unwrap()
+                .with_timezone(&Utc),
+        );
+        let mut comment = make_comment(Some("user1"), "Edited comment");
+        comment.last_edited_at = Some(
+            DateTime::parse_from_rfc3339("2024-01-16T08:00:00Z")
+                .unwrap()
+                .with_timezone(&Utc),
+        );
+        discussion.comments.nodes = Some(vec![Some(comment)]);
+
+        let formatted = format_discussion(&discussion, "owner", "repo");
+
+        assert!(
+            formatted.contains(
+                "_author: testuser (2024-01-15T10:30:00Z) (edited 2024-01-15T10:45:00Z)_"
+            )
+        );
+        assert!(
+            formatted
+                .contains("_author: user1 (2024-01-15T11:00:00Z) (edited 2024-01-16T08:00:00Z)_")
+        );
+    }
+
+    #[test]
+    fn test_no_edited_suffix_when_unedited_or_same_time() {
+        let mut discussion = make_discussion();
+        let mut comment = make_comment(Some("user1"), "Comment");
+        comment.last_edited_at = Some(comment.created_at);
+        discussion.comments.nodes = Some(vec![Some(comment)]);
+
+        let formatted = format_discussion(&discussion, "owner", "repo");
+
+        assert!(!formatted.contains("(edited"));
+        assert!(formatted.contains("_author: user1 (2024-01-15T11:00:00Z)_"));
+    }
+
+    #[test]
+    fn test_no_reactions_line_when_empty() {
+        let mut discussion = make_discussion();
+        let mut comment = make_comment(Some("user1"), "Comment body");
+        comment.reaction_groups = Some(vec![make_reaction_group("THUMBS_UP", 0)]);
+        discussion.comments.nodes = Some(vec![Some(comment)]);
+
+        let formatted = format_discussion(&discussion, "owner", "repo");
+        assert!(!formatted.contains("Reactions:"));
+    }
+
+    #[test]
+    fn test_generate_original_post_with_reactions() {
+        let mut discussion = make_discussion();
+        discussion.reaction_groups = Some(vec![make_reaction_group("ROCKET", 3)]);
+        let post = generate_original_post(&discussion, &OutputOptions::default());
+
+        assert!(post.ends_with("This is the original post body.\n\nReactions: 🚀 3\n\n---\n"));
+    }
+
     #[test]
     fn test_heading_escape() {
         let input = "## This is a heading\nRegular text\n### Another heading";
-        let escaped = escape_headings(input);
+        let escaped = escape_headings(input, HeadingEscape::All);
 
         assert_eq!(
             escaped,
@@ -312,7 +1911,7 @@ mod tests {
     #[test]
     fn test_escape_headings_preserves_trailing_newline() {
         let input = "# Heading\nContent\n";
-        let escaped = escape_headings(input);
+        let escaped = escape_headings(input, HeadingEscape::All);
 
         assert!(
             escaped.ends_with('\n'),
@@ -321,6 +1920,34 @@ mod tests {
         assert_eq!(escaped, "\\# Heading\nContent\n");
     }
 
+    #[test]
+    fn test_process_body_preserves_blank_lines_and_whitespace() {
+        for input in [
+            "",
+            "\n",
+            "Text\n\n\n",
+            "\n\nLeading blank lines",
+            "Para 1\n\n\n\nPara 2",
+            "Hard break  \nnext line\t\n",
+            "Whitespace-only lines\n   \n\t\n",
+        ] {
+            assert_eq!(
+                process_body(input, HeadingEscape::All),
+                input,
+                "{:?}",
+                input
+            );
+        }
+        assert_eq!(
+            process_body("# Title  \n\n\n", HeadingEscape::All),
+            "\\# Title  \n\n\n"
+        );
+        assert_eq!(
+            process_body("Text\r\n\r\n\r\n", HeadingEscape::All),
+            "Text\n\n\n"
+        );
+    }
+
     #[test]
     fn test_crlf_normalization() {
         let input = "Line 1\r\nLine 2\r\nLine 3";
@@ -342,7 +1969,7 @@ mod tests {
     #[test]
     fn test_process_body_verbatim_with_heading_escape() {
         let input = "# Heading in body\nRegular text\n## Another heading";
-        let processed = process_body(input);
+        let processed = process_body(input, HeadingEscape::All);
 
         // Should escape headings but preserve everything else verbatim
         assert!(processed.contains("\\# Heading in body"));
@@ -353,10 +1980,63 @@ mod tests {
     #[test]
     fn test_process_body_crlf_normalization() {
         let input = "Line 1\r\nLine 2\r\nLine 3";
-        let processed = process_body(input);
+        let processed = process_body(input, HeadingEscape::All);
+
+        assert!(!processed.contains("\r\n"));
+        assert!(processed.contains("Line 1\nLine 2\nLine 3"));
+    }
+
+    #[test]
+    fn test_blockquote_multi_line_body() {
+        assert_eq!(
+            blockquote("First line\n\nSecond paragraph\n"),
+            "> First line\n>\n> Second paragraph\n"
+        );
+        assert_eq!(blockquote("No trailing newline"), "> No trailing newline");
+        assert_eq!(blockquote(""), "");
+    }
+
+    #[test]
+    fn test_blockquote_nests_existing_quotes() {
+        assert_eq!(
+            blockquote("> Someone said\n\nI agree"),
+            "> > Someone said\n>\n> I agree"
+        );
+    }
+
+    #[test]
+    fn test_render_body_with_blockquote_option() {
+        let options = OutputOptions {
+            blockquote_bodies: true,
+            ..Default::default()
+        };
+        let rendered = render_body("# Heading\r\nText\r\n", &HashMap::new(), &options);
+        assert_eq!(rendered, "> \\# Heading\n> Text\n");
+
+        let rendered = render_body(
+            "# Heading\r\nText\r\n",
+            &HashMap::new(),
+            &OutputOptions::default(),
+        );
+        assert_eq!(rendered, "\\# Heading\nText\n");
+    }
+
+    #[test]
+    fn test_format_discussion_with_blockquote_bodies() {
+        let mut discussion = make_discussion();
+        let mut comment = make_comment(Some("commenter"), "Line 1\nLine 2");
+        comment.replies.nodes = Some(vec![Some(make_reply("R_1", "> quoted\nreply"))]);
+        discussion.comments.nodes = Some(vec![Some(comment)]);
 
-        assert!(!processed.contains("\r\n"));
-        assert!(processed.contains("Line 1\nLine 2\nLine 3"));
+        let options = OutputOptions {
+            blockquote_bodies: true,
+            ..Default::default()
+        };
+        let formatted = format_discussion_with_options(&discussion, "owner", "repo", &options);
+
+        assert!(formatted.contains("_\n\n> This is the original post body.\n"));
+        assert!(formatted.contains("_\n\n> Line 1\n> Line 2\n"));
+        assert!(formatted.contains("_\n\n> > quoted\n> reply\n"));
     }
 
     #[test]
@@ -380,11 +2060,13 @@ mod tests {
             database_id: 2,
             author: Some(Author {
                 login: Some("replier".to_string()),
+                typename: None,
             }),
             created_at: DateTime::parse_from_rfc3339("2024-01-15T12:00:00Z")
                 .unwrap()
                 .with_timezone(&Utc),
             body: "Reply body".to_string(),
+            ..Default::default()
         };
 
         comment.replies.nodes = Some(vec![Some(reply)]);
@@ -400,6 +2082,30 @@ mod tests {
         assert!(formatted.contains("#### Reply 1.1")); // Level 4
     }
 
+    #[test]
+    fn test_format_discussion_json_round_trip() {
+        let mut discussion = make_discussion();
+        let mut comment = make_comment(Some("user1"), "Comment body");
+        comment.replies.nodes = Some(vec![Some(Reply {
+            id: "reply_id".to_string(),
+            database_id: 2,
+            author: None,
+            created_at: DateTime::parse_from_rfc3339("2024-01-15T12:00:00Z")
+                .unwrap()
+                .with_timezone(&Utc),
+            body: "Reply body".to_string(),
+            ..Default::default()
+        })]);
+        discussion.comments.nodes = Some(vec![Some(comment)]);
+
+        let json = format_discussion_json(&discussion).unwrap();
+        assert!(json.ends_with('\n'));
+        assert!(json.contains("\"createdAt\": \"2024-01-15T10:30:00Z\""));
+
+        let parsed: Discussion = serde_json::from_str(&json).unwrap();
+        assert_eq!(parsed, discussion);
+    }
+
     #[test]
     fn test_write_output_creates_file() {
         let temp_dir = std::env::temp_dir();
@@ -416,6 +2122,45 @@ mod tests {
         fs::remove_file(&file_path).unwrap();
     }
 
+    #[test]
+    fn test_should_write() {
+        assert!(should_write(false, false));
+        assert!(should_write(false, true));
+        assert!(!should_write(true, false));
+        assert!(should_write(true, true));
+    }
+
+    #[test]
+    fn test_check_output_path_existing_file() {
+        let temp_dir = std::env::temp_dir();
+        let test_file = temp_dir.join("test_check_output_path_existing.md");
+        let path = test_file.to_str().unwrap();
+        fs::write(&test_file, "previous export").unwrap();
+
+        assert!(matches!(
+            check_output_path(path, false),
+            Err(Error::OutputExists(p)) if p == path
+        ));
+        assert!(check_output_path(path, true).is_ok());
+
+        fs::remove_file(&test_file).ok();
+    }
+
+    #[test]
+    fn test_check_output_path_missing_file() {
+        let test_file = std::env::temp_dir().join("test_check_output_path_missing.md");
+        fs::remove_file(&test_file).ok();
+
+        assert!(check_output_path(test_file.to_str().unwrap(), false).is_ok());
+    }
+
+    #[test]
+    fn test_write_to_writes_content_verbatim() {
+        let mut buffer = Vec::new();
+        write_to(&mut buffer, "# Test\n\nContent here\n").unwrap();
+        assert_eq!(buffer, b"# Test\n\nContent here\n");
+    }
+
     #[test]
     fn test_write_output_handles_io_error() {
         // Use an invalid path (directory that doesn't exist)
@@ -436,11 +2181,13 @@ mod tests {
             database_id: 11,
             author: Some(Author {
                 login: Some("replier1".to_string()),
+                typename: None,
             }),
             created_at: DateTime::parse_from_rfc3339("2024-01-15T12:00:00Z")
                 .unwrap()
                 .with_timezone(&Utc),
             body: "Reply 1.1".to_string(),
+            ..Default::default()
         };
 
         let reply1_2 = Reply {
@@ -448,11 +2195,13 @@ mod tests {
             database_id: 12,
             author: Some(Author {
                 login: Some("replier2".to_string()),
+                typename: None,
             }),
             created_at: DateTime::parse_from_rfc3339("2024-01-15T12:30:00Z")
                 .unwrap()
                 .with_timezone(&Utc),
             body: "Reply 1.2".to_string(),
+            ..Default::default()
         };
 
         let reply2_1 = Reply {
@@ -460,42 +2209,585 @@ mod tests {
             database_id: 21,
             author: Some(Author {
                 login: Some("replier3".to_string()),
+                typename: None,
             }),
             created_at: DateTime::parse_from_rfc3339("2024-01-15T13:00:00Z")
                 .unwrap()
                 .with_timezone(&Utc),
             body: "Reply 2.1".to_string(),
+            ..Default::default()
+        };
+
+        comment1.replies.nodes = Some(vec![Some(reply1_1), Some(reply1_2)]);
+        comment2.replies.nodes = Some(vec![Some(reply2_1)]);
+        discussion.comments.nodes = Some(vec![Some(comment1), Some(comment2)]);
+
+        let formatted = format_discussion(&discussion, "owner", "repo");
+
+        // Check proper reply numbering
+        assert!(formatted.contains("### Comment 1"));
+        assert!(formatted.contains("#### Reply 1.1"));
+        assert!(formatted.contains("Reply 1.1"));
+        assert!(formatted.contains("#### Reply 1.2"));
+        assert!(formatted.contains("Reply 1.2"));
+        assert!(formatted.contains("### Comment 2"));
+        assert!(formatted.contains("#### Reply 2.1"));
+        assert!(formatted.contains("Reply 2.1"));
+    }
+
+    #[test]
+    fn test_comment_with_no_replies() {
+        let mut discussion = make_discussion();
+        let comment = make_comment(Some("user1"), "Comment without replies");
+
+        discussion.comments.nodes = Some(vec![Some(comment)]);
+        let formatted = format_discussion(&discussion, "owner", "repo");
+
+        assert!(formatted.contains("### Comment 1"));
+        assert!(formatted.contains("Comment without replies"));
+        // Should not contain reply headings
+        assert!(!formatted.contains("#### Reply"));
+    }
+
+    fn make_reply(id: &str, body: &str) -> Reply {
+        Reply {
+            id: id.to_string(),
+            body: body.to_string(),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_comment_permalink() {
+        assert_eq!(
+            comment_permalink("https://github.com/owner/repo/discussions/123", 456),
+            "https://github.com/owner/repo/discussions/123#discussioncomment-456"
+        );
+    }
+
+    #[test]
+    fn test_generate_comments_with_permalinks() {
+        let mut discussion = make_discussion();
+        let mut comment = make_comment(Some("commenter"), "Comment body");
+        comment.database_id = 456;
+        let mut reply = make_reply("R_1", "Reply body");
+        reply.database_id = 789;
+        comment.replies.nodes = Some(vec![Some(reply)]);
+        discussion.comments.nodes = Some(vec![Some(comment)]);
+
+        let options = OutputOptions {
+            permalinks: true,
+            ..Default::default()
+        };
+        let comments = generate_comments(&discussion, &options);
+
+        assert!(comments.contains(
+            "Comment body\n\n[permalink](https://github.com/owner/repo/discussions/123#discussioncomment-456)\n\n"
+        ));
+        assert!(comments.contains(
+            "Reply body\n\n[permalink](https://github.com/owner/repo/discussions/123#discussioncomment-789)\n\n"
+        ));
+
+        let comments = generate_comments(&discussion, &OutputOptions::default());
+        assert!(!comments.contains("[permalink]"));
+    }
+
+    #[test]
+    fn test_generate_comments_with_anchor_ids() {
+        let mut discussion = make_discussion();
+        let mut first = make_comment(Some("user1"), "# Heading in body");
+        first.replies.nodes = Some(vec![
+            Some(make_reply("R_1", "First reply")),
+            None,
+            Some(make_reply("R_2", "Second reply")),
+        ]);
+        discussion.comments.nodes = Some(vec![
+            Some(first),
+            None,
+            Some(make_comment(Some("user2"), "Second comment")),
+        ]);
+
+        let options = OutputOptions {
+            anchor_ids: true,
+            ..Default::default()
+        };
+        let comments = generate_comments(&discussion, &options);
+
+        assert!(comments.contains(
+            "\n<a id=\"comment-1\"></a>\n\n### Comment 1\n\n_author: user1 (2024-01-15T11:00:00Z)_\n\n\\# Heading in body\n"
+        ));
+        assert!(comments.contains("\n<a id=\"reply-1-1\"></a>\n\n#### Reply 1.1\n"));
+        assert!(comments.contains("\n<a id=\"reply-1-2\"></a>\n\n#### Reply 1.2\n"));
+        assert!(comments.contains("\n<a id=\"comment-2\"></a>\n\n### Comment 2\n"));
+        assert!(!comments.contains("comment-3"));
+        assert!(!comments.contains("## Table of Contents"));
+    }
+
+    #[test]
+    fn test_anchor_ids_match_toc_links() {
+        let mut discussion = make_discussion();
+        discussion.comments.nodes = Some(vec![
+            Some(make_comment(Some("user1"), "First")),
+            Some(make_comment(Some("user2"), "Second")),
+        ]);
+
+        let options = OutputOptions {
+            anchor_ids: true,
+            ..Default::default()
+        };
+        let comments = generate_comments(&discussion, &options);
+        let toc = generate_toc(&discussion);
+
+        for n in 1..=2 {
+            assert!(toc.contains(&format!("](#comment-{})", n)));
+            assert!(comments.contains(&format!("<a id=\"comment-{}\"></a>", n)));
+        }
+    }
+
+    #[test]
+    fn test_format_discussion_comments_only() {
+        let mut discussion = make_discussion();
+        discussion.comments.nodes = Some(vec![Some(make_comment(Some("user1"), "A comment"))]);
+
+        let options = OutputOptions {
+            comments_only: true,
+            ..Default::default()
+        };
+        let formatted = format_discussion_with_options(&discussion, "owner", "repo", &options);
+
+        assert!(formatted.starts_with("# Test Discussion\n"));
+        assert!(!formatted.contains("## Original Post"));
+        assert!(!formatted.contains("This is the original post body."));
+        assert!(formatted.contains("---\n## Comments\n\n\n### Comment 1\n"));
+        assert!(formatted.contains("A comment"));
+    }
+
+    #[test]
+    fn test_format_discussion_post_only() {
+        let mut discussion = make_discussion();
+        discussion.comments.nodes = Some(vec![Some(make_comment(Some("user1"), "A comment"))]);
+
+        let options = OutputOptions {
+            post_only: true,
+            toc: true,
+            ..Default::default()
+        };
+        let formatted = format_discussion_with_options(&discussion, "owner", "repo", &options);
+
+        assert!(formatted.starts_with("# Test Discussion\n"));
+        assert!(formatted.contains("## Original Post"));
+        assert!(formatted.contains("This is the original post body."));
+        assert!(!formatted.contains("## Table of Contents"));
+        assert!(!formatted.contains("## Comments"));
+        assert!(!formatted.contains("A comment"));
+    }
+
+    #[test]
+    fn test_generate_comments_with_reply_counts() {
+        let mut discussion = make_discussion();
+        let mut comment1 = make_comment(Some("user1"), "Comment 1");
+        comment1.replies.nodes = Some(vec![
+            Some(make_reply("reply_1", "Reply 1")),
+            Some(make_reply("reply_2", "Reply 2")),
+        ]);
+        let comment2 = make_comment(Some("user2"), "Comment 2");
+        discussion.comments.nodes = Some(vec![Some(comment1), Some(comment2)]);
+
+        let options = OutputOptions {
+            reply_counts: true,
+            ..Default::default()
         };
+        let comments = generate_comments(&discussion, &options);
+
+        assert!(
+            comments.contains("_author: user1 (2024-01-15T11:00:00Z)_\n\nReplies: 2\n\nComment 1")
+        );
+        assert!(comments.contains("_author: user2 (2024-01-15T11:00:00Z)_\n\nComment 2"));
+        assert_eq!(comments.matches("Replies:").count(), 1);
+    }
+
+    #[test]
+    fn test_reply_count_falls_back_to_total_count() {
+        let replies = crate::models::CommentReplies {
+            nodes: None,
+            total_count: Some(7),
+            ..Default::default()
+        };
+        assert_eq!(reply_count(&replies), 7);
+    }
+
+    #[test]
+    fn test_generate_comments_without_reply_counts() {
+        let mut discussion = make_discussion();
+        let mut comment = make_comment(Some("user1"), "Comment 1");
+        comment.replies.nodes = Some(vec![Some(make_reply("reply_1", "Reply 1"))]);
+        discussion.comments.nodes = Some(vec![Some(comment)]);
+
+        let comments = generate_comments(&discussion, &OutputOptions::default());
+
+        assert!(!comments.contains("Replies:"));
+    }
+
+    #[test]
+    fn test_author_links() {
+        let mut discussion = make_discussion();
+        let mut comment = make_comment(Some("user1"), "Comment 1");
+        comment.replies.nodes = Some(vec![Some(Reply {
+            author: Some(Author {
+                login: Some("replier1".to_string()),
+                typename: None,
+            }),
+            ..make_reply("reply_1", "Reply 1")
+        })]);
+        discussion.comments.nodes = Some(vec![Some(comment)]);
+
+        let options = OutputOptions {
+            author_links: true,
+            ..Default::default()
+        };
+        let formatted = format_discussion_with_options(&discussion, "owner", "repo", &options);
+
+        assert!(
+            formatted.contains(
+                "_author: [testuser](https://github.com/testuser) (2024-01-15T10:30:00Z)_"
+            )
+        );
+        assert!(
+            formatted
+                .contains("_author: [user1](https://github.com/user1) (2024-01-15T11:00:00Z)_")
+        );
+        assert!(formatted.contains("_author: [replier1](https://github.com/replier1) ("));
+        // The plain-text header is unaffected
+        assert!(formatted.contains("Author: testuser\n"));
+    }
+
+    #[test]
+    fn test_author_links_with_deleted_author() {
+        let mut discussion = make_discussion();
+        discussion.author = None;
+        discussion.comments.nodes = Some(vec![Some(make_comment(None, "Orphan comment"))]);
+
+        let options = OutputOptions {
+            author_links: true,
+            ..Default::default()
+        };
+        let formatted = format_discussion_with_options(&discussion, "owner", "repo", &options);
+
+        assert!(formatted.contains("_author: \\<deleted\\> (2024-01-15T10:30:00Z)_"));
+        assert!(formatted.contains("_author: \\<deleted\\> (2024-01-15T11:00:00Z)_"));
+        assert!(!formatted.contains("](https://github.com/"));
+    }
+
+    #[test]
+    fn test_deleted_author_sentinel_from_fetch() {
+        // fetch replaces null authors with the `<deleted>` login
+        let mut discussion = make_discussion();
+        discussion.author = Some(Author {
+            login: Some(DELETED_AUTHOR.to_string()),
+            typename: None,
+        });
+        let mut comment = make_comment(None, "Orphan comment");
+        comment.author = Some(Author {
+            login: Some(DELETED_AUTHOR.to_string()),
+            typename: None,
+        });
+        discussion.comments.nodes = Some(vec![Some(comment)]);
+
+        let options = OutputOptions {
+            author_links: true,
+            toc: true,
+            ..Default::default()
+        };
+        let formatted = format_discussion_with_options(&discussion, "owner", "repo", &options);
+
+        assert!(formatted.contains("Author: \\<deleted\\>\n"));
+        assert!(formatted.contains("- [Comment 1 by \\<deleted\\>](#comment-1)\n"));
+        assert!(formatted.contains("_author: \\<deleted\\> (2024-01-15T10:30:00Z)_"));
+        assert!(formatted.contains("_author: \\<deleted\\> (2024-01-15T11:00:00Z)_"));
+        assert!(!formatted.contains(" <deleted>"));
+        assert!(!formatted.contains("](https://github.com/"));
+    }
+
+    #[test]
+    fn test_front_matter_keeps_deleted_sentinel() {
+        let mut discussion = make_discussion();
+        discussion.author = None;
+        let front_matter =
+            generate_front_matter(&discussion, "owner", "repo", &OutputOptions::default());
+
+        assert!(front_matter.contains("author: \"<deleted>\"\n"));
+    }
+
+    #[test]
+    fn test_format_discussion_with_toc() {
+        let mut discussion = make_discussion();
+        discussion.comments.nodes = Some(vec![
+            Some(make_comment(Some("user1"), "Comment 1")),
+            None,
+            Some(make_comment(None, "Comment from deleted user")),
+            Some(make_comment(Some("user3"), "")),
+        ]);
+
+        let options = OutputOptions {
+            toc: true,
+            ..Default::default()
+        };
+        let formatted = format_discussion_with_options(&discussion, "owner", "repo", &options);
+
+        assert!(formatted.contains(
+            "---\n## Table of Contents\n\n\
+             - [Comment 1 by user1](#comment-1)\n\
+             - [Comment 2 by \\<deleted\\>](#comment-2)\n\
+             - [Comment 3 by user3](#comment-3)\n\n\
+             ## Original Post"
+        ));
+        assert_eq!(formatted.matches("](#comment-").count(), 3);
+        for n in 1..=3 {
+            assert!(formatted.contains(&format!(
+                "<a id=\"comment-{}\"></a>\n\n### Comment {}\n",
+                n, n
+            )));
+        }
+    }
+
+    #[test]
+    fn test_generate_toc_with_no_comments() {
+        let discussion = make_discussion();
+        assert_eq!(generate_toc(&discussion), "");
+    }
+
+    #[test]
+    fn test_no_anchors_without_toc() {
+        let mut discussion = make_discussion();
+        discussion.comments.nodes = Some(vec![Some(make_comment(Some("user1"), "Comment 1"))]);
+        let formatted = format_discussion(&discussion, "owner", "repo");
+
+        assert!(!formatted.contains("## Table of Contents"));
+        assert!(!formatted.contains("<a id="));
+    }
+
+    fn options_with_timezone(name: &str) -> OutputOptions {
+        OutputOptions {
+            timezone: Some(name.parse().unwrap()),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_format_timestamp_defaults_to_utc() {
+        let discussion = make_discussion();
+        assert_eq!(
+            format_timestamp(&discussion.created_at, &OutputOptions::default()),
+            "2024-01-15T10:30:00Z"
+        );
+    }
+
+    #[test]
+    fn test_timestamps_in_asia_tokyo() {
+        let mut discussion = make_discussion();
+        discussion.comments.nodes = Some(vec![Some(make_comment(Some("user1"), "Comment 1"))]);
+        let options = options_with_timezone("Asia/Tokyo");
+        let formatted = format_discussion_with_options(&discussion, "owner", "repo", &options);
+
+        assert!(formatted.contains("Created at: 2024-01-15T19:30:00+09:00\n"));
+        assert!(formatted.contains("_author: testuser (2024-01-15T19:30:00+09:00)_"));
+        assert!(formatted.contains("_author: user1 (2024-01-15T20:00:00+09:00)_"));
+    }
+
+    #[test]
+    fn test_timestamps_in_america_new_york() {
+        let discussion = make_discussion();
+        let options = options_with_timezone("America/New_York");
+
+        // January is outside daylight saving time (UTC-5)
+        let header = generate_header(&discussion, "owner", "repo", &options);
+        assert!(header.contains("Created at: 2024-01-15T05:30:00-05:00\n"));
+
+        let front_matter = generate_front_matter(&discussion, "owner", "repo", &options);
+        assert!(front_matter.contains("created_at: 2024-01-15T05:30:00-05:00\n"));
+    }
+
+    fn options_with_date_format(fmt: &str) -> OutputOptions {
+        OutputOptions {
+            date_format: Some(fmt.to_string()),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_date_format_date_only() {
+        let mut discussion = make_discussion();
+        discussion.comments.nodes = Some(vec![Some(make_comment(Some("user1"), "Comment 1"))]);
+        let options = options_with_date_format("%Y-%m-%d");
+        let formatted = format_discussion_with_options(&discussion, "owner", "repo", &options);
+
+        assert!(formatted.contains("Created at: 2024-01-15\n"));
+        assert!(formatted.contains("_author: testuser (2024-01-15)_"));
+        assert!(formatted.contains("_author: user1 (2024-01-15)_"));
+    }
+
+    #[test]
+    fn test_date_format_rfc3339() {
+        let discussion = make_discussion();
+        let options = options_with_date_format("%+");
+        let header = generate_header(&discussion, "owner", "repo", &options);
+
+        assert!(header.contains("Created at: 2024-01-15T10:30:00+00:00\n"));
+    }
+
+    #[test]
+    fn test_date_format_with_timezone() {
+        let discussion = make_discussion();
+        let options = OutputOptions {
+            timezone: Some(chrono_tz::Asia::Tokyo),
+            date_format: Some("%Y-%m-%d %H:%M %Z".to_string()),
+            ..Default::default()
+        };
+        let post = generate_original_post(&discussion, &options);
+
+        assert!(post.contains("_author: testuser (2024-01-15 19:30 JST)_"));
+    }
+
+    #[test]
+    fn test_date_format_does_not_affect_front_matter() {
+        let discussion = make_discussion();
+        let options = options_with_date_format("%Y-%m-%d");
+        let front_matter = generate_front_matter(&discussion, "owner", "repo", &options);
+
+        assert!(front_matter.contains("created_at: 2024-01-15T10:30:00Z\n"));
+    }
+
+    #[test]
+    fn test_local_links() {
+        let mut discussion = make_discussion();
+        discussion.body =
+            "See https://github.com/owner/repo/discussions/123#discussioncomment-11".to_string();
+        let mut comment = make_comment(
+            Some("user1"),
+            "[Reply](https://github.com/owner/repo/discussions/123#discussioncomment-11) and \
+             [elsewhere](https://github.com/owner/repo/discussions/9#discussioncomment-99)",
+        );
+        comment.replies.nodes = Some(vec![Some(Reply {
+            database_id: 11,
+            ..make_reply("reply_1", "Reply 1")
+        })]);
+        discussion.comments.nodes = Some(vec![Some(comment)]);
+
+        let options = OutputOptions {
+            local_links: true,
+            ..Default::default()
+        };
+        let formatted = format_discussion_with_options(&discussion, "owner", "repo", &options);
+
+        assert!(formatted.contains(
+            "See [https://github.com/owner/repo/discussions/123#discussioncomment-11](#reply-1-1)"
+        ));
+        assert!(formatted.contains("[Reply](#reply-1-1) and [elsewhere](https://github.com/owner/repo/discussions/9#discussioncomment-99)"));
+        assert!(formatted.contains("<a id=\"comment-1\"></a>\n\n### Comment 1\n"));
+        assert!(formatted.contains("<a id=\"reply-1-1\"></a>\n\n#### Reply 1.1\n"));
+    }
+
+    #[test]
+    fn test_local_links_with_blockquote_bodies() {
+        let mut discussion = make_discussion();
+        let mut comment = make_comment(
+            Some("user1"),
+            "See https://github.com/owner/repo/discussions/123#discussioncomment-11\n\n\
+             ```\n\
+             https://github.com/owner/repo/discussions/123#discussioncomment-11\n\
+             ```",
+        );
+        comment.replies.nodes = Some(vec![Some(Reply {
+            database_id: 11,
+            ..make_reply("reply_1", "Reply 1")
+        })]);
+        discussion.comments.nodes = Some(vec![Some(comment)]);
 
-        comment1.replies.nodes = Some(vec![Some(reply1_1), Some(reply1_2)]);
-        comment2.replies.nodes = Some(vec![Some(reply2_1)]);
-        discussion.comments.nodes = Some(vec![Some(comment1), Some(comment2)]);
+        let options = OutputOptions {
+            local_links: true,
+            blockquote_bodies: true,
+            ..Default::default()
+        };
+        let formatted = format_discussion_with_options(&discussion, "owner", "repo", &options);
+
+        // Links are rewritten outside code only, then the body is quoted
+        assert!(formatted.contains(
+            "> See [https://github.com/owner/repo/discussions/123#discussioncomment-11](#reply-1-1)\n\
+             >\n\
+             > ```\n\
+             > https://github.com/owner/repo/discussions/123#discussioncomment-11\n\
+             > ```"
+        ));
+    }
 
+    #[test]
+    fn test_links_verbatim_without_local_links() {
+        let mut discussion = make_discussion();
+        discussion.body =
+            "[c](https://github.com/owner/repo/discussions/123#discussioncomment-1)".to_string();
+        discussion.comments.nodes = Some(vec![Some(make_comment(Some("user1"), "Comment 1"))]);
         let formatted = format_discussion(&discussion, "owner", "repo");
 
-        // Check proper reply numbering
-        assert!(formatted.contains("### Comment 1"));
-        assert!(formatted.contains("#### Reply 1.1"));
-        assert!(formatted.contains("Reply 1.1"));
-        assert!(formatted.contains("#### Reply 1.2"));
-        assert!(formatted.contains("Reply 1.2"));
-        assert!(formatted.contains("### Comment 2"));
-        assert!(formatted.contains("#### Reply 2.1"));
-        assert!(formatted.contains("Reply 2.1"));
+        assert!(
+            formatted
+                .contains("[c](https://github.com/owner/repo/discussions/123#discussioncomment-1)")
+        );
     }
 
     #[test]
-    fn test_comment_with_no_replies() {
+    fn test_format_dry_run_summary() {
         let mut discussion = make_discussion();
-        let comment = make_comment(Some("user1"), "Comment without replies");
+        let mut comment1 = make_comment(Some("user1"), "Comment 1");
+        comment1.replies.nodes = Some(vec![
+            Some(make_reply("reply_1", "Reply 1")),
+            None,
+            Some(make_reply("reply_2", "Reply 2")),
+        ]);
+        let mut comment2 = make_comment(Some("user2"), "Comment 2");
+        comment2.replies.nodes = Some(vec![Some(make_reply("reply_3", "Reply 3"))]);
+        discussion.comments.nodes = Some(vec![Some(comment1), None, Some(comment2)]);
 
-        discussion.comments.nodes = Some(vec![Some(comment)]);
-        let formatted = format_discussion(&discussion, "owner", "repo");
+        assert_eq!(
+            format_dry_run_summary(&discussion, "123-discussion.md"),
+            "Dry run: nothing was written\n\n\
+             Title: Test Discussion\n\
+             Comments: 2\n\
+             Replies: 3\n\
+             Output: 123-discussion.md\n"
+        );
+    }
 
-        assert!(formatted.contains("### Comment 1"));
-        assert!(formatted.contains("Comment without replies"));
-        // Should not contain reply headings
-        assert!(!formatted.contains("#### Reply"));
+    #[test]
+    fn test_format_porcelain_summary() {
+        let mut discussion = make_discussion();
+        let mut comment1 = make_comment(Some("user1"), "Comment 1");
+        comment1.replies.nodes = Some(vec![
+            Some(make_reply("reply_1", "Reply 1")),
+            None,
+            Some(make_reply("reply_2", "Reply 2")),
+        ]);
+        discussion.comments.nodes = Some(vec![
+            Some(comment1),
+            None,
+            Some(make_comment(Some("user2"), "Comment 2")),
+        ]);
+
+        assert_eq!(
+            format_porcelain_summary(&discussion, "exports/123 discussion.md"),
+            "exported comments=2 replies=2 path=exports/123 discussion.md\n"
+        );
+        assert_eq!(
+            format_porcelain_summary(&make_discussion(), "123-discussion.md"),
+            "exported comments=0 replies=0 path=123-discussion.md\n"
+        );
+    }
+
+    #[test]
+    fn test_format_dry_run_summary_without_comments() {
+        let discussion = make_discussion();
+        let summary = format_dry_run_summary(&discussion, "<stdout>");
+
+        assert!(summary.contains("Comments: 0\nReplies: 0\nOutput: <stdout>\n"));
     }
 
     #[test]
@@ -506,7 +2798,7 @@ mod tests {
         discussion.comments.nodes = Some(vec![Some(comment)]);
         let formatted = format_discussion(&discussion, "owner", "repo");
 
-        assert!(formatted.contains("_author: <deleted>"));
+        assert!(formatted.contains("_author: \\<deleted\\> ("));
         assert!(formatted.contains("Comment from deleted user"));
     }
 
@@ -523,6 +2815,7 @@ mod tests {
                 .unwrap()
                 .with_timezone(&Utc),
             body: "Reply from deleted user".to_string(),
+            ..Default::default()
         };
 
         comment.replies.nodes = Some(vec![Some(reply)]);
@@ -531,7 +2824,7 @@ mod tests {
         let formatted = format_discussion(&discussion, "owner", "repo");
 
         assert!(formatted.contains("#### Reply 1.1"));
-        assert!(formatted.contains("_author: <deleted>"));
+        assert!(formatted.contains("_author: \\<deleted\\> ("));
         assert!(formatted.contains("Reply from deleted user"));
     }
 
@@ -576,11 +2869,13 @@ mod tests {
             database_id: 1,
             author: Some(Author {
                 login: Some("replier1".to_string()),
+                typename: None,
             }),
             created_at: DateTime::parse_from_rfc3339("2024-01-15T12:00:00Z")
                 .unwrap()
                 .with_timezone(&Utc),
             body: "Reply 1".to_string(),
+            ..Default::default()
         };
 
         let reply2 = Reply {
@@ -588,11 +2883,13 @@ mod tests {
             database_id: 2,
             author: Some(Author {
                 login: Some("replier2".to_string()),
+                typename: None,
             }),
             created_at: DateTime::parse_from_rfc3339("2024-01-15T12:30:00Z")
                 .unwrap()
                 .with_timezone(&Utc),
             body: "Reply 2".to_string(),
+            ..Default::default()
         };
 
         // Create replies with None entries interspersed
@@ -615,4 +2912,704 @@ mod tests {
         // Should not contain Reply 1.3 (only 2 actual replies)
         assert!(!formatted.contains("#### Reply 1.3"));
     }
+
+    /// Discussion with one comment per database ID, bodies "Comment <id>"
+    fn discussion_with_comment_ids(ids: &[i64]) -> Discussion {
+        let mut discussion = make_discussion();
+        discussion.comments.nodes = Some(
+            ids.iter()
+                .map(|&id| {
+                    let mut comment = make_comment(Some("commenter"), &format!("Comment {}", id));
+                    comment.id = format!("C_{}", id);
+                    comment.database_id = id;
+                    Some(comment)
+                })
+                .collect(),
+        );
+        discussion
+    }
+
+    #[test]
+    fn test_parse_db_id_marker() {
+        assert_eq!(parse_db_id_marker("<!-- comment-db-id: 456 -->"), Some(456));
+        assert_eq!(
+            parse_db_id_marker("  <!-- comment-db-id:789-->  "),
+            Some(789)
+        );
+        assert_eq!(parse_db_id_marker("<!-- comment-db-id: abc -->"), None);
+        assert_eq!(parse_db_id_marker("<!-- comment-db-id: 456"), None);
+        assert_eq!(parse_db_id_marker("<!-- other: 456 -->"), None);
+        assert_eq!(parse_db_id_marker("<!-- reply-db-id: 456 -->"), None);
+        assert_eq!(parse_db_id_marker("Comment body"), None);
+    }
+
+    #[test]
+    fn test_generate_comments_with_db_id_markers() {
+        let mut discussion = discussion_with_comment_ids(&[456]);
+        let mut reply = make_reply("R_1", "Reply body");
+        reply.database_id = 789;
+        if let Some(Some(comment)) = discussion.comments.nodes.as_mut().unwrap().first_mut() {
+            comment.replies.nodes = Some(vec![Some(reply)]);
+        }
+        let options = OutputOptions {
+            db_id_markers: true,
+            ..Default::default()
+        };
+        let comments = generate_comments(&discussion, &options);
+        assert!(comments.contains(
+            "_author: commenter (2024-01-15T11:00:00Z)_\n\n<!-- comment-db-id: 456 -->\nComment 456\n"
+        ));
+        assert!(comments.contains("_\n\n<!-- reply-db-id: 789 -->\nReply body\n"));
+        // Reply markers are not mistaken for comments when appending
+        assert_eq!(exported_comment_ids(&comments), vec![456]);
+
+        let comments = generate_comments(&discussion, &OutputOptions::default());
+        assert!(!comments.contains("-db-id"));
+    }
+
+    #[test]
+    fn test_last_exported_comment_id() {
+        let options = OutputOptions {
+            db_id_markers: true,
+            ..Default::default()
+        };
+        let markdown = format_discussion_with_options(
+            &discussion_with_comment_ids(&[10, 30, 20]),
+            "owner",
+            "repo",
+            &options,
+        );
+        assert_eq!(exported_comment_ids(&markdown), vec![10, 30, 20]);
+        assert_eq!(last_exported_comment_id(&markdown), Some(30));
+
+        let markdown = format_discussion(&discussion_with_comment_ids(&[10]), "owner", "repo");
+        assert_eq!(last_exported_comment_id(&markdown), None);
+    }
+
+    #[test]
+    fn test_format_appended_comments() {
+        let options = OutputOptions {
+            db_id_markers: true,
+            ..Default::default()
+        };
+        let existing = format_discussion_with_options(
+            &discussion_with_comment_ids(&[10, 20]),
+            "owner",
+            "repo",
+            &options,
+        );
+
+        let appended = format_appended_comments(
+            &discussion_with_comment_ids(&[10, 20, 30, 40]),
+            &existing,
+            &options,
+        )
+        .unwrap();
+        assert_eq!((appended.comments, appended.replies), (2, 0));
+        let appended = appended.markdown;
+        assert!(!appended.contains("Comment 10\n") && !appended.contains("Comment 20\n"));
+        assert!(appended.starts_with("\n### Comment 3\n"));
+        assert!(appended.contains("<!-- comment-db-id: 30 -->\nComment 30\n"));
+        assert!(appended.contains("\n### Comment 4\n"));
+        assert_eq!(exported_comment_ids(&appended), vec![30, 40]);
+
+        // Appending the result again finds nothing new
+        let combined = format!("{}{}", existing, appended);
+        let appended = format_appended_comments(
+            &discussion_with_comment_ids(&[10, 20, 30, 40]),
+            &combined,
+            &options,
+        )
+        .unwrap();
+        assert_eq!(appended.markdown, "");
+        assert_eq!(appended.comments, 0);
+    }
+
+    #[test]
+    fn test_format_appended_comments_without_markers() {
+        let options = OutputOptions {
+            db_id_markers: true,
+            ..Default::default()
+        };
+        // Exported without --append: appending would duplicate every comment
+        let existing = format_discussion(&discussion_with_comment_ids(&[10, 20]), "owner", "repo");
+        let result = format_appended_comments(
+            &discussion_with_comment_ids(&[10, 20, 30]),
+            &existing,
+            &options,
+        );
+        assert!(matches!(
+            result,
+            Err(Error::InvalidArgs(msg)) if msg.contains("no db-id markers")
+        ));
+
+        // An export of a discussion without comments has nothing to duplicate
+        let existing = format_discussion(&discussion_with_comment_ids(&[]), "owner", "repo");
+        let appended =
+            format_appended_comments(&discussion_with_comment_ids(&[10]), &existing, &options)
+                .unwrap();
+        assert_eq!(appended.comments, 1);
+        assert!(appended.markdown.starts_with("\n### Comment 1\n"));
+    }
+
+    #[test]
+    fn test_exported_comment_ids_ignores_markers_in_bodies() {
+        let options = OutputOptions {
+            db_id_markers: true,
+            ..Default::default()
+        };
+        let mut discussion = discussion_with_comment_ids(&[10, 20]);
+        if let Some(Some(comment)) = discussion.comments.nodes.as_mut().unwrap().first_mut() {
+            comment.body = "Quoting the format:\n\n<!-- comment-db-id: 999999999 -->\n\n```\n<!-- comment-db-id: 999999998 -->\n```".to_string();
+            comment.replies.nodes = Some(vec![Some(make_reply("R_1", "Reply"))]);
+        }
+        let options_with_counts = OutputOptions {
+            reply_counts: true,
+            ..options.clone()
+        };
+        let markdown =
+            format_discussion_with_options(&discussion, "owner", "repo", &options_with_counts);
+        assert_eq!(exported_comment_ids(&markdown), vec![10, 20]);
+
+        // Collapsed runs keep their markers
+        let options = OutputOptions {
+            collapse_author_runs: true,
+            ..options
+        };
+        let markdown = format_discussion_with_options(
+            &discussion_with_comment_ids(&[10, 20]),
+            "owner",
+            "repo",
+            &options,
+        );
+        assert!(markdown.contains("* * *"));
+        assert_eq!(exported_comment_ids(&markdown), vec![10, 20]);
+    }
+
+    #[test]
+    fn test_format_porcelain_counts() {
+        assert_eq!(
+            format_porcelain_counts(2, 3, "out dir/1.md"),
+            "exported comments=2 replies=3 path=out dir/1.md\n"
+        );
+    }
+
+    #[test]
+    fn test_append_output() {
+        let file_path = std::env::temp_dir().join("test_append_output.md");
+        let path = file_path.to_str().unwrap();
+        fs::write(&file_path, "first\n").unwrap();
+
+        append_output("second\n", path).unwrap();
+        assert_eq!(fs::read_to_string(&file_path).unwrap(), "first\nsecond\n");
+
+        fs::remove_file(&file_path).ok();
+        assert!(matches!(append_output("x", path), Err(Error::Io(_))));
+    }
+
+    #[test]
+    fn test_generate_split_index() {
+        let mut discussion = discussion_with_comment_ids(&[10, 20, 30]);
+        discussion.answer = Some(crate::models::DiscussionAnswer {
+            id: "C_20".to_string(),
+        });
+        let index = generate_split_index(&discussion, "owner", "repo", &OutputOptions::default());
+
+        assert!(index.starts_with("# Test Discussion\n"));
+        assert!(index.contains("## Original Post\n"));
+        assert!(index.ends_with(
+            "## Comments\n\n\
+             - [Comment 1 by commenter](comment-1.md)\n\
+             - [Comment 2 by commenter](comment-2.md) (✓ Accepted Answer)\n\
+             - [Comment 3 by commenter](comment-3.md)\n"
+        ));
+        assert!(!index.contains("Comment 10"));
+    }
+
+    #[test]
+    fn test_generate_comment_file() {
+        let mut discussion = discussion_with_comment_ids(&[10, 20, 30]);
+        let mut reply = make_reply("R_1", "Reply body");
+        reply.database_id = 31;
+        reply.author = Some(Author {
+            login: Some("replier".to_string()),
+            typename: None,
+        });
+        reply.created_at = DateTime::parse_from_rfc3339("2024-01-15T12:00:00Z")
+            .unwrap()
+            .with_timezone(&Utc);
+        if let Some(Some(comment)) = discussion.comments.nodes.as_mut().unwrap().get_mut(2) {
+            comment.replies.nodes = Some(vec![Some(reply)]);
+        }
+        let comment = discussion_comments(&discussion)[2];
+        let file = generate_comment_file(
+            &discussion,
+            comment,
+            3,
+            "123-discussion.md",
+            &HashMap::new(),
+            &OutputOptions::default(),
+        );
+
+        assert_eq!(
+            file,
+            "# Test Discussion\n\n\
+             [Back to index](123-discussion.md)\n\n\
+             ### Comment 3\n\n\
+             _author: commenter (2024-01-15T11:00:00Z)_\n\n\
+             Comment 30\n\n\n\
+             #### Reply 3.1\n\n\
+             _author: replier (2024-01-15T12:00:00Z)_\n\n\
+             Reply body\n\n"
+        );
+    }
+
+    #[test]
+    fn test_format_discussion_split() {
+        let discussion = discussion_with_comment_ids(&[10, 20, 30]);
+        let files = format_discussion_split(
+            &discussion,
+            "owner",
+            "repo",
+            "index.md",
+            &OutputOptions::default(),
+        );
+
+        let names: Vec<_> = files.iter().map(|file| file.name.as_str()).collect();
+        assert_eq!(
+            names,
+            ["index.md", "comment-1.md", "comment-2.md", "comment-3.md"]
+        );
+        assert!(files[2].content.contains("### Comment 2\n"));
+        assert!(files[2].content.contains("Comment 20\n"));
+        assert!(files[2].content.contains("[Back to index](index.md)"));
+    }
+
+    #[test]
+    fn test_format_discussion_split_local_links() {
+        let mut discussion = discussion_with_comment_ids(&[10, 20]);
+        discussion.body =
+            "See https://github.com/owner/repo/discussions/123#discussioncomment-20".to_string();
+        if let Some(Some(comment)) = discussion.comments.nodes.as_mut().unwrap().get_mut(0) {
+            comment.body =
+                "[Below](https://github.com/owner/repo/discussions/123#discussioncomment-20)"
+                    .to_string();
+        }
+        let options = OutputOptions {
+            local_links: true,
+            ..Default::default()
+        };
+        let files = format_discussion_split(&discussion, "owner", "repo", "index.md", &options);
+
+        // Links point into the comment files, whose headings carry the anchors
+        assert!(files[0].content.contains(
+            "See [https://github.com/owner/repo/discussions/123#discussioncomment-20](comment-2.md#comment-2)"
+        ));
+        assert!(files[1].content.contains("[Below](comment-2.md#comment-2)"));
+        assert!(
+            files[2]
+                .content
+                .contains("<a id=\"comment-2\"></a>\n\n### Comment 2\n")
+        );
+    }
+
+    #[test]
+    fn test_write_split_output() {
+        let dir = std::env::temp_dir().join("test_write_split_output");
+        fs::remove_dir_all(&dir).ok();
+        fs::create_dir_all(&dir).unwrap();
+        let index_path = dir.join("index.md");
+        let index = index_path.to_str().unwrap();
+        let files = vec![
+            SplitFile {
+                name: "index.md".to_string(),
+                content: "index\n".to_string(),
+            },
+            SplitFile {
+                name: "comment-1.md".to_string(),
+                content: "comment\n".to_string(),
+            },
+        ];
+
+        write_split_output(&files, index, false).unwrap();
+        assert_eq!(fs::read_to_string(&index_path).unwrap(), "index\n");
+        assert_eq!(
+            fs::read_to_string(dir.join("comment-1.md")).unwrap(),
+            "comment\n"
+        );
+
+        // An existing comment file stops the export unless forced
+        fs::remove_file(&index_path).unwrap();
+        assert!(matches!(
+            write_split_output(&files, index, false),
+            Err(Error::OutputExists(p)) if p.ends_with("comment-1.md")
+        ));
+        assert!(!index_path.exists());
+        assert!(write_split_output(&files, index, true).is_ok());
+
+        fs::remove_dir_all(&dir).ok();
+    }
+
+    #[test]
+    fn test_body_format_html() {
+        let mut discussion = discussion_with_comment_ids(&[10, 20]);
+        discussion.body_html = Some("<p>Post HTML</p>".to_string());
+        if let Some(Some(comment)) = discussion.comments.nodes.as_mut().unwrap().first_mut() {
+            comment.body_html = Some("<p>Comment HTML</p>".to_string());
+        }
+        let options = OutputOptions {
+            body_format: BodyFormat::Html,
+            ..Default::default()
+        };
+        let markdown = format_discussion_with_options(&discussion, "owner", "repo", &options);
+        assert!(markdown.contains("_\n\n<p>Post HTML</p>\n"));
+        assert!(markdown.contains("_\n\n<p>Comment HTML</p>\n"));
+        // Falls back to the Markdown source where no HTML was fetched
+        assert!(markdown.contains("_\n\nComment 20\n"));
+
+        let markdown = format_discussion(&discussion, "owner", "repo");
+        assert!(!markdown.contains("HTML</p>"));
+    }
+
+    #[test]
+    fn test_render_body_with_link_mentions() {
+        let options = OutputOptions {
+            link_mentions: true,
+            blockquote_bodies: true,
+            ..Default::default()
+        };
+        assert_eq!(
+            render_body("Thanks @octocat\n`@code`", &HashMap::new(), &options),
+            "> Thanks [@octocat](https://github.com/octocat)\n> `@code`"
+        );
+        assert_eq!(
+            process_body_with_options("Thanks @octocat", &OutputOptions::default()),
+            "Thanks @octocat"
+        );
+    }
+
+    #[test]
+    fn test_process_body_with_normalize_unicode() {
+        // "が" as か (U+304B) + combining dakuten (U+3099), and "é" as e + U+0301
+        let decomposed = "\u{304B}\u{3099}\r\ncafe\u{0301}";
+        let options = OutputOptions {
+            normalize_unicode: true,
+            ..Default::default()
+        };
+        assert_eq!(
+            process_body_with_options(decomposed, &options),
+            "\u{304C}\ncaf\u{00E9}"
+        );
+        // Off by default: only line endings change
+        assert_eq!(
+            process_body_with_options(decomposed, &OutputOptions::default()),
+            "\u{304B}\u{3099}\ncafe\u{0301}"
+        );
+    }
+
+    #[test]
+    fn test_heading_escape_never() {
+        let input = "# Title\n## Section\nText\n";
+        assert_eq!(escape_headings(input, HeadingEscape::Never), input);
+    }
+
+    #[test]
+    fn test_heading_escape_min_level() {
+        let input = "# Title\n## Section\n### Detail\n#### Deeper\n#tag\n";
+        assert_eq!(
+            escape_headings(input, HeadingEscape::MinLevel(3)),
+            "# Title\n## Section\n\\### Detail\n\\#### Deeper\n#tag\n"
+        );
+        assert_eq!(
+            escape_headings(input, HeadingEscape::MinLevel(1)),
+            escape_headings(input, HeadingEscape::All)
+        );
+    }
+
+    #[test]
+    fn test_process_body_with_heading_escape() {
+        let options = OutputOptions {
+            heading_escape: HeadingEscape::Never,
+            ..Default::default()
+        };
+        assert_eq!(
+            process_body_with_options("# Title\r\nText", &options),
+            "# Title\nText"
+        );
+        assert_eq!(
+            process_body_with_options("# Title\r\nText", &OutputOptions::default()),
+            "\\# Title\nText"
+        );
+    }
+
+    #[test]
+    fn test_bodies_embedded_verbatim() {
+        let body = "Para 1  \n\n\nPara 2\n   \n\n";
+        let options = OutputOptions::default();
+
+        let mut discussion = make_discussion();
+        discussion.body = body.to_string();
+        let post = generate_original_post(&discussion, &options);
+        assert!(post.contains(&format!(")_\n\n{}\n\n---\n", body)));
+
+        let mut comment = make_comment(Some("user"), body);
+        comment.replies.nodes = Some(vec![Some(make_reply("R_1", body))]);
+        discussion.comments.nodes = Some(vec![Some(comment)]);
+        let comments = generate_comments(&discussion, &options);
+        assert_eq!(comments.matches(&format!(")_\n\n{}\n\n", body)).count(), 2);
+    }
+
+    #[test]
+    fn test_process_body_with_strip_html_comments() {
+        let options = OutputOptions {
+            strip_html_comments: true,
+            ..Default::default()
+        };
+        assert_eq!(
+            process_body_with_options("<!-- hidden --># Title\r\n<!-- x -->\r\nText", &options),
+            "\\# Title\nText"
+        );
+        assert_eq!(
+            process_body_with_options("Text <!-- x -->", &OutputOptions::default()),
+            "Text <!-- x -->"
+        );
+    }
+
+    #[test]
+    fn test_stats_footer() {
+        let mut discussion = make_discussion();
+        discussion.body = "Three word body".to_string();
+        let mut comment = make_comment(Some("user1"), "Two words");
+        comment.created_at = "2024-01-16T08:00:00Z".parse().unwrap();
+        discussion.comments.nodes = Some(vec![Some(comment)]);
+        let options = OutputOptions {
+            stats: true,
+            ..Default::default()
+        };
+
+        let formatted = format_discussion_with_options(&discussion, "owner", "repo", &options);
+        assert!(formatted.ends_with(
+            "Two words\n\n\n## Statistics\n\n- Comments: 1\n- Replies: 0\n- Participants: 2\n- Words: 5\n- Date span: 2024-01-15T10:30:00Z to 2024-01-16T08:00:00Z\n"
+        ));
+        assert!(!format_discussion(&discussion, "owner", "repo").contains("## Statistics"));
+
+        // The footer covers comments the export omits
+        let post_only = OutputOptions {
+            post_only: true,
+            ..options
+        };
+        let formatted = format_discussion_with_options(&discussion, "owner", "repo", &post_only);
+        assert!(formatted.contains("---\n\n## Statistics\n\n- Comments: 1\n"));
+    }
+
+    fn make_poll(question: &str, options: &[(&str, u64)]) -> Poll {
+        Poll {
+            question: question.to_string(),
+            total_vote_count: options.iter().map(|(_, n)| n).sum(),
+            options: crate::models::PollOptions {
+                nodes: Some(
+                    options
+                        .iter()
+                        .map(|&(option, n)| {
+                            Some(crate::models::PollOption {
+                                option: option.to_string(),
+                                total_vote_count: n,
+                            })
+                        })
+                        .collect(),
+                ),
+            },
+        }
+    }
+
+    #[test]
+    fn test_original_post_with_poll() {
+        let mut discussion = make_discussion();
+        discussion.upvote_count = 2;
+        discussion.poll = Some(make_poll("Which *name*?", &[("foo", 2), ("bar", 1)]));
+
+        let post = generate_original_post(&discussion, &OutputOptions::default());
+        assert!(post.ends_with(
+            "This is the original post body.\n\n\
+             Poll: Which \\*name\\*?\n\n\
+             - foo: 2 votes (67%)\n\
+             - bar: 1 vote (33%)\n\n\
+             Total votes: 3\n\n\
+             Upvotes: 2\n\n---\n"
+        ));
+    }
+
+    #[test]
+    fn test_format_poll() {
+        assert_eq!(format_poll(None), "");
+        assert_eq!(
+            format_poll(Some(&make_poll("No votes yet", &[("yes", 0), ("no", 0)]))),
+            "Poll: No votes yet\n\n- yes: 0 votes (0%)\n- no: 0 votes (0%)\n\nTotal votes: 0\n\n"
+        );
+    }
+
+    #[test]
+    fn test_collapse_author_runs() {
+        let mut discussion = make_discussion();
+        let mut first = make_comment(Some("alice"), "First part");
+        first.id = "C_1".to_string();
+        let mut second = make_comment(Some("alice"), "Second part");
+        second.id = "C_2".to_string();
+        second.created_at = "2024-01-15T11:05:00Z".parse().unwrap();
+        let mut third = make_comment(Some("bob"), "Reply from bob");
+        third.id = "C_3".to_string();
+        third.created_at = "2024-01-15T12:00:00Z".parse().unwrap();
+        discussion.comments.nodes = Some(vec![Some(first), Some(second), Some(third)]);
+        let options = OutputOptions {
+            collapse_author_runs: true,
+            ..Default::default()
+        };
+
+        let comments = generate_comments(&discussion, &options);
+        assert_eq!(comments.matches("### Comment").count(), 2);
+        assert!(comments.contains(
+            "### Comment 1\n\n_author: alice (2024-01-15T11:00:00Z)_\n\nFirst part\n\n\n\
+             * * *\n\n_Comment 2 (2024-01-15T11:05:00Z)_\n\nSecond part\n\n\n\
+             ### Comment 3\n\n_author: bob (2024-01-15T12:00:00Z)_\n\nReply from bob\n"
+        ));
+
+        // Off by default
+        let comments = generate_comments(&discussion, &OutputOptions::default());
+        assert_eq!(comments.matches("### Comment").count(), 3);
+    }
+
+    #[test]
+    fn test_format_discussion_ndjson() {
+        let mut discussion = make_discussion();
+        let mut first = make_comment(Some("alice"), "First\nwith \"quotes\"");
+        first.id = "C_1".to_string();
+        first.database_id = 11;
+        first.replies.nodes = Some(vec![
+            Some(make_reply("R_1", "Reply one")),
+            Some(make_reply("R_2", "Reply two")),
+        ]);
+        let mut second = make_comment(Some("bob"), "Second");
+        second.id = "C_2".to_string();
+        discussion.comments.nodes = Some(vec![Some(first), None, Some(second)]);
+
+        let ndjson = format_discussion_ndjson(&discussion).unwrap();
+        assert!(ndjson.ends_with('\n'));
+        let lines: Vec<serde_json::Value> = ndjson
+            .lines()
+            .map(|line| serde_json::from_str(line).unwrap())
+            .collect();
+        // The post, two comments, and two replies
+        assert_eq!(lines.len(), 5);
+        let kinds: Vec<&str> = lines.iter().map(|l| l["type"].as_str().unwrap()).collect();
+        assert_eq!(kinds, ["post", "comment", "reply", "reply", "comment"]);
+
+        assert_eq!(lines[0]["author"], "testuser");
+        assert!(lines[0]["databaseId"].is_null());
+        assert_eq!(lines[1]["id"], "C_1");
+        assert_eq!(lines[1]["databaseId"], 11);
+        assert_eq!(lines[1]["body"], "First\nwith \"quotes\"");
+        assert_eq!(lines[1]["createdAt"], "2024-01-15T11:00:00Z");
+        assert!(lines[1].get("parent_id").is_none());
+        assert_eq!(lines[3]["id"], "R_2");
+        assert_eq!(lines[3]["parent_id"], "C_1");
+        assert_eq!(lines[4]["author"], "bob");
+    }
+
+    #[test]
+    fn test_indent_reply() {
+        assert_eq!(indent_reply("a\n\nb\n\n"), "> a\n>\n> b\n\n");
+        assert_eq!(indent_reply("a"), "> a");
+        // Indented lines keep their indentation inside the quote
+        assert_eq!(
+            indent_reply("Intro\n\n  indented\n\n    code\n"),
+            "> Intro\n>\n>   indented\n>\n>     code\n"
+        );
+    }
+
+    #[test]
+    fn test_indent_replies() {
+        let mut discussion = make_discussion();
+        let mut comment = make_comment(Some("commenter"), "Comment body");
+        let mut reply = make_reply("R_1", "## Heading\n\n> Quoted\n\n```\ncode\n```");
+        reply.author = Some(Author {
+            login: Some("replier".to_string()),
+            typename: None,
+        });
+        reply.created_at = "2024-01-15T12:00:00Z".parse().unwrap();
+        reply.upvote_count = 2;
+        comment.replies.nodes = Some(vec![Some(reply)]);
+        discussion.comments.nodes = Some(vec![Some(comment)]);
+        let options = OutputOptions {
+            indent_replies: true,
+            ..Default::default()
+        };
+
+        let comments = generate_comments(&discussion, &options);
+        // The comment itself is not indented
+        assert!(
+            comments.contains("\n_author: commenter (2024-01-15T11:00:00Z)_\n\nComment body\n")
+        );
+        assert!(comments.contains(
+            "\n#### Reply 1.1\n\n> _author: replier (2024-01-15T12:00:00Z)_\n>\n> \\## Heading\n>\n> > Quoted\n>\n> ```\n> code\n> ```\n>\n> Upvotes: 2\n\n"
+        ));
+
+        // Bodies are blockquoted, then indented one level deeper
+        let options = OutputOptions {
+            indent_replies: true,
+            blockquote_bodies: true,
+            ..Default::default()
+        };
+        let comments = generate_comments(&discussion, &options);
+        assert!(comments.contains(
+            "> _author: replier (2024-01-15T12:00:00Z)_\n>\n> > \\## Heading\n> >\n> > > Quoted\n"
+        ));
+    }
+
+    #[test]
+    fn test_indent_replies_keeps_indented_lines() {
+        let mut discussion = make_discussion();
+        let mut comment = make_comment(Some("commenter"), "Comment body");
+        let mut reply = make_reply("R_1", "Steps:\n\n  two-space indented line\n");
+        reply.author = Some(Author {
+            login: Some("replier".to_string()),
+            typename: None,
+        });
+        comment.replies.nodes = Some(vec![Some(reply)]);
+        discussion.comments.nodes = Some(vec![Some(comment)]);
+        let options = OutputOptions {
+            indent_replies: true,
+            ..Default::default()
+        };
+
+        let comments = generate_comments(&discussion, &options);
+        // Two spaces after the quote marker: still a paragraph, not code
+        assert!(comments.contains("> Steps:\n>\n>   two-space indented line\n\n"));
+        assert!(!comments.contains(">     two-space"));
+    }
+
+    #[test]
+    fn test_collapse_author_runs_stops_at_replies_and_answers() {
+        let mut discussion = make_discussion();
+        let mut with_reply = make_comment(Some("alice"), "Has a reply");
+        with_reply.replies.nodes = Some(vec![Some(make_reply("R_1", "Reply"))]);
+        let after_reply = make_comment(Some("alice"), "After the reply");
+        let mut answer = make_comment(Some("alice"), "The answer");
+        answer.is_answer = true;
+        let deleted = make_comment(Some(DELETED_AUTHOR), "Deleted one");
+        let deleted_again = make_comment(Some(DELETED_AUTHOR), "Deleted two");
+        discussion.comments.nodes = Some(
+            [with_reply, after_reply, answer, deleted, deleted_again]
+                .into_iter()
+                .map(Some)
+                .collect(),
+        );
+        let options = OutputOptions {
+            collapse_author_runs: true,
+            ..Default::default()
+        };
+
+        let comments = generate_comments(&discussion, &options);
+        assert_eq!(comments.matches("### Comment").count(), 5);
+        assert!(!comments.contains("* * *"));
+    }
 }
@@ -0,0 +1,160 @@
+//! Progress reporting for long-running fetches
+//!
+//! This module provides a simple counter that renders `<label>: <current>/<total>`
+//! on a single terminal line, so users exporting large discussions get feedback
+//! while pages are being fetched.
+
+use std::io::{IsTerminal, Write};
+
+/// Reports progress of a counted operation on a single line.
+///
+/// A hidden reporter only tracks counts without printing anything, which is
+/// used for `--quiet` runs, non-terminal stderr, and tests.
+pub struct ProgressReporter {
+    label: String,
+    current: u64,
+    total: Option<u64>,
+    output: Option<Box<dyn Write>>,
+}
+
+impl ProgressReporter {
+    /// Create a reporter that prints to stderr when `enabled` is true and
+    /// stderr is a terminal; otherwise the reporter is hidden.
+    pub fn new(label: &str, enabled: bool) -> Self {
+        if enabled && std::io::stderr().is_terminal() {
+            Self::with_output(label, Box::new(std::io::stderr()))
+        } else {
+            Self::hidden(label)
+        }
+    }
+
+    /// Create a reporter that writes to the given output
+    pub fn with_output(label: &str, output: Box<dyn Write>) -> Self {
+        Self {
+            label: label.to_string(),
+            current: 0,
+            total: None,
+            output: Some(output),
+        }
+    }
+
+    /// Create a reporter that tracks counts but never prints
+    pub fn hidden(label: &str) -> Self {
+        Self {
+            label: label.to_string(),
+            current: 0,
+            total: None,
+            output: None,
+        }
+    }
+
+    /// Set the expected total (e.g. from a GraphQL `totalCount`)
+    pub fn set_total(&mut self, total: u64) {
+        self.total = Some(total);
+        self.render();
+    }
+
+    /// Advance the counter by `n` and redraw the progress line
+    pub fn inc(&mut self, n: u64) {
+        self.current += n;
+        self.render();
+    }
+
+    /// Current count
+    pub fn current(&self) -> u64 {
+        self.current
+    }
+
+    /// Expected total, if known
+    pub fn total(&self) -> Option<u64> {
+        self.total
+    }
+
+    /// Terminate the progress line (only if anything was printed)
+    pub fn finish(&mut self) {
+        if let Some(output) = self.output.as_mut() {
+            // Progress output is best-effort; ignore write failures
+            let _ = writeln!(output);
+            let _ = output.flush();
+        }
+    }
+
+    /// Format the progress line without control characters
+    fn line(&self) -> String {
+        match self.total {
+            Some(total) => format!("{}: {}/{}", self.label, self.current, total),
+            None => format!("{}: {}", self.label, self.current),
+        }
+    }
+
+    fn render(&mut self) {
+        let line = self.line();
+        if let Some(output) = self.output.as_mut() {
+            let _ = write!(output, "\r{}", line);
+            let _ = output.flush();
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::{Arc, Mutex};
+
+    /// Writer that appends to a shared buffer so tests can inspect output
+    #[derive(Clone, Default)]
+    struct SharedBuffer(Arc<Mutex<Vec<u8>>>);
+
+    impl Write for SharedBuffer {
+        fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+            self.0.lock().unwrap().extend_from_slice(buf);
+            Ok(buf.len())
+        }
+
+        fn flush(&mut self) -> std::io::Result<()> {
+            Ok(())
+        }
+    }
+
+    impl SharedBuffer {
+        fn contents(&self) -> String {
+            String::from_utf8(self.0.lock().unwrap().clone()).unwrap()
+        }
+    }
+
+    #[test]
+    fn test_hidden_reporter_tracks_counts() {
+        let mut reporter = ProgressReporter::hidden("Fetching comments");
+        reporter.set_total(10);
+        reporter.inc(3);
+        reporter.inc(4);
+        reporter.finish();
+
+        assert_eq!(reporter.current(), 7);
+        assert_eq!(reporter.total(), Some(10));
+    }
+
+    #[test]
+    fn test_reporter_renders_progress_line() {
+        let buffer = SharedBuffer::default();
+        let mut reporter =
+            ProgressReporter::with_output("Fetching comments", Box::new(buffer.clone()));
+        reporter.set_total(5);
+        reporter.inc(2);
+        reporter.finish();
+
+        assert_eq!(
+            buffer.contents(),
+            "\rFetching comments: 0/5\rFetching comments: 2/5\n"
+        );
+    }
+
+    #[test]
+    fn test_reporter_without_total() {
+        let buffer = SharedBuffer::default();
+        let mut reporter = ProgressReporter::with_output("Fetching", Box::new(buffer.clone()));
+        reporter.inc(1);
+
+        assert_eq!(buffer.contents(), "\rFetching: 1");
+    }
+}
@@ -0,0 +1,180 @@
+// Summary statistics of a discussion
+//
+// Computed over the fully fetched `Discussion` for the `--stats` footer,
+// independent of which sections the export renders.
+
+use chrono::{DateTime, Utc};
+use std::collections::HashSet;
+
+use crate::models::{Author, DELETED_AUTHOR, Discussion};
+
+/// Summary statistics of a discussion
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Stats {
+    /// Number of fetched comments
+    pub comments: usize,
+    /// Number of fetched replies across all comments
+    pub replies: usize,
+    /// Number of distinct author logins, excluding deleted accounts
+    pub participants: usize,
+    /// Number of whitespace-separated words in the post, comment, and reply bodies
+    pub words: usize,
+    /// Earliest `created_at` of the post, comments, and replies
+    pub first_activity: DateTime<Utc>,
+    /// Latest `created_at` of the post, comments, and replies
+    pub last_activity: DateTime<Utc>,
+}
+
+/// Compute the statistics of a discussion
+///
+/// `null` comments and replies (deleted ones) are not counted.
+pub fn compute_stats(discussion: &Discussion) -> Stats {
+    let mut stats = Stats {
+        comments: 0,
+        replies: 0,
+        participants: 0,
+        words: 0,
+        first_activity: discussion.created_at,
+        last_activity: discussion.created_at,
+    };
+    let mut participants = HashSet::new();
+    let mut count = |author: Option<&Author>, body: &str, created_at: DateTime<Utc>| {
+        if let Some(login) = author.and_then(|a| a.login.as_deref())
+            && login != DELETED_AUTHOR
+        {
+            participants.insert(login.to_string());
+        }
+        stats.words += body.split_whitespace().count();
+        stats.first_activity = stats.first_activity.min(created_at);
+        stats.last_activity = stats.last_activity.max(created_at);
+    };
+
+    count(
+        discussion.author.as_ref(),
+        &discussion.body,
+        discussion.created_at,
+    );
+    let mut comments = 0;
+    let mut replies = 0;
+    for comment in discussion.comments.nodes.iter().flatten().flatten() {
+        comments += 1;
+        count(comment.author.as_ref(), &comment.body, comment.created_at);
+        for reply in comment.replies.nodes.iter().flatten().flatten() {
+            replies += 1;
+            count(reply.author.as_ref(), &reply.body, reply.created_at);
+        }
+    }
+
+    stats.comments = comments;
+    stats.replies = replies;
+    stats.participants = participants.len();
+    stats
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::{Comment, CommentReplies, DiscussionComments, Reply};
+
+    fn author(login: Option<&str>) -> Option<Author> {
+        Some(Author {
+            login: login.map(str::to_string),
+            typename: None,
+        })
+    }
+
+    fn timestamp(s: &str) -> DateTime<Utc> {
+        s.parse().unwrap()
+    }
+
+    fn make_discussion(comments: Vec<Option<Comment>>) -> Discussion {
+        Discussion {
+            id: "D_1".to_string(),
+            title: "Title".to_string(),
+            number: 1,
+            url: "https://github.com/owner/repo/discussions/1".to_string(),
+            created_at: timestamp("2024-01-15T10:00:00Z"),
+            body: "Original post body".to_string(),
+            author: author(Some("alice")),
+            comments: DiscussionComments {
+                nodes: Some(comments),
+                ..Default::default()
+            },
+            ..Default::default()
+        }
+    }
+
+    fn make_comment(login: Option<&str>, created_at: &str, replies: Vec<Reply>) -> Comment {
+        Comment {
+            id: "C".to_string(),
+            author: author(login),
+            created_at: timestamp(created_at),
+            body: "two words".to_string(),
+            replies: CommentReplies {
+                nodes: Some(replies.into_iter().map(Some).collect()),
+                ..Default::default()
+            },
+            ..Default::default()
+        }
+    }
+
+    fn make_reply(login: Option<&str>, created_at: &str) -> Reply {
+        Reply {
+            id: "R".to_string(),
+            author: author(login),
+            created_at: timestamp(created_at),
+            body: "a\nthree  word reply".to_string(),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_stats_of_post_only() {
+        let stats = compute_stats(&make_discussion(vec![]));
+        assert_eq!(stats.comments, 0);
+        assert_eq!(stats.replies, 0);
+        assert_eq!(stats.participants, 1);
+        assert_eq!(stats.words, 3);
+        assert_eq!(stats.first_activity, timestamp("2024-01-15T10:00:00Z"));
+        assert_eq!(stats.last_activity, timestamp("2024-01-15T10:00:00Z"));
+    }
+
+    #[test]
+    fn test_stats_participants() {
+        let discussion = make_discussion(vec![
+            Some(make_comment(
+                Some("bob"),
+                "2024-01-15T11:00:00Z",
+                vec![
+                    make_reply(Some("alice"), "2024-01-15T12:00:00Z"),
+                    make_reply(Some(DELETED_AUTHOR), "2024-01-15T12:30:00Z"),
+                ],
+            )),
+            None,
+            Some(make_comment(Some("bob"), "2024-01-16T09:00:00Z", vec![])),
+            Some(make_comment(None, "2024-01-16T10:00:00Z", vec![])),
+        ]);
+        let stats = compute_stats(&discussion);
+        // alice and bob; the deleted placeholder and null authors are excluded
+        assert_eq!(stats.participants, 2);
+        assert_eq!(stats.comments, 3);
+        assert_eq!(stats.replies, 2);
+        assert_eq!(stats.words, 3 + 3 * 2 + 2 * 4);
+    }
+
+    #[test]
+    fn test_stats_date_span() {
+        // Comments are not necessarily in chronological order
+        let discussion = make_discussion(vec![
+            Some(make_comment(
+                Some("bob"),
+                "2024-03-01T08:00:00Z",
+                vec![make_reply(Some("carol"), "2024-03-02T18:45:00Z")],
+            )),
+            Some(make_comment(Some("dave"), "2024-01-01T00:00:00Z", vec![])),
+        ]);
+        let stats = compute_stats(&discussion);
+        assert_eq!(stats.first_activity, timestamp("2024-01-01T00:00:00Z"));
+        assert_eq!(stats.last_activity, timestamp("2024-03-02T18:45:00Z"));
+    }
+}
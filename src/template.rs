@@ -0,0 +1,416 @@
+// Template-based Markdown rendering
+//
+// Renders a discussion through a user-supplied Handlebars template
+// (`--template`) instead of the hardcoded layout in `output.rs`. Values are
+// formatted with the same output options as the built-in layout, so only the
+// layout comes from the template.
+
+use handlebars::Handlebars;
+use serde::Serialize;
+
+use crate::error::{Error, Result};
+use crate::models::Discussion;
+use crate::output::{
+    OutputOptions, category_label, comment_permalink, discussion_comments, edited_timestamp,
+    escape_markdown_text, format_author, format_poll, format_timestamp, link_anchors,
+    markdown_author_login, reaction_summary, render_body, reply_count, source_body, status_label,
+};
+
+/// Built-in template reproducing the default Markdown layout
+///
+/// A starting point for custom templates; rendering it with default output
+/// options gives the same output as `format_discussion`.
+pub const DEFAULT_TEMPLATE: &str = include_str!("../templates/default.md.hbs");
+
+/// Template context for a discussion
+///
+/// Text values are ready to insert into Markdown: timestamps follow
+/// `--timezone` / `--date-format`, authors follow `--author-links`, and
+/// bodies are processed as in the built-in layout. The raw fetched data is
+/// available as `discussion`, with the GraphQL field names.
+#[derive(Debug, Serialize)]
+pub struct TemplateContext<'a> {
+    pub owner: &'a str,
+    pub repo: &'a str,
+    /// Title with Markdown syntax escaped
+    pub title: String,
+    pub number: u64,
+    pub url: &'a str,
+    /// Author login, never linked
+    pub author_login: &'a str,
+    pub author: String,
+    pub created_at: String,
+    /// Edit time, absent if the post was never edited
+    pub edited_at: Option<String>,
+    /// Category name with its emoji, e.g. `💬 General`
+    pub category: Option<String>,
+    /// e.g. `closed at 2024-02-01T00:00:00Z (locked)` or `open (locked)`;
+    /// absent when open
+    pub status: Option<String>,
+    pub body: String,
+    /// Poll section as in the built-in layout; absent when there is no poll
+    pub poll: Option<String>,
+    pub upvotes: u64,
+    /// Reaction summary, e.g. `👍 5  ❤️ 2`; absent when there are none
+    pub reactions: Option<String>,
+    pub comments: Vec<CommentContext>,
+    pub discussion: &'a Discussion,
+}
+
+/// Template context for a comment
+#[derive(Debug, Serialize)]
+pub struct CommentContext {
+    /// 1-based position among the exported comments
+    pub number: usize,
+    pub database_id: i64,
+    pub author: String,
+    pub created_at: String,
+    pub edited_at: Option<String>,
+    pub is_answer: bool,
+    pub body: String,
+    pub upvotes: u64,
+    pub reactions: Option<String>,
+    pub permalink: String,
+    pub reply_count: u64,
+    pub replies: Vec<ReplyContext>,
+}
+
+/// Template context for a reply
+#[derive(Debug, Serialize)]
+pub struct ReplyContext {
+    /// 1-based position among the replies of its comment
+    pub number: usize,
+    /// Number of the comment this reply belongs to
+    pub comment_number: usize,
+    pub database_id: i64,
+    pub author: String,
+    pub created_at: String,
+    pub edited_at: Option<String>,
+    pub body: String,
+    pub upvotes: u64,
+    pub reactions: Option<String>,
+    pub permalink: String,
+}
+
+impl<'a> TemplateContext<'a> {
+    /// Build the template context for a discussion
+    pub fn new(
+        discussion: &'a Discussion,
+        owner: &'a str,
+        repo: &'a str,
+        options: &OutputOptions,
+    ) -> Self {
+        let anchors = link_anchors(discussion, options);
+        let answer_id = discussion.answer.as_ref().map(|a| a.id.as_str());
+
+        let comments = discussion_comments(discussion)
+            .into_iter()
+            .enumerate()
+            .map(|(i, comment)| {
+                let comment_number = i + 1;
+                let replies = comment
+                    .replies
+                    .nodes
+                    .iter()
+                    .flatten()
+                    .flatten()
+                    .enumerate()
+                    .map(|(j, reply)| ReplyContext {
+                        number: j + 1,
+                        comment_number,
+                        database_id: reply.database_id,
+                        author: format_author(reply.author.as_ref(), options),
+                        created_at: format_timestamp(&reply.created_at, options),
+                        edited_at: edited_timestamp(
+                            &reply.created_at,
+                            reply.last_edited_at.as_ref(),
+                            options,
+                        ),
+                        body: render_body(
+                            source_body(&reply.body, reply.body_html.as_deref(), options),
+                            &anchors,
+                            options,
+                        ),
+                        upvotes: reply.upvote_count,
+                        reactions: reaction_summary(reply.reaction_groups.as_ref()),
+                        permalink: comment_permalink(&discussion.url, reply.database_id),
+                    })
+                    .collect();
+                CommentContext {
+                    number: comment_number,
+                    database_id: comment.database_id,
+                    author: format_author(comment.author.as_ref(), options),
+                    created_at: format_timestamp(&comment.created_at, options),
+                    edited_at: edited_timestamp(
+                        &comment.created_at,
+                        comment.last_edited_at.as_ref(),
+                        options,
+                    ),
+                    is_answer: comment.is_answer || answer_id == Some(comment.id.as_str()),
+                    body: render_body(
+                        source_body(&comment.body, comment.body_html.as_deref(), options),
+                        &anchors,
+                        options,
+                    ),
+                    upvotes: comment.upvote_count,
+                    reactions: reaction_summary(comment.reaction_groups.as_ref()),
+                    permalink: comment_permalink(&discussion.url, comment.database_id),
+                    reply_count: reply_count(&comment.replies),
+                    replies,
+                }
+            })
+            .collect();
+
+        Self {
+            owner,
+            repo,
+            title: escape_markdown_text(&discussion.title),
+            number: discussion.number,
+            url: &discussion.url,
+            author_login: markdown_author_login(discussion.author.as_ref()),
+            author: format_author(discussion.author.as_ref(), options),
+            created_at: format_timestamp(&discussion.created_at, options),
+            edited_at: edited_timestamp(
+                &discussion.created_at,
+                discussion.last_edited_at.as_ref(),
+                options,
+            ),
+            category: discussion.category.as_ref().map(category_label),
+            status: status_label(discussion, options),
+            body: render_body(
+                source_body(&discussion.body, discussion.body_html.as_deref(), options),
+                &anchors,
+                options,
+            ),
+            poll: discussion
+                .poll
+                .is_some()
+                .then(|| format_poll(discussion.poll.as_ref())),
+            upvotes: discussion.upvote_count,
+            reactions: reaction_summary(discussion.reaction_groups.as_ref()),
+            comments,
+            discussion,
+        }
+    }
+}
+
+/// Handlebars registry for Markdown output
+///
+/// HTML escaping is disabled, since the output is Markdown and the values
+/// are already escaped where needed.
+fn registry() -> Handlebars<'static> {
+    let mut handlebars = Handlebars::new();
+    handlebars.register_escape_fn(handlebars::no_escape);
+    handlebars
+}
+
+/// Read a template file and check its syntax
+///
+/// Called before fetching so a broken template wastes no API calls.
+/// Returns `Err(Error::Template)` describing the syntax error.
+pub fn load_template(path: &str) -> Result<String> {
+    let template = std::fs::read_to_string(path)?;
+    registry()
+        .register_template_string("export", &template)
+        .map_err(|e| Error::Template(format!("{}: {}", path, e)))?;
+    Ok(template)
+}
+
+/// Render a discussion as Markdown through a Handlebars template
+///
+/// See `TemplateContext` for the values available to the template.
+/// Returns `Err(Error::Template)` if the template is invalid or fails to render.
+pub fn render_template(
+    template: &str,
+    discussion: &Discussion,
+    owner: &str,
+    repo: &str,
+    options: &OutputOptions,
+) -> Result<String> {
+    let context = TemplateContext::new(discussion, owner, repo, options);
+    registry()
+        .render_template(template, &context)
+        .map_err(|e| Error::Template(e.to_string()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::output::format_discussion;
+    use serde_json::json;
+
+    /// Discussion exercising every part of the default layout
+    fn sample_discussion() -> Discussion {
+        serde_json::from_value(json!({
+            "id": "D_1",
+            "title": "How to *export*?",
+            "number": 42,
+            "url": "https://github.com/owner/repo/discussions/42",
+            "createdAt": "2024-01-15T10:30:00Z",
+            "lastEditedAt": "2024-01-16T09:00:00Z",
+            "body": "Question body",
+            "author": {"login": "asker"},
+            "answer": {"id": "C_2"},
+            "upvoteCount": 3,
+            "reactionGroups": [
+                {"content": "THUMBS_UP", "reactors": {"totalCount": 2}},
+                {"content": "HEART", "reactors": {"totalCount": 0}}
+            ],
+            "category": {"name": "Q&A", "emoji": ":pray:"},
+            "closed": true,
+            "closedAt": "2024-02-01T00:00:00Z",
+            "locked": true,
+            "poll": {
+                "question": "Does it work?",
+                "totalVoteCount": 4,
+                "options": {"nodes": [
+                    {"option": "Yes", "totalVoteCount": 3},
+                    {"option": "No", "totalVoteCount": 1}
+                ]}
+            },
+            "comments": {
+                "totalCount": 2,
+                "nodes": [
+                    {
+                        "id": "C_1",
+                        "databaseId": 101,
+                        "author": null,
+                        "createdAt": "2024-01-15T11:00:00Z",
+                        "body": "First comment",
+                        "replies": {
+                            "nodes": [
+                                {
+                                    "id": "R_1",
+                                    "databaseId": 102,
+                                    "author": {"login": "asker"},
+                                    "createdAt": "2024-01-15T11:30:00Z",
+                                    "lastEditedAt": "2024-01-15T11:45:00Z",
+                                    "upvoteCount": 1,
+                                    "body": "A reply"
+                                }
+                            ],
+                            "pageInfo": {"hasNextPage": false, "endCursor": null}
+                        }
+                    },
+                    {
+                        "id": "C_2",
+                        "databaseId": 103,
+                        "author": {"login": "helper"},
+                        "createdAt": "2024-01-15T12:00:00Z",
+                        "body": "The answer",
+                        "reactionGroups": [
+                            {"content": "ROCKET", "reactors": {"totalCount": 4}}
+                        ],
+                        "replies": {
+                            "nodes": [],
+                            "pageInfo": {"hasNextPage": false, "endCursor": null}
+                        }
+                    }
+                ],
+                "pageInfo": {"hasNextPage": false, "endCursor": null}
+            }
+        }))
+        .unwrap()
+    }
+
+    #[test]
+    fn test_default_template_matches_built_in_layout() {
+        let discussion = sample_discussion();
+        let rendered = render_template(
+            DEFAULT_TEMPLATE,
+            &discussion,
+            "owner",
+            "repo",
+            &OutputOptions::default(),
+        )
+        .unwrap();
+        assert_eq!(rendered, format_discussion(&discussion, "owner", "repo"));
+
+        // Open discussion without category, reactions, or comments
+        let discussion = Discussion {
+            title: "Empty".to_string(),
+            url: "https://github.com/owner/repo/discussions/1".to_string(),
+            ..Default::default()
+        };
+        let rendered = render_template(
+            DEFAULT_TEMPLATE,
+            &discussion,
+            "owner",
+            "repo",
+            &OutputOptions::default(),
+        )
+        .unwrap();
+        assert_eq!(rendered, format_discussion(&discussion, "owner", "repo"));
+    }
+
+    #[test]
+    fn test_render_custom_template() {
+        let template = "{{title}} ({{owner}}/{{repo}}#{{number}}) by {{author}}\n\
+                        {{#each comments}}{{number}}. {{author}}: {{body}} <{{permalink}}>\n\
+                        {{#each replies}}  {{comment_number}}.{{number}} {{body}}\n{{/each}}{{/each}}\
+                        Category: {{discussion.category.name}}\n";
+        let rendered = render_template(
+            template,
+            &sample_discussion(),
+            "owner",
+            "repo",
+            &OutputOptions::default(),
+        )
+        .unwrap();
+        assert_eq!(
+            rendered,
+            "How to \\*export\\*? (owner/repo#42) by asker\n\
+             1. \\<deleted\\>: First comment <https://github.com/owner/repo/discussions/42#discussioncomment-101>\n\
+             \x20 1.1 A reply\n\
+             2. helper: The answer <https://github.com/owner/repo/discussions/42#discussioncomment-103>\n\
+             Category: Q&A\n"
+        );
+    }
+
+    #[test]
+    fn test_template_values_follow_output_options() {
+        let options = OutputOptions {
+            author_links: true,
+            timezone: Some(chrono_tz::Asia::Tokyo),
+            ..Default::default()
+        };
+        let rendered = render_template(
+            "{{author}} {{created_at}}",
+            &sample_discussion(),
+            "owner",
+            "repo",
+            &options,
+        )
+        .unwrap();
+        assert_eq!(
+            rendered,
+            "[asker](https://github.com/asker) 2024-01-15T19:30:00+09:00"
+        );
+    }
+
+    #[test]
+    fn test_invalid_template() {
+        let result = render_template(
+            "{{#each comments}}",
+            &sample_discussion(),
+            "owner",
+            "repo",
+            &OutputOptions::default(),
+        );
+        assert!(matches!(result, Err(Error::Template(_))));
+
+        let path = std::env::temp_dir().join("test_invalid_template.hbs");
+        std::fs::write(&path, "{{#if title}}").unwrap();
+        let result = load_template(path.to_str().unwrap());
+        assert!(
+            matches!(result, Err(Error::Template(msg)) if msg.contains("test_invalid_template.hbs"))
+        );
+
+        std::fs::write(&path, DEFAULT_TEMPLATE).unwrap();
+        assert_eq!(
+            load_template(path.to_str().unwrap()).unwrap(),
+            DEFAULT_TEMPLATE
+        );
+        std::fs::remove_file(&path).ok();
+    }
+}
@@ -0,0 +1,631 @@
+// Optional rewrites of body content
+//
+// The default export is verbatim (see output.rs). The functions here are
+// only applied when the user opts in, e.g. with `--local-links`,
+// `--link-mentions`, or `--strip-html-comments`. Code blocks are examples,
+// not content, so all rewrites skip fenced and indented code blocks (see
+// `CodeBlocks`).
+
+use std::collections::HashMap;
+
+/// Fragment GitHub uses to link to a discussion comment or reply
+const COMMENT_FRAGMENT: &str = "#discussioncomment-";
+
+/// Characters that end a URL found in body text
+fn is_url_terminator(c: char) -> bool {
+    c.is_whitespace() || matches!(c, ')' | ']' | '<' | '>' | '"' | '\'')
+}
+
+/// Trailing punctuation that belongs to the sentence, not the URL
+fn is_trailing_punctuation(c: char) -> bool {
+    matches!(c, '.' | ',' | ';' | ':' | '!' | '?')
+}
+
+/// Extract the comment database ID from a discussion comment URL
+///
+/// Matches `https://<host>/<owner>/<repo>/discussions/<n>#discussioncomment-<id>`
+/// and returns `<id>`, or `None` for any other URL.
+fn comment_id(url: &str) -> Option<i64> {
+    let (path, id) = url.split_once(COMMENT_FRAGMENT)?;
+    if !path.contains("/discussions/") || id.is_empty() || !id.bytes().all(|b| b.is_ascii_digit()) {
+        return None;
+    }
+    id.parse().ok()
+}
+
+/// Rewrite links to comments of the current export into local anchors
+///
+/// `anchors` maps comment/reply database IDs to the link target of the anchor
+/// emitted for them (e.g. `456 -> "#comment-1"`, or
+/// `456 -> "comment-1.md#comment-1"` across files). URLs ending in
+/// `#discussioncomment-<id>` whose ID is in the map are rewritten:
+/// - link destinations (`[text](url)`, `href="url"`) become `<target>`
+/// - autolinks (`<url>`) and bare URLs become `[url](<target>)`
+///
+/// All other text, including non-matching links, the text of inline links
+/// and links in code blocks, is left untouched.
+pub fn rewrite_comment_links(body: &str, anchors: &HashMap<i64, String>) -> String {
+    if anchors.is_empty() || !body.contains(COMMENT_FRAGMENT) {
+        return body.to_string();
+    }
+    map_text_lines(body, |line| rewrite_comment_links_in_line(line, anchors))
+}
+
+/// Rewrite the comment links of a single line
+fn rewrite_comment_links_in_line(line: &str, anchors: &HashMap<i64, String>) -> String {
+    let mut output = String::with_capacity(line.len());
+    let mut rest = line;
+
+    while let Some(start) = rest.find("http") {
+        if let Some(pos) = rest[..start].find('[') {
+            // Copy the text of an inline link verbatim, since links cannot
+            // nest; its destination is still rewritten
+            let end = link_text_end(rest, pos).unwrap_or(pos + 1);
+            output.push_str(&rest[..end]);
+            rest = &rest[end..];
+            continue;
+        }
+
+        let candidate = &rest[start..];
+        if !candidate.starts_with("https://") && !candidate.starts_with("http://") {
+            output.push_str(&rest[..start + 4]);
+            rest = &rest[start + 4..];
+            continue;
+        }
+
+        output.push_str(&rest[..start]);
+        let end = candidate.find(is_url_terminator).unwrap_or(candidate.len());
+        let url = candidate[..end].trim_end_matches(is_trailing_punctuation);
+        let after = &candidate[url.len()..];
+
+        match comment_id(url).and_then(|id| anchors.get(&id)) {
+            Some(anchor) if output.ends_with('<') && after.starts_with('>') => {
+                output.pop();
+                output.push_str(&format!("[{}]({})", url, anchor));
+                rest = &after[1..];
+                continue;
+            }
+            Some(anchor) if output.ends_with(['(', '"', '\'']) => output.push_str(anchor),
+            Some(anchor) => output.push_str(&format!("[{}]({})", url, anchor)),
+            None => output.push_str(url),
+        }
+        rest = after;
+    }
+
+    output.push_str(rest);
+    output
+}
+
+/// Longest GitHub username
+const MAX_USERNAME_LEN: usize = 39;
+
+/// Detect a code fence line (```` ``` ```` or `~~~`, indented at most 3 spaces)
+///
+/// Returns the fence character and the length of its run.
+fn code_fence(line: &str) -> Option<(char, usize)> {
+    let trimmed = line.trim_start_matches(' ');
+    if line.len() - trimmed.len() > 3 {
+        return None;
+    }
+    let fence = trimmed.chars().next().filter(|c| matches!(c, '`' | '~'))?;
+    let len = trimmed.chars().take_while(|&c| c == fence).count();
+    (len >= 3).then_some((fence, len))
+}
+
+/// Whether `line` closes a code block opened by a `len`-long run of `fence`
+fn closes_fence(line: &str, fence: char, len: usize) -> bool {
+    code_fence(line).is_some_and(|(c, n)| c == fence && n >= len)
+        && line.trim().trim_start_matches(fence).is_empty()
+}
+
+/// Whether `line` is indented enough to be part of an indented code block
+fn is_code_indented(line: &str) -> bool {
+    line.starts_with("    ") || line.starts_with('\t')
+}
+
+/// Tracks whether body lines belong to fenced or indented code blocks
+///
+/// An indented line only starts a code block after a blank line, as in
+/// CommonMark. Indented list continuations are treated as code too, which
+/// errs on the side of leaving text unchanged.
+struct CodeBlocks {
+    fence: Option<(char, usize)>,
+    in_indented_code: bool,
+    prev_blank: bool,
+}
+
+impl CodeBlocks {
+    fn new() -> Self {
+        CodeBlocks {
+            fence: None,
+            in_indented_code: false,
+            prev_blank: true,
+        }
+    }
+
+    /// Whether `line`, the next line of the body, is code (including fences)
+    fn is_code(&mut self, line: &str) -> bool {
+        let blank = line.trim().is_empty();
+        let code = match self.fence {
+            Some((c, len)) => {
+                if closes_fence(line, c, len) {
+                    self.fence = None;
+                }
+                true
+            }
+            None => {
+                if let Some(open) = code_fence(line) {
+                    self.fence = Some(open);
+                    self.in_indented_code = false;
+                    true
+                } else if blank {
+                    self.in_indented_code
+                } else if is_code_indented(line) && (self.prev_blank || self.in_indented_code) {
+                    self.in_indented_code = true;
+                    true
+                } else {
+                    self.in_indented_code = false;
+                    false
+                }
+            }
+        };
+        self.prev_blank = blank;
+        code
+    }
+}
+
+/// Apply `rewrite` to each line outside fenced and indented code blocks
+///
+/// Lines are passed with their trailing newline, if any; code block lines
+/// (including fences) are copied verbatim.
+fn map_text_lines(body: &str, mut rewrite: impl FnMut(&str) -> String) -> String {
+    let mut output = String::with_capacity(body.len());
+    let mut code = CodeBlocks::new();
+
+    for line in body.split_inclusive('\n') {
+        if code.is_code(line) {
+            output.push_str(line);
+        } else {
+            output.push_str(&rewrite(line));
+        }
+    }
+    output
+}
+
+/// Length of the GitHub username at the start of `text`, or 0 if there is none
+///
+/// Usernames are alphanumeric with single hyphens, and neither start nor end
+/// with a hyphen.
+fn username_len(text: &str) -> usize {
+    if !text.starts_with(|c: char| c.is_ascii_alphanumeric()) {
+        return 0;
+    }
+    let run = text
+        .find(|c: char| !(c.is_ascii_alphanumeric() || c == '-'))
+        .unwrap_or(text.len());
+    let len = text[..run].trim_end_matches('-').len();
+    if len > MAX_USERNAME_LEN || text[..len].contains("--") {
+        return 0;
+    }
+    len
+}
+
+/// Characters that, right before `@`, show it is not a mention
+///
+/// Covers email addresses (`user@example.com`), paths and URLs (`/@user`),
+/// and existing link text (`[@user](...)`).
+fn is_mention_blocker(c: char) -> bool {
+    c.is_alphanumeric() || matches!(c, '_' | '-' | '.' | '+' | '/' | '[' | '`' | '@')
+}
+
+/// Find a run of exactly `len` backticks in `text`, returning its start
+fn find_backtick_run(text: &str, len: usize) -> Option<usize> {
+    let mut offset = 0;
+    while let Some(start) = text[offset..].find('`') {
+        let start = offset + start;
+        let run = text[start..].chars().take_while(|&c| c == '`').count();
+        if run == len {
+            return Some(start);
+        }
+        offset = start + run;
+    }
+    None
+}
+
+/// End of the code span opened by the backtick run at `pos` in `text`
+///
+/// An unmatched run is literal text, so only the run itself is spanned.
+fn code_span_end(text: &str, pos: usize) -> usize {
+    let run = text[pos..].chars().take_while(|&c| c == '`').count();
+    match find_backtick_run(&text[pos + run..], run) {
+        Some(close) => pos + run + close + run,
+        None => pos + run,
+    }
+}
+
+/// End of the text `[text]` of the inline link opened by the `[` at `pos`
+/// in `text`, or `None` if the brackets are not followed by a destination
+fn link_text_end(text: &str, pos: usize) -> Option<usize> {
+    let mut depth = 0;
+    for (i, c) in text[pos..].char_indices() {
+        match c {
+            '[' => depth += 1,
+            ']' => {
+                depth -= 1;
+                if depth == 0 {
+                    let after = pos + i + 1;
+                    return text[after..].starts_with('(').then_some(after);
+                }
+            }
+            _ => {}
+        }
+    }
+    None
+}
+
+/// End of the inline link `[text](destination)` opened by the `[` at `pos`
+/// in `text`, or `None` if the brackets are not followed by a destination
+fn inline_link_end(text: &str, pos: usize) -> Option<usize> {
+    let after = link_text_end(text, pos)?;
+    text[after..].find(')').map(|close| after + close + 1)
+}
+
+/// Link the `@username` mentions of a single line outside code spans and
+/// inline links
+fn link_mentions_in_line(line: &str) -> String {
+    let mut output = String::with_capacity(line.len());
+    let mut rest = line;
+
+    while let Some(pos) = rest.find(['`', '[', '@']) {
+        if rest[pos..].starts_with('`') {
+            // Copy a code span verbatim
+            let end = code_span_end(rest, pos);
+            output.push_str(&rest[..end]);
+            rest = &rest[end..];
+            continue;
+        }
+        if rest[pos..].starts_with('[') {
+            // Copy an inline link verbatim, since links cannot nest
+            let end = inline_link_end(rest, pos).unwrap_or(pos + 1);
+            output.push_str(&rest[..end]);
+            rest = &rest[end..];
+            continue;
+        }
+
+        let prev = rest[..pos]
+            .chars()
+            .next_back()
+            .or(output.chars().next_back());
+        output.push_str(&rest[..pos]);
+        let candidate = &rest[pos + 1..];
+        let len = username_len(candidate);
+        // `@org/team` mentions and names running into `_` are left alone
+        let is_mention = len > 0
+            && !prev.is_some_and(is_mention_blocker)
+            && !candidate[len..].starts_with(['/', '_', '@']);
+        if is_mention {
+            let name = &candidate[..len];
+            output.push_str(&format!("[@{}](https://github.com/{})", name, name));
+            rest = &candidate[len..];
+        } else {
+            output.push('@');
+            rest = candidate;
+        }
+    }
+
+    output.push_str(rest);
+    output
+}
+
+/// Rewrite `@username` mentions into links to the user's GitHub profile
+///
+/// `@octocat` becomes `[@octocat](https://github.com/octocat)`. Mentions in
+/// inline code spans and code blocks, email addresses, team mentions
+/// (`@org/team`), and mentions inside the text of an inline link are left
+/// untouched.
+pub fn link_mentions(body: &str) -> String {
+    if !body.contains('@') {
+        return body.to_string();
+    }
+    map_text_lines(body, link_mentions_in_line)
+}
+
+/// Remove the HTML comments of a single line outside code spans
+///
+/// `in_comment` carries an unterminated comment over to the next line.
+fn strip_html_comments_in_line(line: &str, in_comment: &mut bool) -> String {
+    let mut output = String::with_capacity(line.len());
+    let mut rest = line;
+
+    loop {
+        if *in_comment {
+            match rest.find("-->") {
+                Some(end) => {
+                    *in_comment = false;
+                    rest = &rest[end + 3..];
+                }
+                None => return output,
+            }
+        }
+        let Some(pos) = rest.find(['`', '<']) else {
+            break;
+        };
+        if rest[pos..].starts_with('`') {
+            let end = code_span_end(rest, pos);
+            output.push_str(&rest[..end]);
+            rest = &rest[end..];
+        } else if rest[pos..].starts_with("<!--") {
+            output.push_str(&rest[..pos]);
+            *in_comment = true;
+            rest = &rest[pos + 4..];
+        } else {
+            output.push_str(&rest[..=pos]);
+            rest = &rest[pos + 1..];
+        }
+    }
+
+    output.push_str(rest);
+    output
+}
+
+/// Remove `<!-- ... -->` HTML comments from a body
+///
+/// Comments may span several lines; an unterminated `<!--` runs to the end
+/// of the body, as in HTML. Lines that held nothing but comments are dropped
+/// rather than left blank. Comments in code spans and code blocks are left
+/// untouched.
+pub fn strip_html_comments(body: &str) -> String {
+    if !body.contains("<!--") {
+        return body.to_string();
+    }
+
+    let mut output = String::with_capacity(body.len());
+    let mut code = CodeBlocks::new();
+    let mut in_comment = false;
+    for line in body.split_inclusive('\n') {
+        // Code blocks cannot start inside a comment
+        if !in_comment && code.is_code(line) {
+            output.push_str(line);
+            continue;
+        }
+        let stripped = strip_html_comments_in_line(line, &mut in_comment);
+        if stripped.len() == line.len() || !stripped.trim().is_empty() {
+            output.push_str(&stripped);
+        }
+    }
+    output
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const COMMENT_URL: &str = "https://github.com/owner/repo/discussions/123#discussioncomment-456";
+
+    fn anchors() -> HashMap<i64, String> {
+        HashMap::from([
+            (456, "#comment-1".to_string()),
+            (789, "#reply-1-1".to_string()),
+        ])
+    }
+
+    #[test]
+    fn test_comment_id() {
+        assert_eq!(comment_id(COMMENT_URL), Some(456));
+        assert_eq!(
+            comment_id("https://github.com/owner/repo/issues/1#discussioncomment-456"),
+            None
+        );
+        assert_eq!(
+            comment_id("https://github.com/owner/repo/discussions/123#discussioncomment-"),
+            None
+        );
+        assert_eq!(comment_id("https://example.com/"), None);
+    }
+
+    #[test]
+    fn test_rewrite_markdown_link() {
+        let body = format!("See [this comment]({}) for details.", COMMENT_URL);
+        assert_eq!(
+            rewrite_comment_links(&body, &anchors()),
+            "See [this comment](#comment-1) for details."
+        );
+    }
+
+    #[test]
+    fn test_rewrite_link_with_url_text() {
+        let body = format!(
+            "See [{}]({})\n[x] {}",
+            COMMENT_URL, COMMENT_URL, COMMENT_URL
+        );
+        assert_eq!(
+            rewrite_comment_links(&body, &anchors()),
+            format!(
+                "See [{}](#comment-1)\n[x] [{}](#comment-1)",
+                COMMENT_URL, COMMENT_URL
+            )
+        );
+    }
+
+    #[test]
+    fn test_rewrite_html_href() {
+        let body = format!(
+            "<a href=\"{}\">reply</a>",
+            COMMENT_URL.replace("456", "789")
+        );
+        assert_eq!(
+            rewrite_comment_links(&body, &anchors()),
+            "<a href=\"#reply-1-1\">reply</a>"
+        );
+    }
+
+    #[test]
+    fn test_rewrite_bare_and_autolinked_urls() {
+        let body = format!("As said in {}.\nAlso <{}>", COMMENT_URL, COMMENT_URL);
+        assert_eq!(
+            rewrite_comment_links(&body, &anchors()),
+            format!(
+                "As said in [{}](#comment-1).\nAlso [{}](#comment-1)",
+                COMMENT_URL, COMMENT_URL
+            )
+        );
+    }
+
+    #[test]
+    fn test_unrelated_links_untouched() {
+        let body = "Docs: [guide](https://example.com/guide) and https://httpbin.org/\n\
+                    Other: https://github.com/owner/repo/discussions/9#discussioncomment-999\n";
+        assert_eq!(rewrite_comment_links(body, &anchors()), body);
+    }
+
+    #[test]
+    fn test_no_anchors_is_verbatim() {
+        let body = format!("[link]({})", COMMENT_URL);
+        assert_eq!(rewrite_comment_links(&body, &HashMap::new()), body);
+    }
+
+    #[test]
+    fn test_link_mentions_in_prose() {
+        assert_eq!(
+            link_mentions("Thanks @octocat, and cc @mona-lisa.\n"),
+            "Thanks [@octocat](https://github.com/octocat), and cc [@mona-lisa](https://github.com/mona-lisa).\n"
+        );
+        assert_eq!(
+            link_mentions("@octocat"),
+            "[@octocat](https://github.com/octocat)"
+        );
+    }
+
+    #[test]
+    fn test_link_mentions_skips_code_spans() {
+        let body = "Run `npm i @scope/pkg` or ``use @octocat`` then ask @mona";
+        assert_eq!(
+            link_mentions(body),
+            "Run `npm i @scope/pkg` or ``use @octocat`` then ask [@mona](https://github.com/mona)"
+        );
+    }
+
+    #[test]
+    fn test_link_mentions_skips_email_addresses() {
+        let body = "Mail octocat@github.com or first.last+tag@example.co.jp";
+        assert_eq!(link_mentions(body), body);
+    }
+
+    #[test]
+    fn test_link_mentions_skips_fenced_code_blocks() {
+        let body =
+            "Hi @mona\n```python\n@decorator\ndef f(): pass\n```\n~~~~\n@x\n~~~\n~~~~\nBye @mona\n";
+        assert_eq!(
+            link_mentions(body),
+            "Hi [@mona](https://github.com/mona)\n```python\n@decorator\ndef f(): pass\n```\n~~~~\n@x\n~~~\n~~~~\nBye [@mona](https://github.com/mona)\n"
+        );
+    }
+
+    #[test]
+    fn test_link_mentions_leaves_non_mentions_alone() {
+        let body = "[@octocat](https://github.com/octocat) @org/team https://x.com/@user @ alone @-x @a--b";
+        assert_eq!(link_mentions(body), body);
+        // Mentions inside link text would nest links
+        let body = "[ping @octocat](https://example.com) [see [@hubot]](https://example.com/a)";
+        assert_eq!(link_mentions(body), body);
+        // Brackets without a destination are plain text
+        assert_eq!(
+            link_mentions("[note] @octocat [x @hubot]"),
+            "[note] [@octocat](https://github.com/octocat) [x [@hubot](https://github.com/hubot)]"
+        );
+    }
+
+    #[test]
+    fn test_map_text_lines_skips_code_blocks() {
+        let body = "text\n\
+                    ```markdown\n\
+                    ![example](https://example.com/a.png)\n\
+                    ````\n\
+                    text\n\
+                    \n\
+                    \x20   indented code\n\
+                    \n\
+                    \tstill code\n\
+                    text\n\
+                    \x20   lazy continuation\n";
+        let mapped = map_text_lines(body, |line| line.to_uppercase());
+        assert_eq!(
+            mapped,
+            "TEXT\n\
+             ```markdown\n\
+             ![example](https://example.com/a.png)\n\
+             ````\n\
+             TEXT\n\
+             \n\
+             \x20   indented code\n\
+             \n\
+             \tstill code\n\
+             TEXT\n\
+             \x20   LAZY CONTINUATION\n"
+        );
+    }
+
+    #[test]
+    fn test_unclosed_fence_runs_to_end() {
+        let body = "~~~\n@mona\n```\n@mona";
+        assert_eq!(link_mentions(body), body);
+    }
+
+    #[test]
+    fn test_comment_links_in_code_blocks_untouched() {
+        let body = format!(
+            "See {}\n\n```\n[example]({})\n```\n\n    {}\n",
+            COMMENT_URL, COMMENT_URL, COMMENT_URL
+        );
+        assert_eq!(
+            rewrite_comment_links(&body, &anchors()),
+            format!(
+                "See [{}](#comment-1)\n\n```\n[example]({})\n```\n\n    {}\n",
+                COMMENT_URL, COMMENT_URL, COMMENT_URL
+            )
+        );
+    }
+
+    #[test]
+    fn test_link_mentions_skips_indented_code_blocks() {
+        let body = "Example:\n\n    @decorator\n    def f(): pass\n\nThanks @mona\n";
+        assert_eq!(
+            link_mentions(body),
+            "Example:\n\n    @decorator\n    def f(): pass\n\nThanks [@mona](https://github.com/mona)\n"
+        );
+    }
+
+    #[test]
+    fn test_strip_html_comments_in_prose() {
+        assert_eq!(
+            strip_html_comments("Before <!-- note --> after\n"),
+            "Before  after\n"
+        );
+        assert_eq!(
+            strip_html_comments("Intro\n<!-- Describe the bug -->\n\nDetails\n"),
+            "Intro\n\nDetails\n"
+        );
+        assert_eq!(
+            strip_html_comments("Text <!-- one\ntwo\nthree --> more\nEnd"),
+            "Text  more\nEnd"
+        );
+        assert_eq!(strip_html_comments("<!--a--><!--b-->\nKept\n"), "Kept\n");
+        assert_eq!(
+            strip_html_comments("Kept\n<!-- unterminated\nGone\n"),
+            "Kept\n"
+        );
+        // Blank lines that were not comments are kept
+        assert_eq!(strip_html_comments("A\n\n<!-- c -->B\n"), "A\n\nB\n");
+    }
+
+    #[test]
+    fn test_strip_html_comments_skips_code() {
+        let body =
+            "```html\n<!-- example -->\n```\n\n    <!-- indented -->\n\nUse `<!-- x -->` here\n";
+        assert_eq!(strip_html_comments(body), body);
+        assert_eq!(
+            strip_html_comments("<!-- gone -->\n~~~\n<!-- kept -->\n~~~\n"),
+            "~~~\n<!-- kept -->\n~~~\n"
+        );
+    }
+}
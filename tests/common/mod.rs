@@ -0,0 +1,83 @@
+// Canned GraphQL responses shared by the unit and integration tests
+//
+// The discussion has two comments: `C_1` by `commenter`, whose reply `R_1`
+// is on a second page fetched with the replies query, and `C_2` by a deleted
+// account. Comments are returned newest first to exercise sorting.
+
+use serde_json::json;
+
+/// Canned response for a GraphQL request body, keyed on the query text
+///
+/// Returns `None` for queries other than the discussion and replies queries.
+pub fn canned_response(body: &str) -> Option<String> {
+    let response = if body.contains("repository(owner") {
+        json!({
+            "data": {
+                "repository": {
+                    "discussion": {
+                        "id": "D_1",
+                        "title": "Canned discussion",
+                        "number": 7,
+                        "url": "https://github.com/owner/repo/discussions/7",
+                        "createdAt": "2024-01-15T10:30:00Z",
+                        "body": "Original body",
+                        "author": {"login": "poster"},
+                        "comments": {
+                            "totalCount": 2,
+                            "nodes": [
+                                {
+                                    "id": "C_2",
+                                    "databaseId": 3,
+                                    "author": null,
+                                    "createdAt": "2024-01-15T13:00:00Z",
+                                    "body": "Second comment",
+                                    "replies": {
+                                        "totalCount": 0,
+                                        "nodes": [],
+                                        "pageInfo": {"hasNextPage": false, "endCursor": null}
+                                    }
+                                },
+                                {
+                                    "id": "C_1",
+                                    "databaseId": 1,
+                                    "author": {"login": "commenter"},
+                                    "createdAt": "2024-01-15T11:00:00Z",
+                                    "body": "First comment",
+                                    "replies": {
+                                        "totalCount": 1,
+                                        "nodes": [],
+                                        "pageInfo": {"hasNextPage": true, "endCursor": "r1"}
+                                    }
+                                }
+                            ],
+                            "pageInfo": {"hasNextPage": false, "endCursor": null}
+                        }
+                    }
+                }
+            }
+        })
+    } else if body.contains("on DiscussionComment") {
+        json!({
+            "data": {
+                "node": {
+                    "replies": {
+                        "totalCount": 1,
+                        "nodes": [
+                            {
+                                "id": "R_1",
+                                "databaseId": 2,
+                                "author": {"login": "replier"},
+                                "createdAt": "2024-01-15T12:00:00Z",
+                                "body": "First reply"
+                            }
+                        ],
+                        "pageInfo": {"hasNextPage": false, "endCursor": null}
+                    }
+                }
+            }
+        })
+    } else {
+        return None;
+    };
+    Some(response.to_string())
+}
@@ -0,0 +1,259 @@
+// Integration tests for the library export pipeline
+//
+// Drives `export_discussion_with_client` end to end with a canned HttpClient,
+// so no network access or GitHub token is needed.
+
+use clap::Parser;
+use gh_discussion_export::api::{ExportOptions, export_discussion_with_client};
+use gh_discussion_export::cli::{CliArgs, OutputFormat};
+use gh_discussion_export::client::{GitHubClient, HttpClient};
+use gh_discussion_export::error::{Error, Result};
+use gh_discussion_export::fetch::{FetchOptions, fetch_discussion_with_options};
+use gh_discussion_export::output::{
+    OutputOptions, exported_comment_ids, format_appended_comments, format_discussion_with_options,
+};
+use serde_json::json;
+
+mod common;
+
+/// HttpClient that answers each query type with the shared canned response
+struct CannedHttpClient;
+
+impl HttpClient for CannedHttpClient {
+    fn post(&self, _url: &str, body: &str) -> Result<String> {
+        common::canned_response(body)
+            .ok_or_else(|| Error::Http(format!("Unexpected request: {}", body)))
+    }
+}
+
+#[test]
+fn test_export_discussion_markdown() {
+    let client = GitHubClient::new(Box::new(CannedHttpClient));
+    let markdown =
+        export_discussion_with_client(&client, "owner", "repo", 7, &ExportOptions::default())
+            .unwrap();
+
+    assert!(markdown.starts_with("# Canned discussion\n\nDiscussion: owner/repo#7\n"));
+    assert!(markdown.contains(
+        "## Original Post\n\n_author: poster (2024-01-15T10:30:00Z)_\n\nOriginal body\n"
+    ));
+    assert!(markdown.contains(
+        "### Comment 1\n\n_author: commenter (2024-01-15T11:00:00Z)_\n\nFirst comment\n"
+    ));
+    assert!(
+        markdown.contains(
+            "#### Reply 1.1\n\n_author: replier (2024-01-15T12:00:00Z)_\n\nFirst reply\n"
+        )
+    );
+}
+
+#[test]
+fn test_export_discussion_json() {
+    let client = GitHubClient::new(Box::new(CannedHttpClient));
+    let options = ExportOptions {
+        format: OutputFormat::Json,
+        ..Default::default()
+    };
+    let output = export_discussion_with_client(&client, "owner", "repo", 7, &options).unwrap();
+
+    let value: serde_json::Value = serde_json::from_str(&output).unwrap();
+    assert_eq!(value["title"], "Canned discussion");
+    assert_eq!(value["comments"]["nodes"][0]["body"], "First comment");
+    assert_eq!(
+        value["comments"]["nodes"][0]["replies"]["nodes"][0]["body"],
+        "First reply"
+    );
+}
+
+#[test]
+fn test_export_discussion_ndjson() {
+    let client = GitHubClient::new(Box::new(CannedHttpClient));
+    let options = ExportOptions {
+        format: OutputFormat::Ndjson,
+        ..Default::default()
+    };
+    let output = export_discussion_with_client(&client, "owner", "repo", 7, &options).unwrap();
+
+    let lines: Vec<serde_json::Value> = output
+        .lines()
+        .map(|line| serde_json::from_str(line).unwrap())
+        .collect();
+    assert_eq!(lines.len(), 4);
+    assert_eq!(lines[0]["type"], "post");
+    assert_eq!(lines[0]["body"], "Original body");
+    assert_eq!(lines[1]["type"], "comment");
+    assert_eq!(lines[1]["author"], "commenter");
+    assert_eq!(lines[2]["type"], "reply");
+    assert_eq!(lines[2]["parent_id"], "C_1");
+    assert_eq!(lines[2]["databaseId"], 2);
+    assert_eq!(lines[3]["type"], "comment");
+    assert_eq!(lines[3]["id"], "C_2");
+}
+
+/// HttpClient serving five comments over three pages (two inline with the
+/// discussion, two after cursor `p1`, one after `p2`); the page after
+/// `fail_after` fails with an HTTP error
+struct PagedHttpClient {
+    fail_after: Option<&'static str>,
+}
+
+impl PagedHttpClient {
+    fn comments_page(ids: &[i64], end_cursor: Option<&str>) -> serde_json::Value {
+        let nodes: Vec<_> = ids
+            .iter()
+            .map(|id| {
+                json!({
+                    "id": format!("C_{}", id),
+                    "databaseId": id,
+                    "author": {"login": "commenter"},
+                    "createdAt": format!("2024-01-15T11:0{}:00Z", id),
+                    "body": format!("Comment {}", id),
+                    "replies": {
+                        "nodes": [],
+                        "pageInfo": {"hasNextPage": false, "endCursor": null}
+                    }
+                })
+            })
+            .collect();
+        json!({
+            "totalCount": 5,
+            "nodes": nodes,
+            "pageInfo": {"hasNextPage": end_cursor.is_some(), "endCursor": end_cursor}
+        })
+    }
+}
+
+impl HttpClient for PagedHttpClient {
+    fn post(&self, _url: &str, body: &str) -> Result<String> {
+        let request: serde_json::Value = serde_json::from_str(body).unwrap();
+        let response = if body.contains("repository(owner") {
+            json!({
+                "data": {
+                    "repository": {
+                        "discussion": {
+                            "id": "D_1",
+                            "title": "Paged export",
+                            "number": 7,
+                            "url": "https://github.com/owner/repo/discussions/7",
+                            "createdAt": "2024-01-15T10:30:00Z",
+                            "body": "Original body",
+                            "author": {"login": "poster"},
+                            "comments": Self::comments_page(&[1, 2], Some("p1"))
+                        }
+                    }
+                }
+            })
+        } else {
+            let after = request["variables"]["after"].as_str();
+            if after.is_some() && after == self.fail_after {
+                return Err(Error::Http("connection reset".to_string()));
+            }
+            let comments = match after {
+                Some("p1") => Self::comments_page(&[3, 4], Some("p2")),
+                Some("p2") => Self::comments_page(&[5], None),
+                other => return Err(Error::Http(format!("Unexpected cursor: {:?}", other))),
+            };
+            json!({"data": {"node": {"comments": comments}}})
+        };
+        Ok(response.to_string())
+    }
+}
+
+#[test]
+fn test_resume_failed_append_export() {
+    let options = OutputOptions {
+        db_id_markers: true,
+        ..Default::default()
+    };
+
+    // The page after `p2` fails; the comments fetched before it are written
+    let client = GitHubClient::new(Box::new(PagedHttpClient {
+        fail_after: Some("p2"),
+    }));
+    let (partial, cursor) = match fetch_discussion_with_options(
+        &client,
+        "owner",
+        "repo",
+        7,
+        &FetchOptions::default(),
+    ) {
+        Err(Error::PaginationFailed {
+            comment_id: None,
+            after: Some(cursor),
+            partial: Some(partial),
+            ..
+        }) => (partial, cursor),
+        other => panic!("Expected PaginationFailed, got {:?}", other.map(|d| d.id)),
+    };
+    let earlier = format_discussion_with_options(&partial, "owner", "repo", &options);
+    assert_eq!(exported_comment_ids(&earlier), [1, 2, 3, 4]);
+
+    // Resuming after the printed cursor appends the rest
+    let client = GitHubClient::new(Box::new(PagedHttpClient { fail_after: None }));
+    let fetch_options = FetchOptions {
+        after_comment_cursor: Some(cursor),
+        ..Default::default()
+    };
+    let resumed =
+        fetch_discussion_with_options(&client, "owner", "repo", 7, &fetch_options).unwrap();
+    let appended = format_appended_comments(&resumed, &earlier, &options).unwrap();
+    let combined = format!("{}{}", earlier, appended.markdown);
+
+    assert_eq!(exported_comment_ids(&combined), [1, 2, 3, 4, 5]);
+    for n in 1..=5 {
+        assert!(combined.contains(&format!(
+            "### Comment {}\n\n_author: commenter (2024-01-15T11:0{}:00Z)_\n\n<!-- comment-db-id: {} -->\nComment {}\n",
+            n, n, n, n
+        )));
+    }
+    assert_eq!(combined.matches("### Comment").count(), 5);
+}
+
+#[test]
+fn test_resume_failed_plain_export() {
+    // A plain Markdown file export, without --append, is still resumable
+    let args = CliArgs::try_parse_from(["gh-discussion-export", "7", "-o", "out.md"]).unwrap();
+    assert!(args.resumable());
+
+    let client = GitHubClient::new(Box::new(PagedHttpClient {
+        fail_after: Some("p1"),
+    }));
+    let (partial, cursor) =
+        match fetch_discussion_with_options(&client, "owner", "repo", 7, &args.fetch_options()) {
+            Err(Error::PaginationFailed {
+                comment_id: None,
+                after: Some(cursor),
+                partial: Some(partial),
+                ..
+            }) => (partial, cursor),
+            other => panic!("Expected PaginationFailed, got {:?}", other.map(|d| d.id)),
+        };
+    // The partial export carries db-id markers, as the main binary forces them
+    let options = OutputOptions {
+        db_id_markers: true,
+        ..args.output_options()
+    };
+    let earlier = format_discussion_with_options(&partial, "owner", "repo", &options);
+    assert_eq!(exported_comment_ids(&earlier), [1, 2]);
+
+    // The rerun suggested by the resume hint appends the rest
+    let rerun = CliArgs::try_parse_from([
+        "gh-discussion-export",
+        "7",
+        "-o",
+        "out.md",
+        "--append",
+        "--after-comment-cursor",
+        &cursor,
+    ])
+    .unwrap();
+    rerun.validate().unwrap();
+    let client = GitHubClient::new(Box::new(PagedHttpClient { fail_after: None }));
+    let resumed =
+        fetch_discussion_with_options(&client, "owner", "repo", 7, &rerun.fetch_options()).unwrap();
+    let appended = format_appended_comments(&resumed, &earlier, &rerun.output_options()).unwrap();
+    let combined = format!("{}{}", earlier, appended.markdown);
+
+    assert_eq!(exported_comment_ids(&combined), [1, 2, 3, 4, 5]);
+    assert_eq!(combined.matches("### Comment").count(), 5);
+}